@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type AuditFilter = { action?: string, target?: string, 
+/**
+ * RFC 3339 timestamp; only records at or after it are returned
+ */
+since?: string, };
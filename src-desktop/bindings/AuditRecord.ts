@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type AuditRecord = { timestamp: string, actor: string, action: string, target: string, 
+/**
+ * SHA-256 of the JSON-encoded command parameters
+ */
+params_hash: string, success: boolean, error: string | null, 
+/**
+ * HMAC over the previous record's mac and this record, when chaining is enabled
+ */
+mac?: string, };
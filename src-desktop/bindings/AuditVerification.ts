@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type AuditVerification = { chained: boolean, records: number, 
+/**
+ * 1-based position (oldest first) of the first line that does not parse or whose mac does not match
+ */
+first_invalid: number | null, 
+/**
+ * Records that failed to be written since startup
+ */
+write_errors: number, };
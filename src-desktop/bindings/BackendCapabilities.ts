@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CapabilitySource } from "./CapabilitySource";
+
+export type BackendCapabilities = { streaming: boolean, rerank: boolean, hybrid: boolean, ingest_async: boolean, source: CapabilitySource, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where the webview sends its own backend requests, and with which token
+ */
+export type BackendConnection = { url: string, auth_token: string | null, profile: string | null, };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type BackendStatus = { 
+/**
+ * A backend process was started by the app; no process is bundled yet
+ */
+running: boolean, 
+/**
+ * The background health check is polling the endpoint
+ */
+monitoring: boolean, healthy: boolean, port: number, last_check: string, error: string | null, profile: string | null, };
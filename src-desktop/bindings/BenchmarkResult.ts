@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Measured generation throughput for a model
+ */
+export type BenchmarkResult = { model: string, prompt_tokens: number, generated_tokens: number, prompt_tokens_per_sec: number | null, tokens_per_sec: number | null, expected_tokens_per_sec: number | null, 
+/**
+ * Measured vs expected generation rate, in percent (negative is slower)
+ */
+deviation_pct: number | null, 
+/**
+ * True when generation is more than `BENCHMARK_TOLERANCE_PCT` slower than expected
+ */
+below_expected: boolean, };
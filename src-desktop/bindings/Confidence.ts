@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Where the capability data came from, strongest first
+ */
+export type Confidence = "Reported" | "Curated" | "Inferred";
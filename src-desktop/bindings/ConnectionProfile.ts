@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BackendReplica } from "./BackendReplica";
+
+export type ConnectionProfile = { name: string, backend_url: string, auth_token?: string, accept_invalid_certs: boolean, default_collection?: string, 
+/**
+ * Additional backend instances; `backend_url` has priority 0 and lower values are preferred
+ */
+replicas: Array<BackendReplica>, 
+/**
+ * Stay on the current replica while it is healthy instead of failing back to a preferred one
+ */
+sticky: boolean, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GpuUsage } from "./GpuUsage";
+
+export type ContentionReport = { gpus: Array<GpuUsage>, 
+/**
+ * True when more than one app component runs on the same GPU
+ */
+contended: boolean, recommendation: string | null, };
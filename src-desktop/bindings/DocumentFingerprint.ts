@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type DocumentFingerprint = { 
+/**
+ * Hex SHA-256 of the (normalized) content
+ */
+fingerprint: string, 
+/**
+ * Whether the content was text and had its whitespace normalized
+ */
+normalized: boolean, size_bytes: number, };
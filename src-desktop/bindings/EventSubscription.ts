@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A topic a window wants to receive
+ */
+export type EventSubscription = { 
+/**
+ * Event name, or a prefix ending in `*` (e.g. "ollama-*"); "*" matches every event
+ */
+topic: string, 
+/**
+ * Deliver at most this many events per second, coalescing to the latest payload
+ */
+max_per_sec?: number, };
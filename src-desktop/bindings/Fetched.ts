@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A snapshot field that either loaded or failed independently of the others
+ */
+export type Fetched<T> = { "status": "ok", value: T, } | { "status": "failed", error: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A cited source, as returned with the backend's answer
+ */
+export type FollowupSource = { file_name: string, excerpt: string, };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FollowupMode } from "./FollowupMode";
+
+export type FollowupSuggestions = { questions: Array<string>, 
+/**
+ * Mode that actually produced the questions
+ */
+mode: FollowupMode, };
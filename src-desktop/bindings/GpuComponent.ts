@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * App component a GPU process belongs to
+ */
+export type GpuComponent = "ollama" | "backend" | "desktop";
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GpuComponent } from "./GpuComponent";
+
+/**
+ * A compute process running on a GPU
+ */
+export type GpuProcess = { gpu_uuid: string, pid: number, process_name: string, used_mb: number, component: GpuComponent | null, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { GpuProcess } from "./GpuProcess";
+
+/**
+ * Processes sharing one GPU
+ */
+export type GpuUsage = { uuid: string, name: string, total_mb: number, free_mb: number, processes: Array<GpuProcess>, };
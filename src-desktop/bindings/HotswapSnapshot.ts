@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Fetched } from "./Fetched";
+import type { ModelInfo } from "./ModelInfo";
+
+/**
+ * Everything the hotswap UI needs to render, gathered in one call
+ */
+export type HotswapSnapshot = { models: Fetched<Array<ModelInfo>>, default_model_id: string | null, model_list_version: string | null, gpu_free_vram_mb: Fetched<number>, 
+/**
+ * Model id -> whether its minimum VRAM fits in free GPU memory
+ */
+will_fit: { [key in string]?: boolean }, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Why a latency measurement did not complete
+ */
+export type LatencyError = { "kind": "Unreachable", message: string, } | { "kind": "LoadFailed", model: string, message: string, } | { "kind": "Interrupted", message: string, };
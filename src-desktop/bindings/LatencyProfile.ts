@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Cold vs warm first-token latency for a model
+ */
+export type LatencyProfile = { model: string, cold_ttft_ms: number, warm_ttft_ms: number, tokens_per_sec: number | null, };
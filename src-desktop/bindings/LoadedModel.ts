@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A model currently loaded into memory, as reported by /api/ps
+ */
+export type LoadedModel = { name: string, size: number, size_vram: number, expires_at: string | null, };
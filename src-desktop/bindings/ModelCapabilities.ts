@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Confidence } from "./Confidence";
+
+export type ModelCapabilities = { model_id: string, max_context: number, supports_json_mode: boolean, supports_tools: boolean, supports_vision: boolean, reasoning_model: boolean, confidence: Confidence, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Model entry from the backend's /api/models/ registry
+ */
+export type ModelInfo = { id: string, name: string, backend: string, parameters: string, size: string, speed_rating: number, quality_rating: number, min_vram_gb: number, recommended_vram_gb: number, description: string, use_cases: Array<string>, available: boolean, default: boolean, };
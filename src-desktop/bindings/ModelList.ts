@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ModelInfo } from "./ModelInfo";
+
+export type ModelList = { models: Array<ModelInfo>, count: number, default_model_id: string, };
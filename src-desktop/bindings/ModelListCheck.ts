@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Result of comparing the backend's model list with the last one seen
+ */
+export type ModelListCheck = { changed: boolean, version: string, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SwitchFailure } from "./SwitchFailure";
+
+/**
+ * Result of a model switch, including any fallback that happened
+ */
+export type ModelSwitchResponse = { requested_model_id: string, model_id: string, model_name: string, message: string, requires_restart: boolean, fell_back: boolean, failures: Array<SwitchFailure>, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ModelCapabilities } from "./ModelCapabilities";
+
+/**
+ * "model-switched": the backend finished switching models
+ */
+export type ModelSwitched = { model_id: string, model_name: string, fell_back: boolean, capabilities: ModelCapabilities, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * "models-changed": the backend's model list differs from the one last seen
+ */
+export type ModelsChanged = { version: string, 
+/**
+ * Previous version, if it came from the same backend
+ */
+previous: string | null, count: number, };
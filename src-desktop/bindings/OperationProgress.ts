@@ -0,0 +1,28 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { OperationKind } from "./OperationKind";
+import type { OperationStatus } from "./OperationStatus";
+
+/**
+ * "operation-progress": one envelope for every long-running operation
+ */
+export type OperationProgress = { id: string, kind: OperationKind, 
+/**
+ * What the operation acts on, e.g. the model being pulled
+ */
+label: string, 
+/**
+ * What the operation is doing now, e.g. "downloading" or "reindex"
+ */
+phase: string, current: number | null, total: number | null, 
+/**
+ * Unit of `current` and `total`, e.g. "bytes" or "stages"
+ */
+unit: string | null, percent: number | null, message: string | null, 
+/**
+ * RFC 3339 timestamp
+ */
+started_at: string, 
+/**
+ * Estimated seconds remaining, from the rate so far
+ */
+eta_secs: number | null, status: OperationStatus, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Error returned when a path cannot be used
+ */
+export type PathError = { "kind": "PermissionDenied", path: string, } | { "kind": "Invalid", path: string, message: string, } | { "kind": "Io", path: string, message: string, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Outcome of waiting for the Ollama service to accept connections
+ */
+export type ProbeResult = { running: boolean, attempts: number, waited_ms: number, };
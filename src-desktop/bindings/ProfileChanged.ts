@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * "profile-changed": a connection profile was activated
+ */
+export type ProfileChanged = { name: string, backend_url: string, };
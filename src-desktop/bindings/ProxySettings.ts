@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * User proxy configuration; unset fields fall back to HTTP(S)_PROXY / NO_PROXY
+ */
+export type ProxySettings = { 
+/**
+ * http://, https:// or socks5:// proxy URL
+ */
+url?: string, 
+/**
+ * Extra hosts or domains that bypass the proxy
+ */
+no_proxy: Array<string>, };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Error returned when a model pull cannot start or fails
+ */
+export type PullError = { "kind": "InsufficientDisk", required: number, available: number, } | { "kind": "Failed", message: string, };
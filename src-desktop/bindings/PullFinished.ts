@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PullOutcome } from "./PullOutcome";
+
+/**
+ * "ollama-pull-finished": a pull started with pull_model_stream ended.
+ * Deprecated in favor of "operation-progress"
+ */
+export type PullFinished = { handle: string, model: string, outcome: PullOutcome, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One blob of a model being pulled
+ */
+export type PullLayer = { digest: string, total: number, completed: number, 
+/**
+ * Already present locally; Ollama reported it complete on first sight
+ */
+cached: boolean, };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * "ollama-pull-layer": progress of one layer of a pull started with pull_model_stream.
+ * Deprecated in favor of "operation-progress"
+ */
+export type PullLayerProgress = { handle: string, model: string, digest: string, total: number, completed: number, cached: boolean, };
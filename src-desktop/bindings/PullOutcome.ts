@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PullError } from "./PullError";
+import type { PullLayer } from "./PullLayer";
+
+export type PullOutcome = { "status": "completed", layers: Array<PullLayer>, } | { "status": "cancelled" } | { "status": "failed", error: PullError, };
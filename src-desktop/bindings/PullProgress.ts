@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * "ollama-pull-progress": progress update while a model is being pulled.
+ * Deprecated in favor of "operation-progress"; see operations::LEGACY_EVENTS_KEY
+ */
+export type PullProgress = { model: string, status: string, digest: string | null, total: number | null, completed: number | null, };
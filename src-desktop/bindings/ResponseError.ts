@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Error calling the backend, returned as-is by commands so the frontend can tell
+ * an oversized response from an unreachable or failing backend
+ */
+export type ResponseError = { "kind": "Unreachable", message: string, } | { "kind": "Rejected", message: string, } | { "kind": "ResponseTooLarge", url: string, limit: number, bytes: number, declared: boolean, } | { "kind": "Invalid", message: string, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * "resume-recovery": the health monitor detected a system resume and re-checked the backend
+ */
+export type ResumeRecovery = { 
+/**
+ * Approximate time the machine was suspended
+ */
+suspended_secs: number, connections_reset: boolean, health_checks: number, healthy: boolean, };
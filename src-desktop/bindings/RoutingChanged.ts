@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * "routing-changed": backend requests moved to another replica of the active profile
+ */
+export type RoutingChanged = { profile: string | null, from: string, to: string, };
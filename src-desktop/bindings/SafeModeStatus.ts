@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SafeModeReason } from "./SafeModeReason";
+
+export type SafeModeStatus = { reason: SafeModeReason, 
+/**
+ * Startup tasks held back by safe mode. Embedding, indexing and their
+ * schedulers live in the backend process, so this lists desktop-side tasks.
+ */
+skipped: Array<string>, };
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A setting that was reset or repaired on load
+ */
+export type SettingReset = { key: string, reason: string, 
+/**
+ * True if part of the value was kept, false if it was reset to the default
+ */
+repaired: boolean, };
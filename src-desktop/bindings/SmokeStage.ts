@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ResponseError } from "./ResponseError";
+
+export type SmokeStage = { name: string, passed: boolean, duration_ms: number, detail: string, 
+/**
+ * Why the stage failed, e.g. `ResponseTooLarge`
+ */
+error?: ResponseError, };
@@ -0,0 +1,16 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SmokeStage } from "./SmokeStage";
+
+export type SmokeTestReport = { passed: boolean, 
+/**
+ * Stages in the order they ran; a failed stage is always the last one
+ */
+stages: Array<SmokeStage>, 
+/**
+ * Stages skipped because they would change the user's documents or index
+ */
+not_run: Array<string>, 
+/**
+ * Stage that fails when retrieval returns no sources
+ */
+retrieval_stage: string, };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TaskReport } from "./TaskReport";
+
+export type StartupReport = { 
+/**
+ * Tasks in the order they finished
+ */
+tasks: Array<TaskReport>, 
+/**
+ * Heavy tasks skipped in safe mode, and the tasks that depend on them
+ */
+safe_mode_skipped: Array<string>, total_ms: number, };
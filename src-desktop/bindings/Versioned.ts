@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Envelope sent to the frontend: the payload's fields plus `schema_version`
+ */
+export type Versioned<T> = { schema_version: number, } & T;
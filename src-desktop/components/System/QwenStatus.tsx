@@ -1,6 +1,26 @@
 import React, { useState, useEffect } from 'react';
 import { Brain, CheckCircle, XCircle, Download, Loader2, AlertTriangle } from 'lucide-react';
-import { getOllamaStatus, verifyQwen, type OllamaStatus } from '../../services/ollamaService';
+import {
+  getOllamaStatus,
+  isInsufficientDisk,
+  verifyQwen,
+  type OllamaStatus,
+} from '../../services/ollamaService';
+
+interface DiskShortfall {
+  required: number;
+  available: number;
+}
+
+const formatGB = (bytes: number) => `${(bytes / 1024 ** 3).toFixed(1)} GB`;
+
+/** Message text from a rejected invoke, which may be an Error, a string or a typed error */
+const errorMessage = (err: unknown, fallback: string) => {
+  if (err instanceof Error) return err.message;
+  if (typeof err === 'string') return err;
+  if (typeof err === 'object' && err !== null && 'message' in err) return String(err.message);
+  return fallback;
+};
 
 interface QwenStatusProps {
   autoCheck?: boolean;
@@ -15,6 +35,7 @@ export const QwenStatus: React.FC<QwenStatusProps> = ({
   const [loading, setLoading] = useState(true);
   const [downloading, setDownloading] = useState(false);
   const [error, setError] = useState<string | null>(null);
+  const [diskShortfall, setDiskShortfall] = useState<DiskShortfall | null>(null);
 
   // Check Ollama status
   const checkStatus = async () => {
@@ -23,21 +44,27 @@ export const QwenStatus: React.FC<QwenStatusProps> = ({
       setStatus(ollamaStatus);
       setError(null);
     } catch (err) {
-      setError(err instanceof Error ? err.message : 'Failed to check status');
+      setError(errorMessage(err, 'Failed to check status'));
     } finally {
       setLoading(false);
     }
   };
 
-  // Download and verify Qwen model
-  const handleDownloadQwen = async () => {
+  // Download and verify Qwen model; force skips the disk space check
+  const handleDownloadQwen = async (force = false) => {
     setDownloading(true);
     setError(null);
+    setDiskShortfall(null);
     try {
-      await verifyQwen();
+      await verifyQwen(force);
       await checkStatus(); // Refresh status after download
     } catch (err) {
-      setError(err instanceof Error ? err.message : 'Failed to download model');
+      if (isInsufficientDisk(err)) {
+        setDiskShortfall({ required: err.required, available: err.available });
+        setError('Not enough disk space for the model');
+      } else {
+        setError(errorMessage(err, 'Failed to download model'));
+      }
     } finally {
       setDownloading(false);
     }
@@ -160,6 +187,20 @@ export const QwenStatus: React.FC<QwenStatusProps> = ({
         {error && (
           <div className="mt-3 p-2 bg-red-50 dark:bg-red-900/20 border border-red-200 dark:border-red-800 rounded text-red-700 dark:text-red-400 text-xs">
             {error}
+            {diskShortfall && (
+              <>
+                <p className="mt-1">
+                  Requires {formatGB(diskShortfall.required)}, {formatGB(diskShortfall.available)} available.
+                </p>
+                <button
+                  onClick={() => handleDownloadQwen(true)}
+                  disabled={downloading}
+                  className="mt-2 underline hover:no-underline disabled:opacity-50"
+                >
+                  Download anyway
+                </button>
+              </>
+            )}
           </div>
         )}
 
@@ -167,7 +208,7 @@ export const QwenStatus: React.FC<QwenStatusProps> = ({
         {status && status.installed && status.running && !status.qwen_available && (
           <div className="mt-3 pt-3 border-t border-neutral-200 dark:border-neutral-700">
             <button
-              onClick={handleDownloadQwen}
+              onClick={() => handleDownloadQwen()}
               disabled={downloading}
               className="w-full flex items-center justify-center gap-2 px-4 py-2 bg-primary-600 hover:bg-primary-700 disabled:bg-neutral-400 text-white rounded-lg transition-colors text-sm font-medium"
             >
@@ -1,7 +1,6 @@
 import { useRef, useCallback } from 'react';
 import type { QueryRequest, StreamEvent, StreamCallbacks } from '../types';
-
-const API_BASE = import.meta.env.VITE_API_BASE_URL || 'http://localhost:8000';
+import { backendFetch } from '../services/backendService';
 
 export const useStreamingChat = () => {
   const abortControllerRef = useRef<AbortController | null>(null);
@@ -17,7 +16,7 @@ export const useStreamingChat = () => {
       abortControllerRef.current = new AbortController();
 
       try {
-        const response = await fetch(`${API_BASE}/api/query/stream`, {
+        const response = await backendFetch('/api/query/stream', {
           method: 'POST',
           headers: {
             'Content-Type': 'application/json',
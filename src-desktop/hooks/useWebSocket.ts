@@ -1,7 +1,9 @@
-import { useEffect, useRef, useCallback } from 'react';
+import { useEffect, useRef, useCallback, useState } from 'react';
 import { io, Socket } from 'socket.io-client';
+import { currentBackendConnection, onBackendUrlChanged } from '../services/backendService';
 
 interface UseWebSocketOptions {
+  /** Defaults to the backend the app currently routes to */
   url?: string;
   onConnect?: () => void;
   onDisconnect?: () => void;
@@ -10,19 +12,54 @@ interface UseWebSocketOptions {
 }
 
 export const useWebSocket = ({
-  url = 'http://localhost:8000',
+  url,
   onConnect,
   onDisconnect,
   onError,
   onMessage,
 }: UseWebSocketOptions = {}) => {
   const socketRef = useRef<Socket | null>(null);
+  const [socketUrl, setSocketUrl] = useState<string | null>(url ?? null);
+  const [authToken, setAuthToken] = useState<string | null>(null);
 
+  // Follow the backend endpoint unless a URL was given
   useEffect(() => {
+    if (url) {
+      setSocketUrl(url);
+      setAuthToken(null);
+      return;
+    }
+
+    let active = true;
+    const resolve = () => {
+      currentBackendConnection()
+        .then((connection) => {
+          if (active) {
+            setSocketUrl(connection.url);
+            setAuthToken(connection.auth_token);
+          }
+        })
+        .catch((error) => onError?.(error));
+    };
+    resolve();
+    const unsubscribe = onBackendUrlChanged(resolve);
+
+    return () => {
+      active = false;
+      unsubscribe();
+    };
+  }, [url, onError]);
+
+  useEffect(() => {
+    if (!socketUrl) {
+      return;
+    }
+
     // Initialize socket connection
-    socketRef.current = io(url, {
+    socketRef.current = io(socketUrl, {
       transports: ['websocket'],
       autoConnect: false,
+      auth: authToken ? { token: authToken } : undefined,
     });
 
     const socket = socketRef.current;
@@ -60,7 +97,7 @@ export const useWebSocket = ({
     return () => {
       socket.disconnect();
     };
-  }, [url, onConnect, onDisconnect, onError, onMessage]);
+  }, [socketUrl, authToken, onConnect, onDisconnect, onError, onMessage]);
 
   const sendMessage = useCallback((event: string, data: any) => {
     if (socketRef.current?.connected) {
@@ -1,10 +1,8 @@
 import type { QueryRequest, QueryResponse, HealthStatus, DocumentListResponse, ReindexResponse } from '../types';
+import { backendFetch } from './backendService';
 
-// Tauri desktop app: Connect directly to backend (no proxy needed)
-// Development: Docker backend at localhost:8000
-// Production: Sidecar backend at 127.0.0.1:8000
-const API_BASE = import.meta.env.VITE_API_BASE_URL ||
-  (import.meta.env.DEV ? 'http://localhost:8000' : 'http://127.0.0.1:8000');
+// Tauri desktop app: Connect directly to the backend the Rust side routes to
+// (set_backend_url, the active connection profile or replica failover)
 
 class ApiError extends Error {
   status?: number;
@@ -39,7 +37,7 @@ async function handleResponse<T>(response: Response): Promise<T> {
 export const api = {
   // Query endpoint
   query: async (request: QueryRequest): Promise<QueryResponse> => {
-    const response = await fetch(`${API_BASE}/api/query`, {
+    const response = await backendFetch('/api/query', {
       method: 'POST',
       headers: {
         'Content-Type': 'application/json',
@@ -51,7 +49,7 @@ export const api = {
 
   // Health check
   health: async (): Promise<HealthStatus> => {
-    const response = await fetch(`${API_BASE}/api/health`);
+    const response = await backendFetch('/api/health');
     return handleResponse<HealthStatus>(response);
   },
 
@@ -60,7 +58,7 @@ export const api = {
     const formData = new FormData();
     formData.append('file', file);
 
-    const response = await fetch(`${API_BASE}/api/documents/upload`, {
+    const response = await backendFetch('/api/documents/upload', {
       method: 'POST',
       body: formData,
     });
@@ -69,13 +67,13 @@ export const api = {
 
   // List documents
   listDocuments: async (): Promise<DocumentListResponse> => {
-    const response = await fetch(`${API_BASE}/api/documents`);
+    const response = await backendFetch('/api/documents');
     return handleResponse<DocumentListResponse>(response);
   },
 
   // Reindex documents
   reindexDocuments: async (): Promise<ReindexResponse> => {
-    const response = await fetch(`${API_BASE}/api/documents/reindex`, {
+    const response = await backendFetch('/api/documents/reindex', {
       method: 'POST',
     });
     return handleResponse<ReindexResponse>(response);
@@ -83,7 +81,7 @@ export const api = {
 
   // Delete document
   deleteDocument: async (id: string): Promise<void> => {
-    const response = await fetch(`${API_BASE}/api/documents/${id}`, {
+    const response = await backendFetch(`/api/documents/${id}`, {
       method: 'DELETE',
     });
     if (!response.ok) {
@@ -93,16 +91,16 @@ export const api = {
 
   // Get conversation history
   getConversation: async (conversationId: string): Promise<any> => {
-    const response = await fetch(
-      `${API_BASE}/api/conversations/${conversationId}`
+    const response = await backendFetch(
+      `/api/conversations/${conversationId}`
     );
     return handleResponse<any>(response);
   },
 
   // Clear conversation
   clearConversation: async (conversationId: string): Promise<void> => {
-    const response = await fetch(
-      `${API_BASE}/api/conversations/${conversationId}`,
+    const response = await backendFetch(
+      `/api/conversations/${conversationId}`,
       {
         method: 'DELETE',
       }
@@ -114,7 +112,7 @@ export const api = {
 
   // Clear cache and conversation memory
   clearCacheAndMemory: async (): Promise<{ success: boolean; message: string }> => {
-    const response = await fetch(`${API_BASE}/api/conversation/clear`, {
+    const response = await backendFetch('/api/conversation/clear', {
       method: 'POST',
     });
     return handleResponse<{ success: boolean; message: string }>(response);
@@ -122,13 +120,13 @@ export const api = {
 
   // Get settings
   getSettings: async (): Promise<{ success: boolean; message: string; current_settings: any }> => {
-    const response = await fetch(`${API_BASE}/api/settings`);
+    const response = await backendFetch('/api/settings');
     return handleResponse<{ success: boolean; message: string; current_settings: any }>(response);
   },
 
   // Update settings (including model hot-swap)
   updateSettings: async (settings: any): Promise<{ success: boolean; message: string; current_settings: any }> => {
-    const response = await fetch(`${API_BASE}/api/settings`, {
+    const response = await backendFetch('/api/settings', {
       method: 'PUT',
       headers: {
         'Content-Type': 'application/json',
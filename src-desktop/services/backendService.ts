@@ -4,14 +4,77 @@
  */
 
 import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import type { AuditFilter } from '../bindings/AuditFilter';
+import type { AuditRecord } from '../bindings/AuditRecord';
+import type { AuditVerification } from '../bindings/AuditVerification';
+import type { BackendCapabilities } from '../bindings/BackendCapabilities';
+import type { BackendConnection } from '../bindings/BackendConnection';
+import type { BackendStatus } from '../bindings/BackendStatus';
+import type { ConnectionProfile } from '../bindings/ConnectionProfile';
+import type { ContentionReport } from '../bindings/ContentionReport';
+import type { DocumentFingerprint } from '../bindings/DocumentFingerprint';
+import type { EventSubscription } from '../bindings/EventSubscription';
+import type { FollowupMode } from '../bindings/FollowupMode';
+import type { FollowupSource } from '../bindings/FollowupSource';
+import type { FollowupSuggestions } from '../bindings/FollowupSuggestions';
+import type { HotswapSnapshot } from '../bindings/HotswapSnapshot';
+import type { ModelCapabilities } from '../bindings/ModelCapabilities';
+import type { ModelListCheck } from '../bindings/ModelListCheck';
+import type { ModelsChanged } from '../bindings/ModelsChanged';
+import type { ModelSwitchResponse } from '../bindings/ModelSwitchResponse';
+import type { OperationProgress } from '../bindings/OperationProgress';
+import type { ProfileList } from '../bindings/ProfileList';
+import type { ProxySettings } from '../bindings/ProxySettings';
+import type { ResumeRecovery } from '../bindings/ResumeRecovery';
+import type { RoutingChanged } from '../bindings/RoutingChanged';
+import type { SafeModeStatus } from '../bindings/SafeModeStatus';
+import type { SettingReset } from '../bindings/SettingReset';
+import type { SmokeTestReport } from '../bindings/SmokeTestReport';
+import type { StartupReport } from '../bindings/StartupReport';
+import type { Versioned } from '../bindings/Versioned';
 
-export interface BackendStatus {
-  running: boolean;
-  healthy: boolean;
-  port: number;
-  last_check: string;
-  error?: string;
-}
+export type {
+  AuditFilter,
+  AuditRecord,
+  AuditVerification,
+  BackendCapabilities,
+  BackendConnection,
+  BackendStatus,
+  ConnectionProfile,
+  ContentionReport,
+  DocumentFingerprint,
+  EventSubscription,
+  FollowupMode,
+  FollowupSource,
+  FollowupSuggestions,
+  HotswapSnapshot,
+  ModelCapabilities,
+  ModelListCheck,
+  ModelsChanged,
+  ModelSwitchResponse,
+  OperationProgress,
+  ProfileList,
+  ProxySettings,
+  ResumeRecovery,
+  RoutingChanged,
+  SafeModeStatus,
+  SettingReset,
+  SmokeTestReport,
+  StartupReport,
+};
+export type { BackendReplica } from '../bindings/BackendReplica';
+export type { Fetched } from '../bindings/Fetched';
+export type { ModelInfo as BackendModelInfo } from '../bindings/ModelInfo';
+export type { ModelSwitched } from '../bindings/ModelSwitched';
+export type { OperationKind } from '../bindings/OperationKind';
+export type { OperationStatus } from '../bindings/OperationStatus';
+export type { PathError } from '../bindings/PathError';
+export type { ResponseError } from '../bindings/ResponseError';
+export type { SafeModeReason } from '../bindings/SafeModeReason';
+export type { SmokeStage } from '../bindings/SmokeStage';
+export type { TaskReport } from '../bindings/TaskReport';
+export type { TaskStatus } from '../bindings/TaskStatus';
 
 /**
  * Start the backend sidecar process
@@ -52,6 +115,543 @@ export async function getBackendStatus(): Promise<BackendStatus> {
   }
 }
 
+/**
+ * Get the backend base URL used by the desktop app
+ */
+export async function getBackendUrl(): Promise<string> {
+  try {
+    return await invoke<string>('get_backend_url');
+  } catch (error) {
+    console.error('Failed to get backend URL:', error);
+    throw error;
+  }
+}
+
+/**
+ * Get the backend URL plus the active profile's auth token, for requests made by the webview
+ */
+export async function getBackendConnection(): Promise<BackendConnection> {
+  try {
+    return await invoke<BackendConnection>('get_backend_connection');
+  } catch (error) {
+    console.error('Failed to get backend connection:', error);
+    throw error;
+  }
+}
+
+/**
+ * Change the backend base URL (persisted, applied on the next request)
+ */
+export async function setBackendUrl(url: string): Promise<void> {
+  try {
+    await invoke('set_backend_url', { url });
+    forgetBackendConnection();
+  } catch (error) {
+    console.error('Failed to set backend URL:', error);
+    throw error;
+  }
+}
+
+let backendConnection: Promise<BackendConnection> | null = null;
+let backendConnectionWatched = false;
+const backendConnectionListeners = new Set<() => void>();
+
+function forgetBackendConnection(): void {
+  backendConnection = null;
+  backendConnectionListeners.forEach((listener) => listener());
+}
+
+/**
+ * Backend the app currently routes to, with its URL stripped of any trailing slash.
+ * Re-resolved after a URL change, profile switch or replica failover.
+ */
+export function currentBackendConnection(): Promise<BackendConnection> {
+  if (!backendConnectionWatched) {
+    backendConnectionWatched = true;
+    listen('profile-changed', forgetBackendConnection);
+    listen('routing-changed', forgetBackendConnection);
+  }
+  if (!backendConnection) {
+    backendConnection = getBackendConnection()
+      .then((connection) => ({ ...connection, url: connection.url.replace(/\/+$/, '') }))
+      .catch((error) => {
+        backendConnection = null;
+        throw error;
+      });
+  }
+  return backendConnection;
+}
+
+/**
+ * Call `handler` whenever the backend URL or token may have changed; returns an unsubscribe function
+ */
+export function onBackendUrlChanged(handler: () => void): () => void {
+  backendConnectionListeners.add(handler);
+  return () => {
+    backendConnectionListeners.delete(handler);
+  };
+}
+
+/**
+ * fetch() a backend API path (e.g. "/api/query") on the current backend,
+ * sending the active profile's token as a bearer token
+ */
+export async function backendFetch(path: string, init?: RequestInit): Promise<Response> {
+  const connection = await currentBackendConnection();
+  const headers = new Headers(init?.headers);
+  if (connection.auth_token && !headers.has('Authorization')) {
+    headers.set('Authorization', `Bearer ${connection.auth_token}`);
+  }
+  return fetch(`${connection.url}${path}`, { ...init, headers });
+}
+
+/**
+ * List saved connection profiles and the active one
+ */
+export async function listProfiles(): Promise<ProfileList> {
+  try {
+    return await invoke<ProfileList>('list_profiles');
+  } catch (error) {
+    console.error('Failed to list profiles:', error);
+    throw error;
+  }
+}
+
+/**
+ * Create or update a connection profile
+ */
+export async function saveProfile(profile: ConnectionProfile): Promise<void> {
+  try {
+    await invoke('save_profile', { profile });
+  } catch (error) {
+    console.error('Failed to save profile:', error);
+    throw error;
+  }
+}
+
+/**
+ * Delete a saved (inactive) connection profile
+ */
+export async function deleteProfile(name: string): Promise<void> {
+  try {
+    await invoke('delete_profile', { name });
+  } catch (error) {
+    console.error('Failed to delete profile:', error);
+    throw error;
+  }
+}
+
+/**
+ * Switch the backend connection to a saved profile
+ */
+export async function activateProfile(name: string): Promise<void> {
+  try {
+    await invoke('activate_profile', { name });
+  } catch (error) {
+    console.error('Failed to activate profile:', error);
+    throw error;
+  }
+}
+
+/**
+ * Get the model list, free VRAM and per-model fit in one call
+ */
+export async function getHotswapSnapshot(): Promise<HotswapSnapshot> {
+  try {
+    return await invoke<HotswapSnapshot>('get_hotswap_snapshot');
+  } catch (error) {
+    console.error('Failed to get hotswap snapshot:', error);
+    throw error;
+  }
+}
+
+/**
+ * Check whether the backend's model list changed since it was last fetched.
+ * Rejects with a ResponseError.
+ */
+export async function checkModelListChanged(): Promise<ModelListCheck> {
+  try {
+    return await invoke<ModelListCheck>('check_model_list_changed');
+  } catch (error) {
+    console.error('Failed to check model list:', error);
+    throw error;
+  }
+}
+
+/**
+ * Subscribe to changes in the backend's model list
+ */
+export function onModelsChanged(
+  handler: (change: Versioned<ModelsChanged>) => void
+): Promise<UnlistenFn> {
+  return listen<Versioned<ModelsChanged>>('models-changed', (event) => handler(event.payload));
+}
+
+/**
+ * Switch the backend model, optionally falling back along the configured chain.
+ * Rejects with a ResponseError; `kind === "Unreachable"` means the backend is down and
+ * no fallback was tried, `kind === "ResponseTooLarge"` carries the byte `limit`.
+ */
+export async function switchModel(modelId: string, fallback = false): Promise<ModelSwitchResponse> {
+  try {
+    return await invoke<ModelSwitchResponse>('switch_model', { modelId, fallback });
+  } catch (error) {
+    console.error('Failed to switch model:', error);
+    throw error;
+  }
+}
+
+/**
+ * Get the ordered list of fallback models
+ */
+export async function getModelFallbackChain(): Promise<string[]> {
+  try {
+    return await invoke<string[]>('get_model_fallback_chain');
+  } catch (error) {
+    console.error('Failed to get model fallback chain:', error);
+    throw error;
+  }
+}
+
+/**
+ * Set the ordered list of fallback models
+ */
+export async function setModelFallbackChain(chain: string[]): Promise<void> {
+  try {
+    await invoke('set_model_fallback_chain', { chain });
+  } catch (error) {
+    console.error('Failed to set model fallback chain:', error);
+    throw error;
+  }
+}
+
+/**
+ * Get the optional features supported by the current backend (cached per backend URL)
+ */
+export async function getBackendCapabilities(refresh = false): Promise<BackendCapabilities> {
+  try {
+    return await invoke<BackendCapabilities>('get_backend_capabilities', { refresh });
+  } catch (error) {
+    console.error('Failed to get backend capabilities:', error);
+    throw error;
+  }
+}
+
+/**
+ * Get what a model supports, for gating model-specific UI features
+ */
+export async function getModelCapabilities(modelId: string): Promise<ModelCapabilities> {
+  try {
+    return await invoke<ModelCapabilities>('get_model_capabilities', { modelId });
+  } catch (error) {
+    console.error('Failed to get model capabilities:', error);
+    throw error;
+  }
+}
+
+/**
+ * Check whether Ollama and the backend are competing for the same GPU
+ */
+export async function gpuContentionCheck(): Promise<ContentionReport> {
+  try {
+    return await invoke<ContentionReport>('gpu_contention_check');
+  } catch (error) {
+    console.error('Failed to check GPU contention:', error);
+    throw error;
+  }
+}
+
+/**
+ * Get the outbound proxy configuration
+ */
+export async function getProxySettings(): Promise<ProxySettings> {
+  try {
+    return await invoke<ProxySettings>('get_proxy_settings');
+  } catch (error) {
+    console.error('Failed to get proxy settings:', error);
+    throw error;
+  }
+}
+
+/**
+ * Set the outbound proxy (omit url to fall back to HTTP_PROXY/HTTPS_PROXY)
+ */
+export async function setProxySettings(proxy: ProxySettings): Promise<void> {
+  try {
+    await invoke('set_proxy_settings', { proxy });
+  } catch (error) {
+    console.error('Failed to set proxy settings:', error);
+    throw error;
+  }
+}
+
+/**
+ * Ask the user to pick a folder and allow the app to read and write inside it
+ */
+export async function pickAuthorizedRoot(): Promise<string | null> {
+  try {
+    return await invoke<string | null>('pick_authorized_root');
+  } catch (error) {
+    console.error('Failed to authorize folder:', error);
+    throw error;
+  }
+}
+
+/**
+ * List the folders the user has authorized
+ */
+export async function listAuthorizedRoots(): Promise<string[]> {
+  try {
+    return await invoke<string[]>('list_authorized_roots');
+  } catch (error) {
+    console.error('Failed to list authorized folders:', error);
+    throw error;
+  }
+}
+
+/**
+ * Revoke access to a previously authorized folder
+ */
+export async function revokeAuthorizedRoot(path: string): Promise<void> {
+  try {
+    await invoke('revoke_authorized_root', { path });
+  } catch (error) {
+    console.error('Failed to revoke authorized folder:', error);
+    throw error;
+  }
+}
+
+/**
+ * Content fingerprint of a file inside an authorized folder, for duplicate pre-checks.
+ * Rejects with a PathError; `kind === "PermissionDenied"` means the folder needs authorizing.
+ */
+export async function documentFingerprint(path: string): Promise<DocumentFingerprint> {
+  try {
+    return await invoke<DocumentFingerprint>('document_fingerprint', { path });
+  } catch (error) {
+    console.error('Failed to fingerprint document:', error);
+    throw error;
+  }
+}
+
+/**
+ * Get audit records, newest first
+ */
+export async function getAuditLog(filter?: AuditFilter, limit?: number): Promise<AuditRecord[]> {
+  try {
+    return await invoke<AuditRecord[]>('get_audit_log', { filter, limit });
+  } catch (error) {
+    console.error('Failed to get audit log:', error);
+    throw error;
+  }
+}
+
+/**
+ * Export the full audit log (including rotated files) to a JSONL file inside an authorized folder.
+ * Rejects with a PathError; `kind === "PermissionDenied"` means the folder needs authorizing.
+ */
+export async function exportAuditLog(path: string): Promise<void> {
+  try {
+    await invoke('export_audit_log', { path });
+  } catch (error) {
+    console.error('Failed to export audit log:', error);
+    throw error;
+  }
+}
+
+/**
+ * Check the audit log's HMAC chain for edited or removed records
+ */
+export async function verifyAuditLog(): Promise<AuditVerification> {
+  try {
+    return await invoke<AuditVerification>('verify_audit_log');
+  } catch (error) {
+    console.error('Failed to verify audit log:', error);
+    throw error;
+  }
+}
+
+/**
+ * Run the read-only pipeline check: backend, Ollama, retrieval (non-empty sources) and an answer citing them
+ */
+export async function pipelineSmokeTest(): Promise<SmokeTestReport> {
+  try {
+    return await invoke<SmokeTestReport>('pipeline_smoke_test');
+  } catch (error) {
+    console.error('Failed to run pipeline smoke test:', error);
+    throw error;
+  }
+}
+
+/**
+ * Limit which events a window receives; windows that never subscribe get every event
+ */
+export async function subscribeEvents(windowLabel: string, topics: EventSubscription[]): Promise<void> {
+  try {
+    await invoke('subscribe_events', { windowLabel, topics });
+  } catch (error) {
+    console.error('Failed to subscribe to events:', error);
+    throw error;
+  }
+}
+
+/**
+ * Stop delivering the given topics to a window
+ */
+export async function unsubscribeEvents(windowLabel: string, topics: string[]): Promise<void> {
+  try {
+    await invoke('unsubscribe_events', { windowLabel, topics });
+  } catch (error) {
+    console.error('Failed to unsubscribe from events:', error);
+    throw error;
+  }
+}
+
+/**
+ * Get the status and duration of each startup task
+ */
+export async function getStartupReport(): Promise<StartupReport> {
+  try {
+    return await invoke<StartupReport>('get_startup_report');
+  } catch (error) {
+    console.error('Failed to get startup report:', error);
+    throw error;
+  }
+}
+
+/**
+ * Settings that were invalid at startup and were reset or repaired
+ */
+export async function getSettingsResets(): Promise<SettingReset[]> {
+  try {
+    return await invoke<SettingReset[]>('get_settings_resets');
+  } catch (error) {
+    console.error('Failed to get settings resets:', error);
+    throw error;
+  }
+}
+
+/**
+ * Why the app started in safe mode and what it skipped; null in normal mode
+ */
+export async function getSafeModeReason(): Promise<SafeModeStatus | null> {
+  try {
+    return await invoke<SafeModeStatus | null>('get_safe_mode_reason');
+  } catch (error) {
+    console.error('Failed to get safe mode reason:', error);
+    throw error;
+  }
+}
+
+/**
+ * Clear the crash markers and relaunch the app normally
+ */
+export async function exitSafeModeAndRestart(): Promise<void> {
+  try {
+    await invoke('exit_safe_mode_and_restart');
+  } catch (error) {
+    console.error('Failed to exit safe mode:', error);
+    throw error;
+  }
+}
+
+/**
+ * Suggest follow-up questions for an answer, skipping ones already asked
+ */
+export async function suggestFollowups(
+  answer: string,
+  sources: FollowupSource[],
+  previousQuestions?: string[],
+  mode?: FollowupMode
+): Promise<FollowupSuggestions> {
+  try {
+    return await invoke<FollowupSuggestions>('suggest_followups', {
+      answer,
+      sources,
+      previousQuestions,
+      mode,
+    });
+  } catch (error) {
+    console.error('Failed to suggest follow-ups:', error);
+    throw error;
+  }
+}
+
+/**
+ * Get the saved follow-up suggestion mode
+ */
+export async function getFollowupMode(): Promise<FollowupMode> {
+  try {
+    return await invoke<FollowupMode>('get_followup_mode');
+  } catch (error) {
+    console.error('Failed to get follow-up mode:', error);
+    throw error;
+  }
+}
+
+/**
+ * Save the follow-up suggestion mode
+ */
+export async function setFollowupMode(mode: FollowupMode): Promise<void> {
+  try {
+    await invoke('set_followup_mode', { mode });
+  } catch (error) {
+    console.error('Failed to set follow-up mode:', error);
+    throw error;
+  }
+}
+
+/**
+ * Subscribe to recovery reports after the machine resumes from sleep
+ */
+export function onResumeRecovery(
+  handler: (recovery: Versioned<ResumeRecovery>) => void
+): Promise<UnlistenFn> {
+  return listen<Versioned<ResumeRecovery>>('resume-recovery', (event) => handler(event.payload));
+}
+
+/**
+ * Subscribe to failover between replicas of the active connection profile
+ */
+export function onRoutingChanged(
+  handler: (change: Versioned<RoutingChanged>) => void
+): Promise<UnlistenFn> {
+  return listen<Versioned<RoutingChanged>>('routing-changed', (event) => handler(event.payload));
+}
+
+/**
+ * Latest progress of every pull, smoke test or benchmark still running
+ */
+export async function listActiveOperations(): Promise<OperationProgress[]> {
+  try {
+    return await invoke<OperationProgress[]>('list_active_operations');
+  } catch (error) {
+    console.error('Failed to list active operations:', error);
+    throw error;
+  }
+}
+
+/**
+ * Cancel a running operation; its final progress event reports "cancelled"
+ */
+export async function cancelOperation(id: string): Promise<void> {
+  try {
+    await invoke('cancel_operation', { id });
+  } catch (error) {
+    console.error('Failed to cancel operation:', error);
+    throw error;
+  }
+}
+
+/**
+ * Subscribe to progress of every long-running operation
+ */
+export function onOperationProgress(
+  handler: (progress: Versioned<OperationProgress>) => void
+): Promise<UnlistenFn> {
+  return listen<Versioned<OperationProgress>>('operation-progress', (event) => handler(event.payload));
+}
+
 /**
  * Monitor backend health with polling
  */
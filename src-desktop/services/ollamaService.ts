@@ -4,15 +4,33 @@
  */
 
 import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+import type { BenchmarkResult } from '../bindings/BenchmarkResult';
+import type { LatencyError } from '../bindings/LatencyError';
+import type { LatencyProfile } from '../bindings/LatencyProfile';
+import type { LoadedModel } from '../bindings/LoadedModel';
+import type { OllamaStatus } from '../bindings/OllamaStatus';
+import type { PullError } from '../bindings/PullError';
+import type { ProbeResult } from '../bindings/ProbeResult';
+import type { PullFinished } from '../bindings/PullFinished';
+import type { PullLayerProgress } from '../bindings/PullLayerProgress';
+import type { PullProgress } from '../bindings/PullProgress';
+import type { Versioned } from '../bindings/Versioned';
 
-export interface OllamaStatus {
-  installed: boolean;
-  running: boolean;
-  version?: string;
-  models: string[];
-  qwen_available: boolean;
-  recommended_model: string;
-}
+export type {
+  BenchmarkResult,
+  LatencyError,
+  LatencyProfile,
+  LoadedModel,
+  OllamaStatus,
+  ProbeResult,
+  PullFinished,
+  PullLayerProgress,
+  PullError,
+  PullProgress,
+};
+export type { PullLayer } from '../bindings/PullLayer';
+export type { PullOutcome } from '../bindings/PullOutcome';
 
 /**
  * Get current Ollama installation status
@@ -28,11 +46,11 @@ export async function getOllamaStatus(): Promise<OllamaStatus> {
 }
 
 /**
- * Pull a specific Qwen model
+ * Pull a specific Qwen model (set force to skip the disk space check)
  */
-export async function pullQwenModel(modelName: string): Promise<void> {
+export async function pullQwenModel(modelName: string, force = false): Promise<void> {
   try {
-    await invoke('pull_qwen_model', { model: modelName });
+    await invoke('pull_qwen_model', { model: modelName, force });
     console.log(`Successfully pulled model: ${modelName}`);
   } catch (error) {
     console.error('Failed to pull Qwen model:', error);
@@ -41,11 +59,76 @@ export async function pullQwenModel(modelName: string): Promise<void> {
 }
 
 /**
- * Verify Qwen model is installed, pull if not
+ * Subscribe to progress events emitted while a model is being pulled
+ * @deprecated Use onOperationProgress
+ */
+export function onPullProgress(
+  handler: (progress: Versioned<PullProgress>) => void
+): Promise<UnlistenFn> {
+  return listen<Versioned<PullProgress>>('ollama-pull-progress', (event) => handler(event.payload));
+}
+
+/**
+ * Start pulling a model in the background; returns its operation id for cancelOperation
  */
-export async function verifyQwen(): Promise<void> {
+export async function pullModelStream(modelName: string, force = false): Promise<string> {
   try {
-    await invoke('verify_qwen');
+    return await invoke<string>('pull_model_stream', { model: modelName, force });
+  } catch (error) {
+    console.error('Failed to start model pull:', error);
+    throw error;
+  }
+}
+
+/**
+ * Abort a pull started with pullModelStream
+ * @deprecated Use cancelOperation
+ */
+export async function cancelPull(handle: string): Promise<void> {
+  try {
+    await invoke('cancel_pull', { handle });
+  } catch (error) {
+    console.error('Failed to cancel pull:', error);
+    throw error;
+  }
+}
+
+/**
+ * Subscribe to per-layer progress of pulls started with pullModelStream
+ * @deprecated Use onOperationProgress
+ */
+export function onPullLayerProgress(
+  handler: (progress: Versioned<PullLayerProgress>) => void
+): Promise<UnlistenFn> {
+  return listen<Versioned<PullLayerProgress>>('ollama-pull-layer', (event) => handler(event.payload));
+}
+
+/**
+ * Subscribe to completion, cancellation or failure of pulls started with pullModelStream
+ * @deprecated Use onOperationProgress
+ */
+export function onPullFinished(
+  handler: (finished: Versioned<PullFinished>) => void
+): Promise<UnlistenFn> {
+  return listen<Versioned<PullFinished>>('ollama-pull-finished', (event) => handler(event.payload));
+}
+
+/**
+ * Narrow a rejected pull to the typed disk space error
+ */
+export function isInsufficientDisk(
+  error: unknown
+): error is Extract<PullError, { kind: 'InsufficientDisk' }> {
+  return typeof error === 'object' && error !== null && (error as PullError).kind === 'InsufficientDisk';
+}
+
+/**
+ * Verify Qwen model is installed, pull if not (set force to skip the disk space check)
+ * Rejects with a PullError; use isInsufficientDisk to offer a forced retry.
+ */
+export async function verifyQwen(force = false): Promise<void> {
+  try {
+    await invoke('verify_qwen', { force });
     console.log('Qwen model verified and ready');
   } catch (error) {
     console.error('Failed to verify Qwen:', error);
@@ -66,6 +149,78 @@ export async function getRecommendedQwenModel(): Promise<string> {
   }
 }
 
+/**
+ * Pin or unpin a model in memory ("-1" forever, "0" unload now, "30m", ...)
+ */
+export async function setModelResidency(model: string, keepAlive: string): Promise<void> {
+  try {
+    await invoke('set_model_residency', { model, keepAlive });
+  } catch (error) {
+    console.error('Failed to set model residency:', error);
+    throw error;
+  }
+}
+
+/**
+ * Get models currently loaded into memory
+ */
+export async function getModelResidency(): Promise<LoadedModel[]> {
+  try {
+    return await invoke<LoadedModel[]>('get_model_residency');
+  } catch (error) {
+    console.error('Failed to get loaded models:', error);
+    throw error;
+  }
+}
+
+/**
+ * Measure cold and warm first-token latency for a model (unloads it first)
+ * Rejects with a LatencyError; kind "LoadFailed" means Ollama answered but could not run the model.
+ */
+export async function measureModelLatency(model: string): Promise<LatencyProfile> {
+  try {
+    return await invoke<LatencyProfile>('measure_model_latency', { model });
+  } catch (error) {
+    console.error('Failed to measure model latency:', error);
+    throw error;
+  }
+}
+
+/**
+ * Measure prompt and generation tokens/sec for a model on this machine.
+ * Sizes must be at least 1; prompts are capped at 32768 and generations at 4096 tokens.
+ */
+export async function benchmarkOllama(
+  model: string,
+  promptTokens: number,
+  genTokens: number,
+  expectedTokensPerSec?: number
+): Promise<BenchmarkResult> {
+  try {
+    return await invoke<BenchmarkResult>('benchmark_ollama', {
+      model,
+      promptTokens,
+      genTokens,
+      expectedTokensPerSec,
+    });
+  } catch (error) {
+    console.error('Failed to benchmark model:', error);
+    throw error;
+  }
+}
+
+/**
+ * Wait for the Ollama service to come up, retrying with backoff
+ */
+export async function probeOllamaService(attempts?: number, intervalMs?: number): Promise<ProbeResult> {
+  try {
+    return await invoke<ProbeResult>('probe_ollama_service', { attempts, intervalMs });
+  } catch (error) {
+    console.error('Failed to probe Ollama service:', error);
+    throw error;
+  }
+}
+
 /**
  * Monitor Ollama status with polling
  */
@@ -0,0 +1,733 @@
+// Audit Log
+// Append-only local record of destructive actions (profile deletions, model switches, settings changes)
+
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+
+use crate::paths::{self, PathError};
+use crate::settings::{self, Validator};
+
+const AUDIT_FILE: &str = "audit.jsonl";
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+const ROTATED_FILES: usize = 3;
+
+/// Hex-encoded HMAC key next to the log; when present, each record is chained to
+/// the previous one. Kept out of settings.json, which the webview can read.
+const KEY_FILE: &str = "audit.key";
+
+/// Where the chain starts in the retained files, updated as old files rotate out
+const CHAIN_FILE: &str = "audit.chain";
+
+/// Former settings key for the HMAC key, moved to `KEY_FILE` on startup
+const LEGACY_HMAC_KEY_KEY: &str = "audit_hmac_key";
+
+/// Persisted settings owned by this module
+pub const SETTINGS: &[Validator] = &[Validator {
+    key: LEGACY_HMAC_KEY_KEY,
+    check: check_hmac_key,
+}];
+
+fn check_hmac_key(value: &serde_json::Value) -> Result<Option<serde_json::Value>, String> {
+    parse_key(&settings::parse::<String>(value)?).map(|_| None)
+}
+
+fn parse_key(hex_key: &str) -> Result<Vec<u8>, String> {
+    let key = hex::decode(hex_key.trim()).map_err(|e| format!("not hex: {}", e))?;
+    if key.is_empty() {
+        return Err("empty key".to_string());
+    }
+    Ok(key)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct AuditRecord {
+    pub timestamp: String,
+    pub actor: String,
+    pub action: String,
+    pub target: String,
+    /// SHA-256 of the JSON-encoded command parameters
+    pub params_hash: String,
+    pub success: bool,
+    pub error: Option<String>,
+    /// HMAC over the previous record's mac and this record, when chaining is enabled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub mac: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct AuditFilter {
+    #[serde(default)]
+    #[ts(optional)]
+    pub action: Option<String>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub target: Option<String>,
+    /// RFC 3339 timestamp; only records at or after it are returned
+    #[serde(default)]
+    #[ts(optional)]
+    pub since: Option<String>,
+}
+
+impl AuditFilter {
+    /// Parse `since` once, returning a matcher that compares instants rather than strings
+    fn matcher(self) -> Result<impl Fn(&AuditRecord) -> bool, String> {
+        let since = self
+            .since
+            .as_deref()
+            .map(|since| {
+                chrono::DateTime::parse_from_rfc3339(since)
+                    .map_err(|e| format!("Invalid since timestamp {:?}: {}", since, e))
+            })
+            .transpose()?;
+
+        Ok(move |record: &AuditRecord| {
+            self.action.as_ref().map_or(true, |a| &record.action == a)
+                && self.target.as_ref().map_or(true, |t| &record.target == t)
+                && since.map_or(true, |since| {
+                    chrono::DateTime::parse_from_rfc3339(&record.timestamp).is_ok_and(|at| at >= since)
+                })
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct AuditVerification {
+    pub chained: bool,
+    #[ts(type = "number")]
+    pub records: u64,
+    /// 1-based position (oldest first) of the first line that does not parse or whose mac does not match
+    #[ts(as = "Option<f64>")]
+    pub first_invalid: Option<u64>,
+    /// Records that failed to be written since startup
+    #[ts(type = "number")]
+    pub write_errors: u64,
+}
+
+/// Chain position of the oldest retained record
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ChainState {
+    /// Leading records written before chaining was enabled
+    unchained: u64,
+    /// Mac of the record before the oldest chained one, once that has rotated out
+    previous: Option<String>,
+}
+
+struct Writer {
+    path: PathBuf,
+    key: Option<Vec<u8>>,
+    last_mac: Option<String>,
+    max_bytes: u64,
+}
+
+enum Message {
+    Append(AuditRecord),
+    /// Reply once every earlier record has been written
+    Flush(mpsc::Sender<()>),
+}
+
+/// Shared handle to the audit log. Recording never blocks or fails the caller;
+/// a single writer thread appends records in the order they were recorded.
+#[derive(Clone)]
+pub struct AuditLog {
+    path: PathBuf,
+    key: Option<Vec<u8>>,
+    sender: mpsc::Sender<Message>,
+    write_errors: Arc<AtomicU64>,
+}
+
+impl AuditLog {
+    /// Open the log in the app data directory, resuming the mac chain from its last record
+    pub fn open(app: &AppHandle) -> Self {
+        let path = app
+            .path()
+            .app_data_dir()
+            .map(|dir| dir.join(AUDIT_FILE))
+            .unwrap_or_else(|e| {
+                log::warn!("No app data directory for the audit log, using working directory: {}", e);
+                PathBuf::from(AUDIT_FILE)
+            });
+
+        migrate_legacy_key(app, &path);
+        let key = load_key(&path);
+        Self::start(path, key, MAX_LOG_BYTES)
+    }
+
+    fn start(path: PathBuf, key: Option<Vec<u8>>, max_bytes: u64) -> Self {
+        let records = read_records(&path);
+        let last_mac = records.last().and_then(|r| r.mac.clone());
+        let never_chained = records.iter().all(|r| r.mac.is_none());
+        if key.is_some() && never_chained && !sibling(&path, CHAIN_FILE).exists() {
+            // Chaining starts now; records already in the log stay unverified.
+            // A missing state file next to chained records is left missing, so
+            // deleting it does not exempt those records from verification.
+            let state = ChainState {
+                unchained: read_lines(&path).len() as u64,
+                previous: None,
+            };
+            if let Err(e) = write_chain_state(&path, &state) {
+                log::warn!("Failed to start audit chain: {}", e);
+            }
+        }
+
+        let mut writer = Writer {
+            path: path.clone(),
+            key: key.clone(),
+            last_mac,
+            max_bytes,
+        };
+        let write_errors = Arc::new(AtomicU64::new(0));
+        let (sender, receiver) = mpsc::channel();
+
+        let errors = write_errors.clone();
+        let spawned = thread::Builder::new()
+            .name("audit-writer".to_string())
+            .spawn(move || {
+                for message in receiver {
+                    match message {
+                        Message::Append(record) => {
+                            if let Err(e) = writer.append(record) {
+                                errors.fetch_add(1, Ordering::Relaxed);
+                                log::warn!("Failed to write audit record: {}", e);
+                            }
+                        }
+                        Message::Flush(done) => {
+                            let _ = done.send(());
+                        }
+                    }
+                }
+            });
+        if let Err(e) = spawned {
+            log::warn!("Failed to start audit writer, records will be dropped: {}", e);
+        }
+
+        Self {
+            path,
+            key,
+            sender,
+            write_errors,
+        }
+    }
+
+    /// Record the outcome of a destructive action in the background
+    pub fn record<T, E: fmt::Display>(
+        &self,
+        action: &str,
+        target: &str,
+        params: &impl Serialize,
+        outcome: &Result<T, E>,
+    ) {
+        let params = serde_json::to_vec(params).unwrap_or_default();
+        let record = AuditRecord {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            actor: local_user(),
+            action: action.to_string(),
+            target: target.to_string(),
+            params_hash: hex::encode(Sha256::digest(&params)),
+            success: outcome.is_ok(),
+            error: outcome.as_ref().err().map(|e| e.to_string()),
+            mac: None,
+        };
+
+        if self.sender.send(Message::Append(record)).is_err() {
+            self.write_errors.fetch_add(1, Ordering::Relaxed);
+            log::warn!("Failed to queue audit record: writer stopped");
+        }
+    }
+
+    /// Block until every record queued so far has been written
+    fn flush(&self) {
+        let (done, wait) = mpsc::channel();
+        if self.sender.send(Message::Flush(done)).is_ok() {
+            let _ = wait.recv();
+        }
+    }
+}
+
+impl Writer {
+    fn append(&mut self, mut record: AuditRecord) -> Result<(), String> {
+        if let Some(key) = &self.key {
+            record.mac = Some(chain_mac(key, self.last_mac.as_deref(), &record)?);
+        }
+        let mut line = serde_json::to_string(&record)
+            .map_err(|e| format!("Failed to serialize audit record: {}", e))?;
+        line.push('\n');
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        self.rotate_if_full()?;
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|e| format!("Failed to append to {}: {}", self.path.display(), e))?;
+
+        self.last_mac = record.mac;
+        Ok(())
+    }
+
+    /// Shift audit.jsonl -> audit.jsonl.1 -> ... once the live file reaches the size limit
+    fn rotate_if_full(&self) -> Result<(), String> {
+        let size = fs::metadata(&self.path).map(|m| m.len()).unwrap_or(0);
+        if size < self.max_bytes {
+            return Ok(());
+        }
+
+        let oldest = rotated_path(&self.path, ROTATED_FILES);
+        if self.key.is_some() && oldest.exists() {
+            self.advance_chain(&oldest)?;
+        }
+
+        for n in (1..ROTATED_FILES).rev() {
+            let from = rotated_path(&self.path, n);
+            if from.exists() {
+                fs::rename(&from, rotated_path(&self.path, n + 1))
+                    .map_err(|e| format!("Failed to rotate {}: {}", from.display(), e))?;
+            }
+        }
+        fs::rename(&self.path, rotated_path(&self.path, 1))
+            .map_err(|e| format!("Failed to rotate {}: {}", self.path.display(), e))
+    }
+
+    /// Move the chain start past the records in `dropped`, which is about to be overwritten
+    fn advance_chain(&self, dropped: &Path) -> Result<(), String> {
+        let lines = read_file_lines(dropped);
+        let mut state = read_chain_state(&self.path);
+        let unchained = state.unchained.min(lines.len() as u64);
+        state.unchained -= unchained;
+        if let Some(last_mac) = lines.iter().rev().find_map(|line| line.as_ref().ok()?.mac.clone()) {
+            state.previous = Some(last_mac);
+        }
+        write_chain_state(&self.path, &state)
+    }
+}
+
+/// Move an HMAC key kept in settings by older versions into the key file
+fn migrate_legacy_key(app: &AppHandle, log_path: &Path) {
+    let Some(hex_key) = settings::get::<String>(app, LEGACY_HMAC_KEY_KEY) else {
+        return;
+    };
+    let key_path = sibling(log_path, KEY_FILE);
+    if !key_path.exists() {
+        if let Err(e) = write_private(&key_path, hex_key.trim().as_bytes()) {
+            log::warn!("Failed to move audit HMAC key out of settings: {}", e);
+            return;
+        }
+        log::info!("Moved audit HMAC key to {}", key_path.display());
+    }
+    if let Err(e) = settings::remove(app, LEGACY_HMAC_KEY_KEY) {
+        log::warn!("Failed to remove audit HMAC key from settings: {}", e);
+    }
+}
+
+fn load_key(log_path: &Path) -> Option<Vec<u8>> {
+    let key_path = sibling(log_path, KEY_FILE);
+    let hex_key = fs::read_to_string(&key_path).ok()?;
+    parse_key(&hex_key)
+        .map_err(|e| log::warn!("Ignoring invalid audit HMAC key in {}: {}", key_path.display(), e))
+        .ok()
+}
+
+/// Create a file only the current user can read
+fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)?.write_all(contents)
+}
+
+fn read_chain_state(log_path: &Path) -> ChainState {
+    fs::read(sibling(log_path, CHAIN_FILE))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+fn write_chain_state(log_path: &Path, state: &ChainState) -> Result<(), String> {
+    let path = sibling(log_path, CHAIN_FILE);
+    let json = serde_json::to_vec(state).map_err(|e| format!("Failed to serialize audit chain: {}", e))?;
+    fs::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// HMAC-SHA256 over the previous mac and the record serialized without its own mac
+fn chain_mac(key: &[u8], previous: Option<&str>, record: &AuditRecord) -> Result<String, String> {
+    let unsigned = AuditRecord {
+        mac: None,
+        ..record.clone()
+    };
+    let body = serde_json::to_vec(&unsigned)
+        .map_err(|e| format!("Failed to serialize audit record: {}", e))?;
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).map_err(|e| format!("Invalid audit HMAC key: {}", e))?;
+    mac.update(previous.unwrap_or_default().as_bytes());
+    mac.update(&body);
+    Ok(hex::encode(mac.finalize().into_bytes()))
+}
+
+fn local_user() -> String {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .unwrap_or_else(|_| "unknown".to_string())
+}
+
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", n));
+    PathBuf::from(name)
+}
+
+fn sibling(log_path: &Path, name: &str) -> PathBuf {
+    log_path.with_file_name(name)
+}
+
+/// Log files from oldest to newest
+fn log_files(path: &Path) -> Vec<PathBuf> {
+    (1..=ROTATED_FILES)
+        .rev()
+        .map(|n| rotated_path(path, n))
+        .chain(std::iter::once(path.to_path_buf()))
+        .filter(|p| p.exists())
+        .collect()
+}
+
+/// Every non-empty line of one file, parsed; unparseable lines are kept as errors
+fn read_file_lines(path: &Path) -> Vec<Result<AuditRecord, String>> {
+    let Ok(file) = File::open(path) else {
+        return Vec::new();
+    };
+    BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| serde_json::from_str(&line).map_err(|e| e.to_string()))
+        .collect()
+}
+
+/// Every line of every log file, oldest first
+fn read_lines(path: &Path) -> Vec<Result<AuditRecord, String>> {
+    log_files(path).iter().flat_map(|p| read_file_lines(p)).collect()
+}
+
+/// All records, oldest first; unreadable lines are skipped
+fn read_records(path: &Path) -> Vec<AuditRecord> {
+    read_lines(path).into_iter().filter_map(Result::ok).collect()
+}
+
+/// Check every line parses and, with a key, that every record after the
+/// pre-chaining ones carries the mac the chain expects
+fn verify(path: &Path, key: Option<&[u8]>, write_errors: u64) -> Result<AuditVerification, String> {
+    let lines = read_lines(path);
+    let state = read_chain_state(path);
+    let mut first_invalid = None;
+    let mut previous = state.previous;
+
+    for (i, line) in lines.iter().enumerate() {
+        let Ok(record) = line else {
+            first_invalid = Some(i as u64 + 1);
+            break;
+        };
+        let Some(key) = key else {
+            continue;
+        };
+        if (i as u64) < state.unchained {
+            continue;
+        }
+        let expected = chain_mac(key, previous.as_deref(), record)?;
+        if record.mac.as_deref() != Some(expected.as_str()) {
+            first_invalid = Some(i as u64 + 1);
+            break;
+        }
+        previous = record.mac.clone();
+    }
+
+    Ok(AuditVerification {
+        chained: key.is_some(),
+        records: lines.len() as u64,
+        first_invalid,
+        write_errors,
+    })
+}
+
+// Tauri Commands
+
+#[tauri::command]
+pub async fn get_audit_log(
+    audit: tauri::State<'_, AuditLog>,
+    filter: Option<AuditFilter>,
+    limit: Option<usize>,
+) -> Result<Vec<AuditRecord>, String> {
+    let audit = audit.inner().clone();
+    let matches = filter.unwrap_or_default().matcher()?;
+    let limit = limit.unwrap_or(200);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        audit.flush();
+        read_records(&audit.path)
+            .into_iter()
+            .rev()
+            .filter(|r| matches(r))
+            .take(limit)
+            .collect()
+    })
+    .await
+    .map_err(|e| format!("Failed to read audit log: {}", e))
+}
+
+#[tauri::command]
+pub async fn export_audit_log(
+    app: AppHandle,
+    audit: tauri::State<'_, AuditLog>,
+    path: String,
+) -> Result<(), PathError> {
+    let target = paths::authorize(&app, &path)?;
+    let audit = audit.inner().clone();
+    let io_error = |message: String| PathError::Io {
+        path: path.clone(),
+        message,
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        audit.flush();
+        let mut out = File::create(&target).map_err(|e| format!("Failed to create file: {}", e))?;
+        for file in log_files(&audit.path) {
+            let contents = fs::read(&file).map_err(|e| format!("Failed to read {}: {}", file.display(), e))?;
+            out.write_all(&contents)
+                .map_err(|e| format!("Failed to write file: {}", e))?;
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| io_error(format!("Failed to export audit log: {}", e)))?
+    .map_err(io_error)
+}
+
+#[tauri::command]
+pub async fn verify_audit_log(audit: tauri::State<'_, AuditLog>) -> Result<AuditVerification, String> {
+    let audit = audit.inner().clone();
+
+    tauri::async_runtime::spawn_blocking(move || {
+        audit.flush();
+        let write_errors = audit.write_errors.load(Ordering::Relaxed);
+        verify(&audit.path, audit.key.as_deref(), write_errors)
+    })
+    .await
+    .map_err(|e| format!("Failed to verify audit log: {}", e))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &[u8] = b"test-key";
+
+    fn open(dir: &tempfile::TempDir, key: Option<&[u8]>, max_bytes: u64) -> AuditLog {
+        AuditLog::start(dir.path().join(AUDIT_FILE), key.map(<[u8]>::to_vec), max_bytes)
+    }
+
+    fn record_n(audit: &AuditLog, n: usize) {
+        for i in 0..n {
+            let outcome: Result<(), String> = if i % 2 == 0 { Ok(()) } else { Err("failed".to_string()) };
+            audit.record("delete_profile", &format!("profile-{}", i), &i, &outcome);
+        }
+        audit.flush();
+    }
+
+    fn verification(audit: &AuditLog) -> AuditVerification {
+        verify(&audit.path, audit.key.as_deref(), 0).unwrap()
+    }
+
+    fn rewrite_lines(path: &Path, edit: impl FnOnce(&mut Vec<String>)) {
+        let mut lines: Vec<String> = fs::read_to_string(path).unwrap().lines().map(str::to_string).collect();
+        edit(&mut lines);
+        fs::write(path, lines.join("\n") + "\n").unwrap();
+    }
+
+    #[test]
+    fn records_have_the_expected_shape_and_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let audit = open(&dir, None, MAX_LOG_BYTES);
+        record_n(&audit, 3);
+
+        let records = read_records(&audit.path);
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[1].target, "profile-1");
+        assert!(!records[1].success);
+        assert_eq!(records[1].error.as_deref(), Some("failed"));
+        assert_eq!(records[0].params_hash, hex::encode(Sha256::digest(b"0")));
+        assert!(records.iter().all(|r| r.mac.is_none()));
+    }
+
+    #[test]
+    fn chain_verifies_and_catches_edits() {
+        let dir = tempfile::tempdir().unwrap();
+        let audit = open(&dir, Some(KEY), MAX_LOG_BYTES);
+        record_n(&audit, 4);
+        assert_eq!(verification(&audit).first_invalid, None);
+
+        rewrite_lines(&audit.path, |lines| {
+            lines[2] = lines[2].replace("profile-2", "profile-9");
+        });
+        let result = verification(&audit);
+        assert!(result.chained);
+        assert_eq!(result.first_invalid, Some(3));
+    }
+
+    #[test]
+    fn stripping_macs_or_leading_records_is_caught() {
+        let dir = tempfile::tempdir().unwrap();
+        let audit = open(&dir, Some(KEY), MAX_LOG_BYTES);
+        record_n(&audit, 3);
+        let original = fs::read_to_string(&audit.path).unwrap();
+
+        rewrite_lines(&audit.path, |lines| {
+            for line in lines.iter_mut() {
+                let mut record: AuditRecord = serde_json::from_str(line).unwrap();
+                record.mac = None;
+                *line = serde_json::to_string(&record).unwrap();
+            }
+        });
+        assert_eq!(verification(&audit).first_invalid, Some(1));
+
+        fs::write(&audit.path, &original).unwrap();
+        rewrite_lines(&audit.path, |lines| {
+            lines.remove(0);
+        });
+        assert_eq!(verification(&audit).first_invalid, Some(1));
+    }
+
+    #[test]
+    fn unparseable_lines_are_invalid() {
+        let dir = tempfile::tempdir().unwrap();
+        let audit = open(&dir, None, MAX_LOG_BYTES);
+        record_n(&audit, 2);
+        rewrite_lines(&audit.path, |lines| lines.insert(1, "{\"timestamp\": ".to_string()));
+
+        let result = verification(&audit);
+        assert_eq!(result.records, 3);
+        assert_eq!(result.first_invalid, Some(2));
+        assert_eq!(read_records(&audit.path).len(), 2);
+    }
+
+    #[test]
+    fn records_before_chaining_stay_unverified() {
+        let dir = tempfile::tempdir().unwrap();
+        record_n(&open(&dir, None, MAX_LOG_BYTES), 2);
+
+        let audit = open(&dir, Some(KEY), MAX_LOG_BYTES);
+        record_n(&audit, 2);
+        let result = verification(&audit);
+        assert_eq!(result.records, 4);
+        assert_eq!(result.first_invalid, None);
+
+        // Deleting the chain state does not exempt the older records again
+        fs::remove_file(sibling(&audit.path, CHAIN_FILE)).unwrap();
+        let reopened = open(&dir, Some(KEY), MAX_LOG_BYTES);
+        assert_eq!(verification(&reopened).first_invalid, Some(1));
+    }
+
+    #[test]
+    fn rotation_keeps_the_chain_verifiable() {
+        let dir = tempfile::tempdir().unwrap();
+        // Small enough that every record starts a new file
+        let audit = open(&dir, Some(KEY), 1);
+        record_n(&audit, 6);
+
+        assert_eq!(log_files(&audit.path).len(), ROTATED_FILES + 1);
+        let records = read_records(&audit.path);
+        assert_eq!(records.len(), ROTATED_FILES + 1);
+        assert_eq!(records[0].target, "profile-2");
+        assert_eq!(verification(&audit).first_invalid, None);
+        assert!(read_chain_state(&audit.path).previous.is_some());
+    }
+
+    #[test]
+    fn failed_writes_are_counted_without_failing_the_caller() {
+        let dir = tempfile::tempdir().unwrap();
+        // The log's directory is a file, so every append fails
+        let blocker = dir.path().join("blocked");
+        fs::write(&blocker, "").unwrap();
+        let audit = AuditLog::start(blocker.join(AUDIT_FILE), None, MAX_LOG_BYTES);
+
+        record_n(&audit, 2);
+        assert_eq!(audit.write_errors.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn filter_matches_action_target_and_time() {
+        let record = AuditRecord {
+            timestamp: "2026-03-01T10:00:00+00:00".to_string(),
+            actor: "analyst".to_string(),
+            action: "switch_model".to_string(),
+            target: "qwen2.5:14b".to_string(),
+            params_hash: String::new(),
+            success: true,
+            error: None,
+            mac: None,
+        };
+        let filter = |action: Option<&str>, target: Option<&str>, since: Option<&str>| AuditFilter {
+            action: action.map(str::to_string),
+            target: target.map(str::to_string),
+            since: since.map(str::to_string),
+        };
+
+        let matches = |action, target, since| filter(action, target, since).matcher().unwrap()(&record);
+
+        assert!(matches(None, None, None));
+        assert!(matches(Some("switch_model"), Some("qwen2.5:14b"), None));
+        assert!(!matches(Some("delete_profile"), None, None));
+        assert!(matches(None, None, Some("2026-03-01T00:00:00+00:00")));
+        assert!(!matches(None, None, Some("2026-03-02T00:00:00+00:00")));
+
+        // Compared as instants, not strings
+        assert!(matches(None, None, Some("2026-03-01T10:00:00Z")));
+        assert!(!matches(None, None, Some("2026-03-01T10:00:01Z")));
+        assert!(matches(None, None, Some("2026-03-01T11:30:00+02:00")));
+        assert!(!matches(None, None, Some("2026-03-01T05:30:00-05:00")));
+
+        let zulu = AuditRecord {
+            timestamp: "2026-03-01T10:00:00Z".to_string(),
+            ..record.clone()
+        };
+        let since = filter(None, None, Some("2026-03-01T12:00:00+02:00")).matcher().unwrap();
+        assert!(since(&zulu));
+    }
+
+    #[test]
+    fn unparseable_since_is_rejected() {
+        for since in ["yesterday", "2026-03-01", "2026-03-01 10:00:00"] {
+            let filter = AuditFilter {
+                since: Some(since.to_string()),
+                ..AuditFilter::default()
+            };
+            let error = filter.matcher().err().unwrap();
+            assert!(error.starts_with("Invalid since timestamp"), "{}", error);
+        }
+    }
+
+    #[test]
+    fn key_file_is_read_and_validated() {
+        let dir = tempfile::tempdir().unwrap();
+        let log_path = dir.path().join(AUDIT_FILE);
+        assert_eq!(load_key(&log_path), None);
+
+        write_private(&sibling(&log_path, KEY_FILE), b"00ff\n").unwrap();
+        assert_eq!(load_key(&log_path), Some(vec![0x00, 0xff]));
+    }
+}
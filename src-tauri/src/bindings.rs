@@ -0,0 +1,366 @@
+// IPC Bindings
+// TypeScript declarations of the types sent to the frontend, and an opt-in runtime check against them
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use serde::Serialize;
+use serde_json::Value;
+use ts_rs::TS;
+
+/// Launch flag that logs every event payload not matching its TypeScript declaration
+pub const VALIDATE_IPC_FLAG: &str = "--validate-ipc";
+
+static VALIDATE: AtomicBool = AtomicBool::new(false);
+
+/// Turn runtime payload validation on or off
+pub fn set_validation(enabled: bool) {
+    if enabled {
+        log::info!("Validating outgoing IPC payloads against their TypeScript declarations");
+    }
+    VALIDATE.store(enabled, Ordering::Relaxed);
+}
+
+/// Log where an outgoing payload differs from the declaration of its type, if validation is on
+pub fn check_outgoing<T: Serialize + TS>(name: &str, payload: &T) {
+    if !VALIDATE.load(Ordering::Relaxed) {
+        return;
+    }
+    match serde_json::to_value(payload) {
+        Ok(value) => {
+            for violation in violations::<T>(&value) {
+                log::warn!("{} payload does not match {}: {}", name, T::name(), violation);
+            }
+        }
+        Err(e) => log::warn!("Failed to serialize {} for validation: {}", name, e),
+    }
+}
+
+/// Ways `value` differs from the TypeScript declaration of `T`. Only the top
+/// level is checked; fields of other named types are accepted as they are.
+pub fn violations<T: TS>(value: &Value) -> Vec<String> {
+    let decl = strip_comments(&T::decl());
+    let body = decl
+        .split_once('=')
+        .map_or("", |(_, body)| body)
+        .trim()
+        .trim_end_matches(';');
+
+    let mut best: Option<Vec<String>> = None;
+    for alternative in split_top_level(body, '|') {
+        let found = check_alternative(alternative.trim(), value);
+        if found.is_empty() {
+            return found;
+        }
+        if best.as_ref().map_or(true, |best| found.len() < best.len()) {
+            best = Some(found);
+        }
+    }
+    best.unwrap_or_default()
+}
+
+fn check_alternative(alternative: &str, value: &Value) -> Vec<String> {
+    let Some(fields) = alternative.strip_prefix('{').and_then(|a| a.strip_suffix('}')) else {
+        return if matches_type(alternative, value) {
+            Vec::new()
+        } else {
+            vec![format!("{} is not {}", kind(value), alternative)]
+        };
+    };
+    let Some(object) = value.as_object() else {
+        return vec![format!("{} is not an object", kind(value))];
+    };
+
+    let mut found = Vec::new();
+    let mut declared = Vec::new();
+    for field in split_top_level(fields, ',') {
+        let Some((name, ty)) = field.split_once(':') else {
+            continue;
+        };
+        let (name, optional) = match name.trim().strip_suffix('?') {
+            Some(name) => (name.trim(), true),
+            None => (name.trim(), false),
+        };
+        let name = name.trim_matches('"');
+        declared.push(name);
+
+        match object.get(name) {
+            Some(value) if !matches_type(ty.trim(), value) => {
+                found.push(format!("field {} is {}, expected {}", name, kind(value), ty.trim()));
+            }
+            Some(_) => {}
+            None if !optional => found.push(format!("missing field {}", name)),
+            None => {}
+        }
+    }
+    for name in object.keys() {
+        if !declared.contains(&name.as_str()) {
+            found.push(format!("unknown field {}", name));
+        }
+    }
+    found
+}
+
+/// Whether `value` fits a field type; named types and generics are not checked
+fn matches_type(ty: &str, value: &Value) -> bool {
+    split_top_level(ty, '|').into_iter().any(|ty| {
+        let ty = ty.trim();
+        match ty {
+            "string" => value.is_string(),
+            "number" => value.is_number(),
+            "boolean" => value.is_boolean(),
+            "null" => value.is_null(),
+            _ if ty.starts_with('"') => value.as_str() == Some(ty.trim_matches('"')),
+            _ if ty.starts_with("Array<") || ty.ends_with("[]") => value.is_array(),
+            _ if ty.starts_with('{') || ty.starts_with("Record<") => value.is_object(),
+            _ => true,
+        }
+    })
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+fn strip_comments(decl: &str) -> String {
+    let mut out = String::with_capacity(decl.len());
+    let mut rest = decl;
+    while let Some(start) = rest.find("/*") {
+        out.push_str(&rest[..start]);
+        rest = rest[start..].split_once("*/").map_or("", |(_, after)| after);
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Split on `separator` outside of brackets and string literals, dropping empty parts
+fn split_top_level(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut quoted = false;
+    let mut start = 0;
+    for (index, c) in text.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '{' | '<' | '(' | '[' if !quoted => depth += 1,
+            '}' | '>' | ')' | ']' if !quoted => depth -= 1,
+            c if c == separator && depth == 0 && !quoted => {
+                parts.push(&text[start..index]);
+                start = index + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+    parts.into_iter().filter(|part| !part.trim().is_empty()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use serde_json::json;
+    use ts_rs::ExportError;
+
+    use crate::events::{OperationProgress, ResumeRecovery};
+    use crate::endpoint::ResponseError;
+    use crate::sidecar::BackendStatus;
+
+    /// Set to write the bindings into the frontend instead of checking them
+    const UPDATE_VAR: &str = "UPDATE_BINDINGS";
+
+    fn committed_dir() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("../src-desktop/bindings")
+    }
+
+    /// Every type crossing the IPC boundary; the types they use are exported with them
+    fn export_all(dir: &Path) -> Result<(), ExportError> {
+        macro_rules! export {
+            ($($ty:ty),* $(,)?) => {
+                $( <$ty as TS>::export_all_to(dir)?; )*
+            };
+        }
+        export![
+            crate::audit::AuditFilter,
+            crate::audit::AuditRecord,
+            crate::audit::AuditVerification,
+            crate::capabilities::ModelCapabilities,
+            crate::documents::DocumentFingerprint,
+            crate::endpoint::BackendConnection,
+            crate::endpoint::ResponseError,
+            crate::events::EventSubscription,
+            crate::events::ModelSwitched,
+            crate::events::ModelsChanged,
+            crate::events::OperationProgress,
+            crate::events::ProfileChanged,
+            crate::events::PullFinished,
+            crate::events::PullLayerProgress,
+            crate::events::PullProgress,
+            crate::events::ResumeRecovery,
+            crate::events::RoutingChanged,
+            crate::events::Versioned<ts_rs::Dummy>,
+            crate::features::BackendCapabilities,
+            crate::followups::FollowupMode,
+            crate::followups::FollowupSource,
+            crate::followups::FollowupSuggestions,
+            crate::hardware::ContentionReport,
+            crate::hardware::DiskSpaceError,
+            crate::models::Fetched<ts_rs::Dummy>,
+            crate::models::HotswapSnapshot,
+            crate::models::ModelList,
+            crate::models::ModelListCheck,
+            crate::models::ModelSwitchResponse,
+            crate::ollama::BenchmarkResult,
+            crate::ollama::LatencyError,
+            crate::ollama::LatencyProfile,
+            crate::ollama::LoadedModel,
+            crate::ollama::OllamaStatus,
+            crate::ollama::ProbeResult,
+            crate::ollama::PullError,
+            crate::ollama::PullLayer,
+            crate::paths::PathError,
+            crate::profiles::ProfileList,
+            crate::proxy::ProxySettings,
+            crate::safe_mode::SafeModeStatus,
+            crate::settings::SettingReset,
+            crate::sidecar::BackendStatus,
+            crate::smoke::SmokeTestReport,
+            crate::startup::StartupReport,
+            crate::titles::ConversationTitle,
+        ];
+        Ok(())
+    }
+
+    fn read_dir(dir: &Path) -> BTreeMap<String, String> {
+        fs::read_dir(dir)
+            .unwrap_or_else(|e| panic!("Failed to read {}: {}", dir.display(), e))
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "ts"))
+            .map(|path| {
+                let name = path.file_name().unwrap().to_string_lossy().into_owned();
+                (name, fs::read_to_string(&path).unwrap())
+            })
+            .collect()
+    }
+
+    /// Files that differ between freshly generated and committed bindings
+    fn stale_files(generated: &Path, committed: &Path) -> Vec<String> {
+        let (generated, committed) = (read_dir(generated), read_dir(committed));
+        let mut stale: Vec<String> = generated
+            .iter()
+            .filter(|(name, content)| committed.get(*name) != Some(content))
+            .map(|(name, _)| name.clone())
+            .collect();
+        stale.extend(committed.keys().filter(|name| !generated.contains_key(*name)).cloned());
+        stale.sort();
+        stale
+    }
+
+    #[test]
+    fn committed_bindings_are_current() {
+        if std::env::var_os(UPDATE_VAR).is_some() {
+            let dir = committed_dir();
+            for name in read_dir(&dir).keys() {
+                fs::remove_file(dir.join(name)).unwrap();
+            }
+            export_all(&dir).expect("export bindings");
+            return;
+        }
+
+        let generated = tempfile::tempdir().unwrap();
+        export_all(generated.path()).expect("export bindings");
+
+        let stale = stale_files(generated.path(), &committed_dir());
+        assert!(
+            stale.is_empty(),
+            "Stale TypeScript bindings: {:?}. Regenerate them with `{}=1 cargo test bindings`",
+            stale,
+            UPDATE_VAR
+        );
+    }
+
+    #[test]
+    fn a_renamed_field_makes_its_binding_stale() {
+        let generated = tempfile::tempdir().unwrap();
+        let committed = tempfile::tempdir().unwrap();
+        export_all(generated.path()).unwrap();
+        export_all(committed.path()).unwrap();
+        assert!(stale_files(generated.path(), committed.path()).is_empty());
+
+        // What the frontend still has after `healthy` is renamed in Rust
+        let path = committed.path().join("BackendStatus.ts");
+        let old = fs::read_to_string(&path).unwrap().replace("healthy: boolean", "is_healthy: boolean");
+        fs::write(&path, old).unwrap();
+        fs::write(committed.path().join("Removed.ts"), "export type Removed = string;\n").unwrap();
+
+        assert_eq!(stale_files(generated.path(), committed.path()), ["BackendStatus.ts", "Removed.ts"]);
+    }
+
+    #[test]
+    fn payloads_matching_their_declaration_pass() {
+        let recovery = ResumeRecovery {
+            suspended_secs: 600,
+            connections_reset: true,
+            health_checks: 2,
+            healthy: true,
+        };
+        assert!(violations::<ResumeRecovery>(&serde_json::to_value(&recovery).unwrap()).is_empty());
+
+        let unreachable = ResponseError::Unreachable {
+            message: "connection refused".to_string(),
+        };
+        assert!(violations::<ResponseError>(&serde_json::to_value(&unreachable).unwrap()).is_empty());
+    }
+
+    #[test]
+    fn a_renamed_field_fails_validation() {
+        let mut status = json!({
+            "running": false,
+            "monitoring": true,
+            "healthy": true,
+            "port": 8000,
+            "last_check": "2026-10-16T12:00:00Z",
+            "error": null,
+            "profile": null,
+        });
+        assert!(violations::<BackendStatus>(&status).is_empty());
+
+        let healthy = status.as_object_mut().unwrap().remove("healthy").unwrap();
+        status["is_healthy"] = healthy;
+        assert_eq!(
+            violations::<BackendStatus>(&status),
+            ["missing field healthy", "unknown field is_healthy"]
+        );
+    }
+
+    #[test]
+    fn wrong_kinds_and_unknown_variants_fail_validation() {
+        let status = json!({
+            "running": "yes",
+            "monitoring": true,
+            "healthy": true,
+            "port": 8000,
+            "last_check": "2026-10-16T12:00:00Z",
+            "error": null,
+            "profile": null,
+        });
+        assert_eq!(
+            violations::<BackendStatus>(&status),
+            ["field running is a string, expected boolean"]
+        );
+
+        let error = json!({"kind": "Timeout", "message": "slow"});
+        assert_eq!(violations::<ResponseError>(&error).len(), 1);
+
+        let progress = json!({"id": "op-1"});
+        assert!(violations::<OperationProgress>(&progress)
+            .contains(&"missing field status".to_string()));
+    }
+}
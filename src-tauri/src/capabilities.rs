@@ -0,0 +1,236 @@
+// Model Capabilities
+// What the active model can do, for gating JSON mode, tools, vision and long context in the UI
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::ollama::{self, ModelDetails};
+
+/// Context window assumed when nothing better is known
+const DEFAULT_MAX_CONTEXT: u64 = 4096;
+
+/// Where the capability data came from, strongest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub enum Confidence {
+    /// Reported by the model runtime (Ollama /api/show)
+    Reported,
+    /// Taken from the curated model family table
+    Curated,
+    /// Conservative defaults for an unknown model
+    Inferred,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ModelCapabilities {
+    pub model_id: String,
+    #[ts(type = "number")]
+    pub max_context: u64,
+    pub supports_json_mode: bool,
+    pub supports_tools: bool,
+    pub supports_vision: bool,
+    pub reasoning_model: bool,
+    pub confidence: Confidence,
+}
+
+/// Partially known capabilities from one source
+#[derive(Debug, Clone, Copy, Default)]
+struct CapabilityHints {
+    max_context: Option<u64>,
+    supports_json_mode: Option<bool>,
+    supports_tools: Option<bool>,
+    supports_vision: Option<bool>,
+    reasoning_model: Option<bool>,
+}
+
+impl CapabilityHints {
+    fn is_empty(&self) -> bool {
+        self.max_context.is_none()
+            && self.supports_json_mode.is_none()
+            && self.supports_tools.is_none()
+            && self.supports_vision.is_none()
+            && self.reasoning_model.is_none()
+    }
+
+    /// Fill fields missing here from a lower-precedence source
+    fn or(self, fallback: CapabilityHints) -> CapabilityHints {
+        CapabilityHints {
+            max_context: self.max_context.or(fallback.max_context),
+            supports_json_mode: self.supports_json_mode.or(fallback.supports_json_mode),
+            supports_tools: self.supports_tools.or(fallback.supports_tools),
+            supports_vision: self.supports_vision.or(fallback.supports_vision),
+            reasoning_model: self.reasoning_model.or(fallback.reasoning_model),
+        }
+    }
+}
+
+/// Curated capabilities keyed by model family; more specific families come first
+const FAMILY_TABLE: &[(&str, u64, bool, bool, bool, bool)] = &[
+    // (family, max_context, json_mode, tools, vision, reasoning)
+    ("llama3.2-vision", 131_072, true, false, true, false),
+    ("llama3.1", 131_072, true, true, false, false),
+    ("llama3.2", 131_072, true, true, false, false),
+    ("llama3", 8_192, true, false, false, false),
+    ("qwen2.5", 32_768, true, true, false, false),
+    ("qwen3", 40_960, true, true, false, true),
+    ("qwq", 40_960, true, true, false, true),
+    ("deepseek-r1", 131_072, true, false, false, true),
+    ("mistral", 32_768, true, true, false, false),
+    ("phi3", 4_096, true, false, false, false),
+    ("gemma2", 8_192, true, false, false, false),
+    ("llava", 4_096, true, false, true, false),
+];
+
+fn curated_hints(model_id: &str) -> CapabilityHints {
+    let id = model_id.to_lowercase();
+
+    FAMILY_TABLE
+        .iter()
+        .find(|(family, ..)| id.contains(family))
+        .map(|&(_, max_context, json, tools, vision, reasoning)| CapabilityHints {
+            max_context: Some(max_context),
+            supports_json_mode: Some(json),
+            supports_tools: Some(tools),
+            supports_vision: Some(vision),
+            reasoning_model: Some(reasoning),
+        })
+        .unwrap_or_default()
+}
+
+fn reported_hints(details: &ModelDetails) -> CapabilityHints {
+    let has = |name: &str| details.capabilities.iter().any(|c| c == name);
+    let listed = !details.capabilities.is_empty();
+
+    CapabilityHints {
+        max_context: details.context_length(),
+        // Ollama's format option works for every completion model
+        supports_json_mode: has("completion").then_some(true),
+        supports_tools: listed.then(|| has("tools")),
+        supports_vision: listed.then(|| has("vision")),
+        reasoning_model: listed.then(|| has("thinking")),
+    }
+}
+
+/// Merge runtime-reported data over the curated table over conservative defaults
+fn merge(model_id: &str, reported: CapabilityHints, curated: CapabilityHints) -> ModelCapabilities {
+    let confidence = if !reported.is_empty() {
+        Confidence::Reported
+    } else if !curated.is_empty() {
+        Confidence::Curated
+    } else {
+        Confidence::Inferred
+    };
+    let hints = reported.or(curated);
+
+    ModelCapabilities {
+        model_id: model_id.to_string(),
+        max_context: hints.max_context.unwrap_or(DEFAULT_MAX_CONTEXT),
+        supports_json_mode: hints.supports_json_mode.unwrap_or(false),
+        supports_tools: hints.supports_tools.unwrap_or(false),
+        supports_vision: hints.supports_vision.unwrap_or(false),
+        reasoning_model: hints.reasoning_model.unwrap_or(false),
+        confidence,
+    }
+}
+
+/// Resolve capabilities for a model id, querying Ollama when it knows the model
+pub async fn model_capabilities(model_id: &str) -> ModelCapabilities {
+    let reported = match ollama::show_model(model_id).await {
+        Ok(details) => reported_hints(&details),
+        Err(e) => {
+            log::debug!("No runtime capability data for {}: {}", model_id, e);
+            CapabilityHints::default()
+        }
+    };
+
+    merge(model_id, reported, curated_hints(model_id))
+}
+
+// Tauri Commands
+
+#[tauri::command]
+pub async fn get_model_capabilities(model_id: String) -> ModelCapabilities {
+    model_capabilities(&model_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn details(value: serde_json::Value) -> ModelDetails {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn resolve(model_id: &str, reported: Option<serde_json::Value>) -> ModelCapabilities {
+        let reported = reported.map(|value| reported_hints(&details(value))).unwrap_or_default();
+        merge(model_id, reported, curated_hints(model_id))
+    }
+
+    #[test]
+    fn reported_data_beats_the_curated_table() {
+        // The table says llama3 has an 8k context and no tools
+        let caps = resolve(
+            "llama3:8b",
+            Some(json!({
+                "capabilities": ["completion", "tools"],
+                "model_info": {"llama.context_length": 16384},
+            })),
+        );
+        assert_eq!(caps.confidence, Confidence::Reported);
+        assert_eq!(caps.max_context, 16_384);
+        assert!(caps.supports_tools);
+        assert!(caps.supports_json_mode);
+        assert!(!caps.supports_vision);
+    }
+
+    #[test]
+    fn curated_table_fills_what_the_runtime_leaves_out() {
+        // Older Ollama versions report the context length but no capability list
+        let caps = resolve(
+            "qwen3:14b",
+            Some(json!({"model_info": {"qwen3.context_length": 32768}})),
+        );
+        assert_eq!(caps.confidence, Confidence::Reported);
+        assert_eq!(caps.max_context, 32_768);
+        assert!(caps.supports_tools);
+        assert!(caps.reasoning_model);
+    }
+
+    #[test]
+    fn curated_table_is_used_when_the_runtime_knows_nothing() {
+        let caps = resolve("Llama3.2-Vision:11b", None);
+        assert_eq!(caps.confidence, Confidence::Curated);
+        // The vision entry wins over the shorter llama3.2 family
+        assert!(caps.supports_vision);
+        assert!(!caps.supports_tools);
+        assert_eq!(caps.max_context, 131_072);
+
+        let caps = resolve("deepseek-r1:7b", Some(json!({})));
+        assert_eq!(caps.confidence, Confidence::Curated);
+        assert!(caps.reasoning_model);
+    }
+
+    #[test]
+    fn unknown_models_get_conservative_defaults() {
+        let caps = resolve("my-finetune:latest", None);
+        assert_eq!(caps.confidence, Confidence::Inferred);
+        assert_eq!(caps.model_id, "my-finetune:latest");
+        assert_eq!(caps.max_context, DEFAULT_MAX_CONTEXT);
+        assert!(!caps.supports_json_mode);
+        assert!(!caps.supports_tools);
+        assert!(!caps.supports_vision);
+        assert!(!caps.reasoning_model);
+    }
+
+    #[test]
+    fn listed_capabilities_override_the_table() {
+        let caps = resolve(
+            "llava:7b",
+            Some(json!({"capabilities": ["completion"], "model_info": {}})),
+        );
+        // Listed capabilities are authoritative, so the curated vision flag is overridden
+        assert!(!caps.supports_vision);
+        assert!(caps.supports_json_mode);
+        assert_eq!(caps.max_context, 4_096);
+    }
+}
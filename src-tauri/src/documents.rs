@@ -0,0 +1,220 @@
+// Documents
+// Content fingerprints for recognizing the same document under another name
+
+use std::fs::File;
+use std::io::{ErrorKind, Read};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::AppHandle;
+use ts_rs::TS;
+
+use crate::paths::{self, PathError};
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct DocumentFingerprint {
+    /// Hex SHA-256 of the (normalized) content
+    pub fingerprint: String,
+    /// Whether the content was text and had its whitespace normalized
+    pub normalized: bool,
+    #[ts(type = "number")]
+    pub size_bytes: u64,
+}
+
+/// Read size when streaming a file through the hashers
+const CHUNK_BYTES: usize = 64 * 1024;
+
+/// Hashes text with runs of whitespace collapsed to one space, fed in pieces
+#[derive(Default)]
+struct NormalizedHasher {
+    hasher: Sha256,
+    /// Any text fed yet; a BOM only counts at the very start
+    fed: bool,
+    started: bool,
+    in_word: bool,
+}
+
+impl NormalizedHasher {
+    fn update(&mut self, mut text: &str) {
+        if !self.fed && !text.is_empty() {
+            text = text.strip_prefix('\u{feff}').unwrap_or(text);
+            self.fed = true;
+        }
+
+        let mut word_start = None;
+        for (i, c) in text.char_indices() {
+            if c.is_whitespace() {
+                if let Some(start) = word_start.take() {
+                    self.hasher.update(&text.as_bytes()[start..i]);
+                }
+                self.in_word = false;
+            } else if word_start.is_none() {
+                if !self.in_word && self.started {
+                    self.hasher.update(b" ");
+                }
+                self.started = true;
+                self.in_word = true;
+                word_start = Some(i);
+            }
+        }
+        if let Some(start) = word_start {
+            self.hasher.update(&text.as_bytes()[start..]);
+        }
+    }
+}
+
+/// Fingerprint file content. Text is hashed with line endings, a leading BOM and
+/// runs of whitespace normalized, so re-saving in another editor keeps the id;
+/// binary formats (PDF, DOCX) are hashed byte for byte. Content is streamed in
+/// chunks and the text hash dropped at the first invalid UTF-8 sequence.
+pub fn fingerprint(mut reader: impl Read) -> std::io::Result<DocumentFingerprint> {
+    let mut raw = Sha256::new();
+    let mut text = Some(NormalizedHasher::default());
+    // Bytes of a UTF-8 sequence split across two reads
+    let mut pending: Vec<u8> = Vec::new();
+    let mut buffer = vec![0u8; CHUNK_BYTES];
+    let mut size_bytes = 0u64;
+
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        let chunk = &buffer[..read];
+        raw.update(chunk);
+        size_bytes += read as u64;
+
+        let Some(hasher) = text.as_mut() else {
+            continue;
+        };
+        pending.extend_from_slice(chunk);
+        let valid = match std::str::from_utf8(&pending) {
+            Ok(_) => pending.len(),
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(_) => {
+                text = None;
+                continue;
+            }
+        };
+        if let Ok(decoded) = std::str::from_utf8(&pending[..valid]) {
+            hasher.update(decoded);
+        }
+        pending.drain(..valid);
+    }
+
+    // A sequence cut off by the end of the file is not text either
+    let (digest, normalized) = match text {
+        Some(text) if pending.is_empty() => (text.hasher.finalize(), true),
+        _ => (raw.finalize(), false),
+    };
+
+    Ok(DocumentFingerprint {
+        fingerprint: hex::encode(digest),
+        normalized,
+        size_bytes,
+    })
+}
+
+// Tauri Commands
+
+#[tauri::command]
+pub async fn document_fingerprint(app: AppHandle, path: String) -> Result<DocumentFingerprint, PathError> {
+    let resolved = paths::authorize(&app, &path)?;
+    let io_error = |message: String| PathError::Io {
+        path: path.clone(),
+        message,
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        File::open(&resolved)
+            .and_then(fingerprint)
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| io_error(format!("Failed to fingerprint document: {}", e)))?
+    .map_err(io_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reader that hands out at most `step` bytes per call
+    struct Trickle<'a> {
+        content: &'a [u8],
+        step: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let n = self.step.min(buf.len()).min(self.content.len());
+            buf[..n].copy_from_slice(&self.content[..n]);
+            self.content = &self.content[n..];
+            Ok(n)
+        }
+    }
+
+    fn of(content: &[u8]) -> DocumentFingerprint {
+        fingerprint(content).unwrap()
+    }
+
+    #[test]
+    fn text_ignores_line_endings_bom_and_whitespace_runs() {
+        let unix = of("Section 1\n  storage\tlimits  \n".as_bytes());
+        let windows = of("\u{feff}Section 1\r\nstorage limits\r\n\r\n".as_bytes());
+
+        assert!(unix.normalized && windows.normalized);
+        assert_eq!(unix.fingerprint, windows.fingerprint);
+        assert_eq!(unix.fingerprint, hex::encode(Sha256::digest(b"Section 1 storage limits")));
+        assert_ne!(unix.size_bytes, windows.size_bytes);
+        assert_ne!(unix.fingerprint, of(b"Section 1 storage limit").fingerprint);
+    }
+
+    #[test]
+    fn only_a_leading_bom_is_dropped() {
+        assert_ne!(of(" \u{feff}a".as_bytes()).fingerprint, of(b"a").fingerprint);
+    }
+
+    #[test]
+    fn binary_content_is_hashed_byte_for_byte() {
+        let pdf = b"%PDF-1.7\n\xff\xfe binary  body";
+        let result = of(pdf);
+        assert!(!result.normalized);
+        assert_eq!(result.fingerprint, hex::encode(Sha256::digest(pdf)));
+        assert_eq!(result.size_bytes, pdf.len() as u64);
+
+        // Cut off partway through a multi-byte character
+        let truncated = &"caf\u{e9}".as_bytes()[..4];
+        assert_eq!(of(truncated).fingerprint, hex::encode(Sha256::digest(truncated)));
+    }
+
+    #[test]
+    fn chunking_does_not_change_the_fingerprint() {
+        let text = "\u{feff}Caf\u{e9} r\u{e9}sum\u{e9}\u{3000}\u{1f600}  Fort   Liberty\r\n".repeat(3);
+        let binary = [text.as_bytes(), b"\xff"].concat();
+
+        for content in [text.as_bytes(), &binary[..]] {
+            let whole = of(content);
+            for step in 1..=8 {
+                let trickled = fingerprint(Trickle { content, step }).unwrap();
+                assert_eq!(trickled.fingerprint, whole.fingerprint, "step {}", step);
+                assert_eq!(trickled.normalized, whole.normalized);
+                assert_eq!(trickled.size_bytes, content.len() as u64);
+            }
+        }
+    }
+
+    #[test]
+    fn files_larger_than_one_chunk_are_streamed() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("large.txt");
+        let line = "word ".repeat(1000) + "\n";
+        std::fs::write(&path, line.repeat(2 * CHUNK_BYTES / line.len() + 1)).unwrap();
+
+        let streamed = fingerprint(File::open(&path).unwrap()).unwrap();
+        assert_eq!(streamed.fingerprint, of(&std::fs::read(&path).unwrap()).fingerprint);
+        assert!(streamed.normalized);
+        assert!(streamed.size_bytes > 2 * CHUNK_BYTES as u64);
+    }
+}
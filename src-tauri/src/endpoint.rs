@@ -0,0 +1,582 @@
+// Backend Endpoint
+// Single source of truth for how the app reaches the backend
+
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use reqwest::{Method, RequestBuilder, Response, Url};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use tauri::AppHandle;
+
+use crate::audit::AuditLog;
+use crate::events::RoutingChanged;
+use crate::profiles::{self, ConnectionProfile};
+use crate::proxy;
+use crate::settings::{self, SettingsStore, Validator};
+
+pub const DEFAULT_BACKEND_URL: &str = "http://localhost:8000";
+
+/// Largest backend response body buffered into memory by default
+pub const DEFAULT_MAX_RESPONSE_BYTES: u64 = 16 * 1024 * 1024;
+
+const BACKEND_URL_KEY: &str = "backend_url";
+
+/// One backend instance a connection can route to
+#[derive(Debug, Clone)]
+struct Replica {
+    url: Url,
+    priority: u32,
+    /// Last health observed by the poller; `None` until first checked
+    healthy: Option<bool>,
+}
+
+#[derive(Debug, Clone)]
+struct Connection {
+    /// Sorted by priority, most preferred first
+    replicas: Vec<Replica>,
+    active: usize,
+    sticky: bool,
+    auth_token: Option<String>,
+    accept_invalid_certs: bool,
+    profile: Option<String>,
+    client: reqwest::Client,
+}
+
+impl Connection {
+    fn new(url: Url, auth_token: Option<String>, accept_invalid_certs: bool, profile: Option<String>) -> Self {
+        Self {
+            client: build_client(accept_invalid_certs),
+            replicas: vec![Replica {
+                url,
+                priority: 0,
+                healthy: None,
+            }],
+            active: 0,
+            sticky: false,
+            auth_token,
+            accept_invalid_certs,
+            profile,
+        }
+    }
+
+    fn url(&self) -> &Url {
+        &self.replicas[self.active].url
+    }
+
+    /// Pick the replica requests should use given the latest health observations
+    fn choose_active(&self) -> usize {
+        let current_healthy = self.replicas[self.active].healthy == Some(true);
+        if self.sticky && current_healthy {
+            return self.active;
+        }
+
+        self.replicas
+            .iter()
+            .position(|r| r.healthy == Some(true))
+            .unwrap_or(self.active)
+    }
+}
+
+fn build_client(accept_invalid_certs: bool) -> reqwest::Client {
+    proxy::client_builder()
+        .danger_accept_invalid_certs(accept_invalid_certs)
+        .build()
+        .unwrap_or_else(|e| {
+            log::warn!("Failed to build backend HTTP client, using defaults: {}", e);
+            reqwest::Client::new()
+        })
+}
+
+/// Shared, updatable backend connection (base URL, auth, TLS options).
+///
+/// Clones share the same underlying state, so components holding a clone
+/// (health monitor, commands) pick up changes on their next request.
+#[derive(Debug, Clone)]
+pub struct BackendEndpoint {
+    connection: Arc<RwLock<Connection>>,
+}
+
+impl Default for BackendEndpoint {
+    fn default() -> Self {
+        Self::new(Url::parse(DEFAULT_BACKEND_URL).expect("default backend URL is valid"))
+    }
+}
+
+impl BackendEndpoint {
+    pub fn new(url: Url) -> Self {
+        Self {
+            connection: Arc::new(RwLock::new(Connection::new(url, None, false, None))),
+        }
+    }
+
+    /// Load the endpoint from the active profile or persisted URL, falling back to the default
+    pub fn load(app: &impl SettingsStore) -> Self {
+        if let Some(profile) = profiles::active_profile(app) {
+            let endpoint = Self::default();
+            match endpoint.apply_profile(&profile) {
+                Ok(()) => {
+                    log::info!("Using connection profile {:?}", profile.name);
+                    return endpoint;
+                }
+                Err(e) => log::warn!("Ignoring active profile {:?}: {}", profile.name, e),
+            }
+        }
+
+        match settings::get::<String>(app, BACKEND_URL_KEY).map(|url| parse_backend_url(&url)) {
+            Some(Ok(url)) => {
+                log::info!("Using persisted backend URL: {}", url);
+                Self::new(url)
+            }
+            Some(Err(e)) => {
+                log::warn!("Ignoring persisted backend URL: {}", e);
+                Self::default()
+            }
+            None => Self::default(),
+        }
+    }
+
+    /// Base URL of the replica requests are currently routed to
+    pub fn get(&self) -> Url {
+        self.connection.read().unwrap().url().clone()
+    }
+
+    /// Base URLs of every replica, most preferred first
+    pub fn replica_urls(&self) -> Vec<Url> {
+        let connection = self.connection.read().unwrap();
+        connection.replicas.iter().map(|r| r.url.clone()).collect()
+    }
+
+    /// Record a replica's health and re-route if needed, returning the change if one happened
+    pub fn record_health(&self, url: &Url, healthy: bool) -> Option<RoutingChanged> {
+        let mut connection = self.connection.write().unwrap();
+        let replica = connection.replicas.iter_mut().find(|r| &r.url == url)?;
+        replica.healthy = Some(healthy);
+
+        let active = connection.choose_active();
+        if active == connection.active {
+            return None;
+        }
+
+        let from = connection.url().to_string();
+        connection.active = active;
+        let to = connection.url().to_string();
+        log::info!("Routing backend requests from {} to {}", from, to);
+
+        Some(RoutingChanged {
+            profile: connection.profile.clone(),
+            from,
+            to,
+        })
+    }
+
+    /// Replace the base URL, detaching from any active profile
+    pub fn set(&self, url: Url) {
+        let mut connection = self.connection.write().unwrap();
+        *connection = Connection::new(url, None, false, None);
+    }
+
+    /// Atomically switch URL, auth and TLS options to those of a profile
+    pub fn apply_profile(&self, profile: &ConnectionProfile) -> Result<(), String> {
+        let url = parse_backend_url(&profile.backend_url)?;
+        let mut replicas = profile
+            .replicas
+            .iter()
+            .map(|replica| {
+                Ok(Replica {
+                    url: parse_backend_url(&replica.url)?,
+                    priority: replica.priority,
+                    healthy: None,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let mut updated = Connection::new(
+            url,
+            profile.auth_token.clone(),
+            profile.accept_invalid_certs,
+            Some(profile.name.clone()),
+        );
+        updated.replicas.append(&mut replicas);
+        updated.replicas.sort_by_key(|r| r.priority);
+        updated.active = 0;
+        updated.sticky = profile.sticky;
+
+        *self.connection.write().unwrap() = updated;
+        Ok(())
+    }
+
+    /// Drop pooled connections (e.g. stale after system sleep) by rebuilding the client
+    pub fn reset_connections(&self) {
+        let mut connection = self.connection.write().unwrap();
+        connection.client = build_client(connection.accept_invalid_certs);
+    }
+
+    /// Name of the connection profile in use, if any
+    pub fn profile(&self) -> Option<String> {
+        self.connection.read().unwrap().profile.clone()
+    }
+
+    /// Bearer token of the connection profile in use, if it has one
+    pub fn auth_token(&self) -> Option<String> {
+        self.connection.read().unwrap().auth_token.clone()
+    }
+
+    /// Build an authorized request to an API path on the current backend
+    pub fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let base = self.get();
+        self.request_to(&base, method, path)
+    }
+
+    /// Build an authorized request to a specific replica, bypassing routing
+    pub fn request_to(&self, base: &Url, method: Method, path: &str) -> RequestBuilder {
+        let connection = self.connection.read().unwrap();
+        let builder = connection.client.request(method, resolve(base, path));
+
+        match &connection.auth_token {
+            Some(token) => builder.bearer_auth(token),
+            None => builder,
+        }
+    }
+
+    /// Port of the current base URL, including scheme defaults
+    pub fn port(&self) -> u16 {
+        self.get().port_or_known_default().unwrap_or(80)
+    }
+}
+
+/// Where the webview sends its own backend requests, and with which token
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct BackendConnection {
+    pub url: String,
+    pub auth_token: Option<String>,
+    pub profile: Option<String>,
+}
+
+/// Error calling the backend, returned as-is by commands so the frontend can tell
+/// an oversized response from an unreachable or failing backend
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(tag = "kind")]
+pub enum ResponseError {
+    /// The request never got a response (connection refused, timeout, TLS failure)
+    Unreachable { message: String },
+    /// The backend answered with an error status or declined the request
+    Rejected { message: String },
+    /// The body exceeded the size limit; `bytes` is the declared Content-Length
+    /// when `declared` is set, otherwise the number of bytes read before aborting
+    ResponseTooLarge {
+        url: String,
+        #[ts(type = "number")]
+        limit: u64,
+        #[ts(type = "number")]
+        bytes: u64,
+        declared: bool,
+    },
+    /// The body could not be read or parsed
+    Invalid { message: String },
+}
+
+impl ResponseError {
+    /// A request that failed before any response arrived
+    pub fn unreachable(error: impl fmt::Display) -> Self {
+        ResponseError::Unreachable {
+            message: format!("Failed to reach backend: {}", error),
+        }
+    }
+}
+
+impl fmt::Display for ResponseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResponseError::Unreachable { message } | ResponseError::Rejected { message } => {
+                write!(f, "{}", message)
+            }
+            ResponseError::ResponseTooLarge { url, limit, bytes, .. } => write!(
+                f,
+                "Response from {} too large: {} bytes exceeds the {} byte limit",
+                url, bytes, limit
+            ),
+            ResponseError::Invalid { message } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<ResponseError> for String {
+    fn from(error: ResponseError) -> Self {
+        error.to_string()
+    }
+}
+
+/// Read and deserialize a JSON body, aborting as soon as it exceeds `limit` bytes
+pub async fn read_json_limited<T: DeserializeOwned>(
+    mut response: Response,
+    limit: u64,
+) -> Result<T, ResponseError> {
+    let url = response.url().to_string();
+
+    if let Some(declared) = response.content_length() {
+        if declared > limit {
+            log::warn!("Refusing {} byte response from {} (limit {})", declared, url, limit);
+            return Err(ResponseError::ResponseTooLarge {
+                url,
+                limit,
+                bytes: declared,
+                declared: true,
+            });
+        }
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| ResponseError::Invalid {
+        message: format!("Failed to read response from {}: {}", url, e),
+    })? {
+        let read = (body.len() + chunk.len()) as u64;
+        if read > limit {
+            log::warn!("Aborted response from {} after {} bytes (limit {})", url, read, limit);
+            return Err(ResponseError::ResponseTooLarge {
+                url,
+                limit,
+                bytes: read,
+                declared: false,
+            });
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    serde_json::from_slice(&body).map_err(|e| ResponseError::Invalid {
+        message: format!("Failed to parse response from {}: {}", url, e),
+    })
+}
+
+/// Resolve an API path (e.g. "/api/health") against a base URL
+fn resolve(base: &Url, path: &str) -> Url {
+    let mut url = base.clone();
+    let prefix = url.path().trim_end_matches('/').to_string();
+    url.set_path(&format!("{}/{}", prefix, path.trim_start_matches('/')));
+    url
+}
+
+/// Validate a user-supplied backend URL
+pub fn parse_backend_url(url: &str) -> Result<Url, String> {
+    let parsed = Url::parse(url.trim()).map_err(|e| format!("Invalid backend URL {:?}: {}", url, e))?;
+
+    match parsed.scheme() {
+        "http" | "https" => {}
+        other => return Err(format!("Unsupported backend URL scheme: {}", other)),
+    }
+    if parsed.host_str().is_none() {
+        return Err(format!("Backend URL has no host: {}", url));
+    }
+
+    Ok(parsed)
+}
+
+/// Persisted settings owned by this module
+pub const SETTINGS: &[Validator] = &[Validator {
+    key: BACKEND_URL_KEY,
+    check: check_backend_url,
+}];
+
+fn check_backend_url(value: &serde_json::Value) -> Result<Option<serde_json::Value>, String> {
+    parse_backend_url(&settings::parse::<String>(value)?)?;
+    Ok(None)
+}
+
+// Tauri Commands
+
+#[tauri::command]
+pub fn get_backend_url(endpoint: tauri::State<'_, BackendEndpoint>) -> String {
+    endpoint.get().to_string()
+}
+
+/// Current backend URL plus the active profile's token, for requests made by the webview
+#[tauri::command]
+pub fn get_backend_connection(endpoint: tauri::State<'_, BackendEndpoint>) -> BackendConnection {
+    BackendConnection {
+        url: endpoint.get().to_string(),
+        auth_token: endpoint.auth_token(),
+        profile: endpoint.profile(),
+    }
+}
+
+#[tauri::command]
+pub fn set_backend_url(
+    app: AppHandle,
+    endpoint: tauri::State<'_, BackendEndpoint>,
+    audit: tauri::State<'_, AuditLog>,
+    url: String,
+) -> Result<(), String> {
+    let result = parse_backend_url(&url).and_then(|parsed| {
+        settings::set(&app, BACKEND_URL_KEY, &parsed.to_string())?;
+        profiles::clear_active(&app)?;
+
+        log::info!("Backend URL changed to {}", parsed);
+        endpoint.set(parsed);
+        Ok(())
+    });
+
+    audit.record("set_backend_url", &url, &url, &result);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use crate::profiles::BackendReplica;
+    use crate::test_support::{MockResponse, MockServer};
+
+    fn endpoint_with(sticky: bool) -> BackendEndpoint {
+        let endpoint = BackendEndpoint::default();
+        endpoint
+            .apply_profile(&ConnectionProfile {
+                name: "lab".to_string(),
+                backend_url: "http://primary:8000".to_string(),
+                auth_token: None,
+                accept_invalid_certs: false,
+                default_collection: None,
+                replicas: vec![
+                    BackendReplica {
+                        url: "http://tertiary:8000".to_string(),
+                        priority: 2,
+                    },
+                    BackendReplica {
+                        url: "http://secondary:8000".to_string(),
+                        priority: 1,
+                    },
+                ],
+                sticky,
+            })
+            .unwrap();
+        endpoint
+    }
+
+    fn url(host: &str) -> Url {
+        Url::parse(&format!("http://{}:8000/", host)).unwrap()
+    }
+
+    fn host(endpoint: &BackendEndpoint) -> String {
+        endpoint.get().host_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn replicas_are_ordered_by_priority() {
+        let endpoint = endpoint_with(false);
+        let hosts: Vec<String> = endpoint
+            .replica_urls()
+            .iter()
+            .map(|u| u.host_str().unwrap().to_string())
+            .collect();
+        assert_eq!(hosts, ["primary", "secondary", "tertiary"]);
+        assert_eq!(host(&endpoint), "primary");
+    }
+
+    #[test]
+    fn fails_over_to_the_most_preferred_healthy_replica_and_back() {
+        let endpoint = endpoint_with(false);
+        assert!(endpoint.record_health(&url("tertiary"), true).is_some());
+        assert_eq!(host(&endpoint), "tertiary");
+
+        // A more preferred replica recovering takes over again
+        let change = endpoint.record_health(&url("secondary"), true).unwrap();
+        assert_eq!(change.profile.as_deref(), Some("lab"));
+        assert_eq!((change.from.as_str(), change.to.as_str()), ("http://tertiary:8000/", "http://secondary:8000/"));
+
+        assert!(endpoint.record_health(&url("primary"), true).is_some());
+        assert_eq!(host(&endpoint), "primary");
+        assert!(endpoint.record_health(&url("primary"), false).is_some());
+        assert_eq!(host(&endpoint), "secondary");
+    }
+
+    #[test]
+    fn sticky_routing_stays_on_a_healthy_replica() {
+        let endpoint = endpoint_with(true);
+        endpoint.record_health(&url("primary"), false);
+        endpoint.record_health(&url("secondary"), true);
+        assert_eq!(host(&endpoint), "secondary");
+
+        assert!(endpoint.record_health(&url("primary"), true).is_none());
+        assert_eq!(host(&endpoint), "secondary");
+
+        // Once it fails, the most preferred healthy replica is chosen
+        assert!(endpoint.record_health(&url("secondary"), false).is_some());
+        assert_eq!(host(&endpoint), "primary");
+    }
+
+    #[test]
+    fn stays_put_when_nothing_is_healthy_or_the_url_is_unknown() {
+        let endpoint = endpoint_with(false);
+        for replica in ["primary", "secondary", "tertiary"] {
+            assert!(endpoint.record_health(&url(replica), false).is_none());
+        }
+        assert_eq!(host(&endpoint), "primary");
+        assert!(endpoint.record_health(&url("elsewhere"), true).is_none());
+    }
+
+    #[test]
+    fn paths_are_joined_under_the_base_path() {
+        let base = Url::parse("http://host:8000/rag/").unwrap();
+        assert_eq!(resolve(&base, "/api/health").as_str(), "http://host:8000/rag/api/health");
+        assert_eq!(resolve(&url("host"), "api/query").as_str(), "http://host:8000/api/query");
+    }
+
+    #[test]
+    fn only_http_urls_with_a_host_are_accepted() {
+        assert!(parse_backend_url(" https://rag.internal:8443 ").is_ok());
+        assert!(parse_backend_url("ftp://rag.internal").is_err());
+        assert!(parse_backend_url("file:///tmp/backend").is_err());
+        assert!(parse_backend_url("localhost:8000").is_err());
+    }
+
+    async fn read_from(response: MockResponse, limit: u64) -> Result<serde_json::Value, ResponseError> {
+        let server = MockServer::start(move |_| response.clone()).await;
+        let response = reqwest::get(&server.url).await.unwrap();
+        read_json_limited(response, limit).await
+    }
+
+    #[tokio::test]
+    async fn a_declared_oversized_body_is_refused_before_reading() {
+        let oversized = "x".repeat(DEFAULT_MAX_RESPONSE_BYTES as usize + 1);
+
+        let error = read_from(MockResponse::text(200, &oversized), DEFAULT_MAX_RESPONSE_BYTES)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            ResponseError::ResponseTooLarge {
+                limit: DEFAULT_MAX_RESPONSE_BYTES,
+                bytes,
+                declared: true,
+                ..
+            } if bytes == DEFAULT_MAX_RESPONSE_BYTES + 1
+        ));
+        let serialized = serde_json::to_value(&error).unwrap();
+        assert_eq!(serialized["kind"], "ResponseTooLarge");
+        assert_eq!(serialized["limit"], DEFAULT_MAX_RESPONSE_BYTES);
+    }
+
+    #[tokio::test]
+    async fn an_undeclared_oversized_body_stops_at_the_limit() {
+        // 64 KiB streamed without a Content-Length, against a 4 KiB limit
+        let chunks = vec![vec![b' '; 1024]; 64];
+        let response = MockResponse::chunks(chunks).chunk_delay(Duration::from_millis(5));
+
+        let error = read_from(response, 4096).await.unwrap_err();
+        match error {
+            ResponseError::ResponseTooLarge { limit, bytes, declared, .. } => {
+                assert_eq!(limit, 4096);
+                assert!(!declared);
+                assert!(bytes > 4096 && bytes <= 4096 + 1024, "read {} bytes", bytes);
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn bodies_within_the_limit_are_parsed() {
+        let chunks = vec![b"{\"status\":".to_vec(), b"\"healthy\"}".to_vec()];
+
+        let body = read_from(MockResponse::chunks(chunks), 1024).await.unwrap();
+        assert_eq!(body["status"], "healthy");
+    }
+}
@@ -0,0 +1,851 @@
+// App Events
+// Typed, versioned payloads for every event emitted to the frontend
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager};
+use ts_rs::TS;
+
+use crate::bindings;
+use crate::capabilities::ModelCapabilities;
+use crate::ollama::{PullError, PullLayer};
+
+/// Version of the event payload contract; bump on any breaking payload change
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// An event payload with the name the frontend listens for
+pub trait AppEvent: Serialize + Clone + TS {
+    const NAME: &'static str;
+
+    /// What the payload reports on (e.g. an operation id); throttling only
+    /// coalesces payloads with the same key
+    fn stream_key(&self) -> Option<String> {
+        None
+    }
+
+    /// Whether this is the last payload for its stream; such payloads are never
+    /// throttled, and any older payload still waiting is dropped
+    fn is_terminal(&self) -> bool {
+        false
+    }
+}
+
+/// Envelope sent to the frontend: the payload's fields plus `schema_version`
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct Versioned<T> {
+    pub schema_version: u32,
+    #[serde(flatten)]
+    #[ts(flatten)]
+    pub payload: T,
+}
+
+/// Where events are sent; the app handle outside of tests
+pub trait EventSink {
+    fn emit_event<E: AppEvent>(&self, payload: E);
+}
+
+impl EventSink for AppHandle {
+    fn emit_event<E: AppEvent>(&self, payload: E) {
+        emit(self, payload);
+    }
+}
+
+/// Emit a typed event to every window subscribed to it, logging (not failing) on error
+pub fn emit<E: AppEvent>(app: &AppHandle, payload: E) {
+    bindings::check_outgoing(E::NAME, &payload);
+    let stream = StreamId {
+        name: E::NAME,
+        key: payload.stream_key(),
+    };
+    let terminal = payload.is_terminal();
+    let event = Versioned {
+        schema_version: SCHEMA_VERSION,
+        payload,
+    };
+    let Some(registry) = app.try_state::<EventRegistry>() else {
+        if let Err(e) = app.emit(E::NAME, event) {
+            log::warn!("Failed to emit {}: {}", E::NAME, e);
+        }
+        return;
+    };
+
+    let value = match serde_json::to_value(&event) {
+        Ok(value) => value,
+        Err(e) => {
+            log::warn!("Failed to serialize {}: {}", E::NAME, e);
+            return;
+        }
+    };
+    for label in app.webview_windows().into_keys() {
+        match registry.route(&label, &stream, terminal, &value) {
+            Delivery::Now => send(app, &label, E::NAME, &value),
+            Delivery::Later(delay) => schedule_flush(app.clone(), label, stream.clone(), delay),
+            Delivery::Skip => {}
+        }
+    }
+}
+
+fn send(app: &AppHandle, label: &str, name: &str, value: &serde_json::Value) {
+    if let Err(e) = app.emit_to(label, name, value) {
+        log::warn!("Failed to emit {} to {}: {}", name, label, e);
+    }
+}
+
+/// Deliver the latest coalesced payload once the throttle interval has passed
+fn schedule_flush(app: AppHandle, label: String, stream: StreamId, delay: Duration) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(delay).await;
+        let pending = app
+            .try_state::<EventRegistry>()
+            .and_then(|registry| registry.take_pending(&label, &stream));
+        if let Some(value) = pending {
+            send(&app, &label, stream.name, &value);
+        }
+    });
+}
+
+/// A topic a window wants to receive
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct EventSubscription {
+    /// Event name, or a prefix ending in `*` (e.g. "ollama-*"); "*" matches every event
+    pub topic: String,
+    /// Deliver at most this many events per second, coalescing to the latest payload
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub max_per_sec: Option<u32>,
+}
+
+fn topic_matches(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => pattern == name,
+    }
+}
+
+/// An event name plus the payload's stream key
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct StreamId {
+    name: &'static str,
+    key: Option<String>,
+}
+
+/// Per-stream throttle state within one subscription
+#[derive(Default)]
+struct Stream {
+    last_sent: Option<Instant>,
+    pending: Option<serde_json::Value>,
+}
+
+struct Route {
+    subscription: EventSubscription,
+    streams: HashMap<StreamId, Stream>,
+}
+
+impl Route {
+    fn interval(&self) -> Option<Duration> {
+        self.subscription
+            .max_per_sec
+            .map(|rate| Duration::from_secs(1) / rate)
+    }
+}
+
+/// Most specific route for an event: an exact topic beats the longest matching wildcard
+fn best_route<'a>(routes: &'a mut [Route], name: &str) -> Option<&'a mut Route> {
+    routes
+        .iter_mut()
+        .filter(|route| topic_matches(&route.subscription.topic, name))
+        .max_by_key(|route| {
+            let topic = &route.subscription.topic;
+            (!topic.ends_with('*'), topic.len())
+        })
+}
+
+enum Delivery {
+    Now,
+    /// Throttled; deliver the pending payload after this delay
+    Later(Duration),
+    /// Not subscribed, or coalesced into an already scheduled delivery
+    Skip,
+}
+
+/// Which windows receive which events.
+///
+/// Windows that never subscribed receive everything, so existing listeners keep
+/// working; once a window subscribes it only receives its topics.
+#[derive(Default)]
+pub struct EventRegistry {
+    windows: Mutex<HashMap<String, Vec<Route>>>,
+}
+
+impl EventRegistry {
+    /// Add or replace topics for a window; rejects the whole batch if any topic is invalid
+    pub fn subscribe(&self, label: &str, topics: Vec<EventSubscription>) -> Result<(), String> {
+        if let Some(topic) = topics.iter().find(|t| t.topic.is_empty()) {
+            return Err(format!("Invalid topic: {:?}", topic.topic));
+        }
+        // Route::interval divides by the rate
+        if topics.iter().any(|t| t.max_per_sec == Some(0)) {
+            return Err("max_per_sec must be at least 1".to_string());
+        }
+
+        let mut windows = self.windows.lock().unwrap();
+        let routes = windows.entry(label.to_string()).or_default();
+        for subscription in topics {
+            routes.retain(|route| route.subscription.topic != subscription.topic);
+            routes.push(Route {
+                subscription,
+                streams: HashMap::new(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Drop topics for a window; it keeps receiving only what remains
+    pub fn unsubscribe(&self, label: &str, topics: &[String]) {
+        if let Some(routes) = self.windows.lock().unwrap().get_mut(label) {
+            routes.retain(|route| !topics.contains(&route.subscription.topic));
+        }
+    }
+
+    /// Forget a window's subscriptions, e.g. when it closes
+    pub fn remove_window(&self, label: &str) {
+        self.windows.lock().unwrap().remove(label);
+    }
+
+    fn route(&self, label: &str, id: &StreamId, terminal: bool, value: &serde_json::Value) -> Delivery {
+        let mut windows = self.windows.lock().unwrap();
+        let Some(routes) = windows.get_mut(label) else {
+            return Delivery::Now;
+        };
+        let Some(route) = best_route(routes, id.name) else {
+            return Delivery::Skip;
+        };
+        let Some(interval) = route.interval() else {
+            return Delivery::Now;
+        };
+
+        let now = Instant::now();
+        if terminal {
+            // Nothing may follow the final payload, so drop the stream entirely
+            route.streams.remove(id);
+            return Delivery::Now;
+        }
+        let stream = route.streams.entry(id.clone()).or_default();
+        match stream.last_sent {
+            Some(last) if now.duration_since(last) < interval => {
+                let scheduled = stream.pending.is_some();
+                stream.pending = Some(value.clone());
+                if scheduled {
+                    Delivery::Skip
+                } else {
+                    Delivery::Later(interval - now.duration_since(last))
+                }
+            }
+            _ => {
+                stream.last_sent = Some(now);
+                stream.pending = None;
+                Delivery::Now
+            }
+        }
+    }
+
+    fn take_pending(&self, label: &str, id: &StreamId) -> Option<serde_json::Value> {
+        let mut windows = self.windows.lock().unwrap();
+        let route = best_route(windows.get_mut(label)?, id.name)?;
+        let stream = route.streams.get_mut(id)?;
+        let pending = stream.pending.take()?;
+        stream.last_sent = Some(Instant::now());
+        Some(pending)
+    }
+}
+
+/// "ollama-pull-progress": progress update while a model is being pulled.
+/// Deprecated in favor of "operation-progress"; see operations::LEGACY_EVENTS_KEY
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct PullProgress {
+    pub model: String,
+    pub status: String,
+    pub digest: Option<String>,
+    #[ts(as = "Option<f64>")]
+    pub total: Option<u64>,
+    #[ts(as = "Option<f64>")]
+    pub completed: Option<u64>,
+}
+
+impl AppEvent for PullProgress {
+    const NAME: &'static str = "ollama-pull-progress";
+
+    fn stream_key(&self) -> Option<String> {
+        Some(self.model.clone())
+    }
+}
+
+/// "ollama-pull-layer": progress of one layer of a pull started with pull_model_stream.
+/// Deprecated in favor of "operation-progress"
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct PullLayerProgress {
+    pub handle: String,
+    pub model: String,
+    pub digest: String,
+    #[ts(type = "number")]
+    pub total: u64,
+    #[ts(type = "number")]
+    pub completed: u64,
+    pub cached: bool,
+}
+
+impl AppEvent for PullLayerProgress {
+    const NAME: &'static str = "ollama-pull-layer";
+
+    /// Layers download in parallel, so each one is coalesced on its own
+    fn stream_key(&self) -> Option<String> {
+        Some(format!("{}/{}", self.handle, self.digest))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum PullOutcome {
+    Completed { layers: Vec<PullLayer> },
+    Cancelled,
+    Failed { error: PullError },
+}
+
+/// "ollama-pull-finished": a pull started with pull_model_stream ended.
+/// Deprecated in favor of "operation-progress"
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct PullFinished {
+    pub handle: String,
+    pub model: String,
+    pub outcome: PullOutcome,
+}
+
+impl AppEvent for PullFinished {
+    const NAME: &'static str = "ollama-pull-finished";
+
+    fn stream_key(&self) -> Option<String> {
+        Some(self.handle.clone())
+    }
+
+    fn is_terminal(&self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationKind {
+    ModelPull,
+    SmokeTest,
+    Benchmark,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum OperationStatus {
+    Running,
+    Completed,
+    Cancelled,
+    Failed { error: String },
+}
+
+/// "operation-progress": one envelope for every long-running operation
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct OperationProgress {
+    pub id: String,
+    pub kind: OperationKind,
+    /// What the operation acts on, e.g. the model being pulled
+    pub label: String,
+    /// What the operation is doing now, e.g. "downloading" or "reindex"
+    pub phase: String,
+    #[ts(as = "Option<f64>")]
+    pub current: Option<u64>,
+    #[ts(as = "Option<f64>")]
+    pub total: Option<u64>,
+    /// Unit of `current` and `total`, e.g. "bytes" or "stages"
+    pub unit: Option<String>,
+    pub percent: Option<f64>,
+    pub message: Option<String>,
+    /// RFC 3339 timestamp
+    pub started_at: String,
+    /// Estimated seconds remaining, from the rate so far
+    #[ts(as = "Option<f64>")]
+    pub eta_secs: Option<u64>,
+    pub status: OperationStatus,
+}
+
+impl AppEvent for OperationProgress {
+    const NAME: &'static str = "operation-progress";
+
+    fn stream_key(&self) -> Option<String> {
+        Some(self.id.clone())
+    }
+
+    fn is_terminal(&self) -> bool {
+        !matches!(self.status, OperationStatus::Running)
+    }
+}
+
+/// "profile-changed": a connection profile was activated
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ProfileChanged {
+    pub name: String,
+    pub backend_url: String,
+}
+
+impl AppEvent for ProfileChanged {
+    const NAME: &'static str = "profile-changed";
+}
+
+/// "model-switched": the backend finished switching models
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ModelSwitched {
+    pub model_id: String,
+    pub model_name: String,
+    pub fell_back: bool,
+    pub capabilities: ModelCapabilities,
+}
+
+impl AppEvent for ModelSwitched {
+    const NAME: &'static str = "model-switched";
+}
+
+/// "models-changed": the backend's model list differs from the one last seen
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ModelsChanged {
+    pub version: String,
+    /// Previous version, if it came from the same backend
+    pub previous: Option<String>,
+    pub count: usize,
+}
+
+impl AppEvent for ModelsChanged {
+    const NAME: &'static str = "models-changed";
+}
+
+/// "resume-recovery": the health monitor detected a system resume and re-checked the backend
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ResumeRecovery {
+    /// Approximate time the machine was suspended
+    #[ts(type = "number")]
+    pub suspended_secs: u64,
+    pub connections_reset: bool,
+    pub health_checks: u32,
+    pub healthy: bool,
+}
+
+impl AppEvent for ResumeRecovery {
+    const NAME: &'static str = "resume-recovery";
+}
+
+/// "routing-changed": backend requests moved to another replica of the active profile
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RoutingChanged {
+    pub profile: Option<String>,
+    pub from: String,
+    pub to: String,
+}
+
+impl AppEvent for RoutingChanged {
+    const NAME: &'static str = "routing-changed";
+}
+
+// Tauri Commands
+
+#[tauri::command]
+pub fn subscribe_events(
+    app: AppHandle,
+    registry: tauri::State<'_, EventRegistry>,
+    window_label: String,
+    topics: Vec<EventSubscription>,
+) -> Result<(), String> {
+    if app.get_webview_window(&window_label).is_none() {
+        return Err(format!("Unknown window: {}", window_label));
+    }
+
+    registry.subscribe(&window_label, topics)
+}
+
+#[tauri::command]
+pub fn unsubscribe_events(
+    registry: tauri::State<'_, EventRegistry>,
+    window_label: String,
+    topics: Vec<String>,
+) {
+    registry.unsubscribe(&window_label, &topics);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn operation(id: &str, status: OperationStatus) -> (StreamId, bool, serde_json::Value) {
+        let progress = OperationProgress {
+            id: id.to_string(),
+            kind: OperationKind::ModelPull,
+            label: "qwen2.5:14b".to_string(),
+            phase: "downloading".to_string(),
+            current: None,
+            total: None,
+            unit: None,
+            percent: None,
+            message: None,
+            started_at: String::new(),
+            eta_secs: None,
+            status,
+        };
+        let stream = StreamId {
+            name: OperationProgress::NAME,
+            key: progress.stream_key(),
+        };
+        (stream, progress.is_terminal(), serde_json::to_value(progress).unwrap())
+    }
+
+    fn throttled_registry() -> EventRegistry {
+        let registry = EventRegistry::default();
+        registry
+            .subscribe(
+                "main",
+                vec![EventSubscription {
+                    topic: "operation-*".to_string(),
+                    max_per_sec: Some(1),
+                }],
+            )
+            .unwrap();
+        registry
+    }
+
+    fn route(registry: &EventRegistry, (stream, terminal, value): &(StreamId, bool, serde_json::Value)) -> Delivery {
+        registry.route("main", stream, *terminal, value)
+    }
+
+    #[test]
+    fn unsubscribed_windows_get_everything_and_subscribed_ones_only_their_topics() {
+        let registry = throttled_registry();
+        let running = operation("op-1", OperationStatus::Running);
+        let other = StreamId {
+            name: ModelsChanged::NAME,
+            key: None,
+        };
+
+        assert!(matches!(registry.route("settings", &other, false, &running.2), Delivery::Now));
+        assert!(matches!(registry.route("main", &other, false, &running.2), Delivery::Skip));
+    }
+
+    #[test]
+    fn progress_for_one_operation_is_coalesced_to_the_latest() {
+        let registry = throttled_registry();
+        let first = operation("op-1", OperationStatus::Running);
+
+        assert!(matches!(route(&registry, &first), Delivery::Now));
+        assert!(matches!(route(&registry, &first), Delivery::Later(_)));
+        let latest = operation("op-1", OperationStatus::Running);
+        assert!(matches!(route(&registry, &latest), Delivery::Skip));
+        assert_eq!(registry.take_pending("main", &latest.0), Some(latest.2));
+    }
+
+    #[test]
+    fn operations_are_throttled_separately() {
+        let registry = throttled_registry();
+
+        assert!(matches!(route(&registry, &operation("op-1", OperationStatus::Running)), Delivery::Now));
+        assert!(matches!(route(&registry, &operation("op-2", OperationStatus::Running)), Delivery::Now));
+    }
+
+    #[test]
+    fn terminal_status_is_never_throttled_or_overwritten() {
+        let registry = throttled_registry();
+        let running = operation("op-1", OperationStatus::Running);
+        let finished = operation("op-1", OperationStatus::Completed);
+
+        assert!(matches!(route(&registry, &running), Delivery::Now));
+        assert!(matches!(route(&registry, &running), Delivery::Later(_)));
+        assert!(matches!(route(&registry, &finished), Delivery::Now));
+        // The stale progress scheduled before the terminal payload is dropped
+        assert_eq!(registry.take_pending("main", &running.0), None);
+
+        // Another operation's progress cannot replace op-1's final envelope
+        let other = operation("op-2", OperationStatus::Running);
+        assert!(matches!(route(&registry, &other), Delivery::Now));
+        assert!(matches!(route(&registry, &other), Delivery::Later(_)));
+        assert_eq!(registry.take_pending("main", &running.0), None);
+    }
+
+    #[test]
+    fn layers_of_one_pull_are_throttled_separately() {
+        let registry = EventRegistry::default();
+        registry
+            .subscribe(
+                "main",
+                vec![EventSubscription {
+                    topic: PullLayerProgress::NAME.to_string(),
+                    max_per_sec: Some(1),
+                }],
+            )
+            .unwrap();
+        let layer = |digest: &str| {
+            let progress = PullLayerProgress {
+                handle: "op-1".to_string(),
+                model: "qwen2.5:14b".to_string(),
+                digest: digest.to_string(),
+                total: 100,
+                completed: 40,
+                cached: false,
+            };
+            let stream = StreamId {
+                name: PullLayerProgress::NAME,
+                key: progress.stream_key(),
+            };
+            (stream, progress.is_terminal(), serde_json::to_value(progress).unwrap())
+        };
+
+        assert!(matches!(route(&registry, &layer("sha256:aaaa")), Delivery::Now));
+        assert!(matches!(route(&registry, &layer("sha256:bbbb")), Delivery::Now));
+        assert!(matches!(route(&registry, &layer("sha256:aaaa")), Delivery::Later(_)));
+    }
+
+    #[test]
+    fn subscriptions_reject_a_zero_rate_and_an_empty_topic() {
+        let registry = EventRegistry::default();
+        let topic = |topic: &str, max_per_sec| EventSubscription {
+            topic: topic.to_string(),
+            max_per_sec,
+        };
+
+        let zero = registry.subscribe("main", vec![topic("operation-*", None), topic("ollama-*", Some(0))]);
+        assert_eq!(zero, Err("max_per_sec must be at least 1".to_string()));
+        assert!(registry.subscribe("main", vec![topic("", None)]).is_err());
+        // Nothing from a rejected batch is applied
+        let other = StreamId {
+            name: ModelsChanged::NAME,
+            key: None,
+        };
+        assert!(matches!(registry.route("main", &other, false, &json!({})), Delivery::Now));
+    }
+
+    #[test]
+    fn pull_finished_is_terminal_per_handle() {
+        let finished = PullFinished {
+            handle: "pull-1".to_string(),
+            model: "qwen2.5:14b".to_string(),
+            outcome: PullOutcome::Cancelled,
+        };
+        assert!(finished.is_terminal());
+        assert_eq!(finished.stream_key().as_deref(), Some("pull-1"));
+    }
+
+    /// The JSON a listener for `E::NAME` receives
+    fn envelope<E: AppEvent>(payload: E) -> serde_json::Value {
+        serde_json::to_value(Versioned {
+            schema_version: SCHEMA_VERSION,
+            payload,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn pull_events_serialize_to_their_documented_shape() {
+        let progress = PullProgress {
+            model: "qwen2.5:14b".to_string(),
+            status: "pulling 2bada8a74506".to_string(),
+            digest: Some("sha256:2bada8a74506".to_string()),
+            total: Some(100),
+            completed: None,
+        };
+        assert_eq!(PullProgress::NAME, "ollama-pull-progress");
+        assert_eq!(
+            envelope(progress),
+            json!({
+                "schema_version": SCHEMA_VERSION,
+                "model": "qwen2.5:14b",
+                "status": "pulling 2bada8a74506",
+                "digest": "sha256:2bada8a74506",
+                "total": 100,
+                "completed": null,
+            })
+        );
+
+        let layer = PullLayerProgress {
+            handle: "op-1".to_string(),
+            model: "qwen2.5:14b".to_string(),
+            digest: "sha256:2bada8a74506".to_string(),
+            total: 100,
+            completed: 40,
+            cached: false,
+        };
+        assert_eq!(PullLayerProgress::NAME, "ollama-pull-layer");
+        assert_eq!(
+            envelope(layer),
+            json!({
+                "schema_version": SCHEMA_VERSION,
+                "handle": "op-1",
+                "model": "qwen2.5:14b",
+                "digest": "sha256:2bada8a74506",
+                "total": 100,
+                "completed": 40,
+                "cached": false,
+            })
+        );
+    }
+
+    #[test]
+    fn pull_outcomes_are_tagged_by_status() {
+        let finished = |outcome| PullFinished {
+            handle: "op-1".to_string(),
+            model: "qwen2.5:14b".to_string(),
+            outcome,
+        };
+        assert_eq!(PullFinished::NAME, "ollama-pull-finished");
+
+        let completed = finished(PullOutcome::Completed {
+            layers: vec![PullLayer {
+                digest: "sha256:2bada8a74506".to_string(),
+                total: 100,
+                completed: 100,
+                cached: true,
+            }],
+        });
+        assert_eq!(
+            envelope(completed),
+            json!({
+                "schema_version": SCHEMA_VERSION,
+                "handle": "op-1",
+                "model": "qwen2.5:14b",
+                "outcome": {
+                    "status": "completed",
+                    "layers": [{"digest": "sha256:2bada8a74506", "total": 100, "completed": 100, "cached": true}],
+                },
+            })
+        );
+        assert_eq!(envelope(finished(PullOutcome::Cancelled))["outcome"], json!({"status": "cancelled"}));
+
+        let failed = finished(PullOutcome::Failed {
+            error: PullError::InsufficientDisk {
+                required: 10,
+                available: 4,
+            },
+        });
+        assert_eq!(
+            envelope(failed)["outcome"],
+            json!({"status": "failed", "error": {"kind": "InsufficientDisk", "required": 10, "available": 4}})
+        );
+    }
+
+    #[test]
+    fn operation_progress_serializes_to_its_documented_shape() {
+        let (_, _, running) = operation("op-3", OperationStatus::Running);
+        let mut expected = json!({
+            "id": "op-3",
+            "kind": "model_pull",
+            "label": "qwen2.5:14b",
+            "phase": "downloading",
+            "current": null,
+            "total": null,
+            "unit": null,
+            "percent": null,
+            "message": null,
+            "started_at": "",
+            "eta_secs": null,
+            "status": {"state": "running"},
+        });
+        assert_eq!(running, expected);
+
+        let (_, _, failed) = operation("op-3", OperationStatus::Failed { error: "disk full".to_string() });
+        expected["status"] = json!({"state": "failed", "error": "disk full"});
+        assert_eq!(failed, expected);
+
+        let progress: OperationProgress = serde_json::from_value(expected).unwrap();
+        assert_eq!(OperationProgress::NAME, "operation-progress");
+        assert_eq!(envelope(progress)["schema_version"], SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn backend_events_serialize_to_their_documented_shape() {
+        let profile = ProfileChanged {
+            name: "lab".to_string(),
+            backend_url: "http://10.0.0.5:8000/".to_string(),
+        };
+        assert_eq!(ProfileChanged::NAME, "profile-changed");
+        assert_eq!(
+            envelope(profile),
+            json!({"schema_version": SCHEMA_VERSION, "name": "lab", "backend_url": "http://10.0.0.5:8000/"})
+        );
+
+        let switched = ModelSwitched {
+            model_id: "qwen2.5:7b".to_string(),
+            model_name: "Qwen 2.5 7B".to_string(),
+            fell_back: true,
+            capabilities: ModelCapabilities {
+                model_id: "qwen2.5:7b".to_string(),
+                max_context: 32_768,
+                supports_json_mode: true,
+                supports_tools: true,
+                supports_vision: false,
+                reasoning_model: false,
+                confidence: crate::capabilities::Confidence::Curated,
+            },
+        };
+        assert_eq!(ModelSwitched::NAME, "model-switched");
+        assert_eq!(
+            envelope(switched),
+            json!({
+                "schema_version": SCHEMA_VERSION,
+                "model_id": "qwen2.5:7b",
+                "model_name": "Qwen 2.5 7B",
+                "fell_back": true,
+                "capabilities": {
+                    "model_id": "qwen2.5:7b",
+                    "max_context": 32768,
+                    "supports_json_mode": true,
+                    "supports_tools": true,
+                    "supports_vision": false,
+                    "reasoning_model": false,
+                    "confidence": "Curated",
+                },
+            })
+        );
+
+        let models = ModelsChanged {
+            version: "b".to_string(),
+            previous: None,
+            count: 3,
+        };
+        assert_eq!(ModelsChanged::NAME, "models-changed");
+        assert_eq!(
+            envelope(models),
+            json!({"schema_version": SCHEMA_VERSION, "version": "b", "previous": null, "count": 3})
+        );
+
+        let resume = ResumeRecovery {
+            suspended_secs: 600,
+            connections_reset: true,
+            health_checks: 2,
+            healthy: true,
+        };
+        assert_eq!(ResumeRecovery::NAME, "resume-recovery");
+        assert_eq!(
+            envelope(resume),
+            json!({
+                "schema_version": SCHEMA_VERSION,
+                "suspended_secs": 600,
+                "connections_reset": true,
+                "health_checks": 2,
+                "healthy": true,
+            })
+        );
+
+        let routing = RoutingChanged {
+            profile: Some("lab".to_string()),
+            from: "http://a:8000/".to_string(),
+            to: "http://b:8000/".to_string(),
+        };
+        assert_eq!(RoutingChanged::NAME, "routing-changed");
+        assert_eq!(
+            envelope(routing),
+            json!({"schema_version": SCHEMA_VERSION, "profile": "lab", "from": "http://a:8000/", "to": "http://b:8000/"})
+        );
+    }
+}
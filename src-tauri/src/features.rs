@@ -0,0 +1,287 @@
+// Backend Features
+// Detects which optional endpoints a backend version supports so the UI can hide the rest
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::endpoint::{read_json_limited, BackendEndpoint, ResponseError, DEFAULT_MAX_RESPONSE_BYTES};
+
+/// How the capabilities were determined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum CapabilitySource {
+    /// Reported by the backend's /api/capabilities endpoint
+    Reported,
+    /// Inferred from the backend's OpenAPI schema
+    Inferred,
+    /// Backend could not be inspected; everything optional is off
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct BackendCapabilities {
+    #[serde(default)]
+    pub streaming: bool,
+    #[serde(default)]
+    pub rerank: bool,
+    #[serde(default)]
+    pub hybrid: bool,
+    #[serde(default)]
+    pub ingest_async: bool,
+    #[serde(default = "unknown_source")]
+    pub source: CapabilitySource,
+}
+
+fn unknown_source() -> CapabilitySource {
+    CapabilitySource::Unknown
+}
+
+impl BackendCapabilities {
+    fn unknown() -> Self {
+        Self {
+            streaming: false,
+            rerank: false,
+            hybrid: false,
+            ingest_async: false,
+            source: CapabilitySource::Unknown,
+        }
+    }
+}
+
+/// Detected capabilities keyed by backend base URL
+#[derive(Debug, Clone, Default)]
+pub struct CapabilityCache {
+    entries: Arc<Mutex<HashMap<String, BackendCapabilities>>>,
+}
+
+impl CapabilityCache {
+    pub fn get(&self, backend_url: &str) -> Option<BackendCapabilities> {
+        self.entries.lock().unwrap().get(backend_url).cloned()
+    }
+
+    pub fn insert(&self, backend_url: &str, capabilities: BackendCapabilities) {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(backend_url.to_string(), capabilities);
+    }
+
+    /// Forget every backend, e.g. after switching profiles
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+/// Ask the backend for its capabilities; `Ok(None)` means the endpoint does not exist
+async fn fetch_reported(endpoint: &BackendEndpoint) -> Result<Option<BackendCapabilities>, ResponseError> {
+    let response = endpoint
+        .request(Method::GET, "/api/capabilities")
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .map_err(ResponseError::unreachable)?;
+
+    match response.status() {
+        StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED => return Ok(None),
+        status if !status.is_success() => {
+            return Err(ResponseError::Rejected {
+                message: format!("Backend /api/capabilities returned {}", status),
+            });
+        }
+        _ => {}
+    }
+
+    let mut capabilities: BackendCapabilities = read_json_limited(response, DEFAULT_MAX_RESPONSE_BYTES).await?;
+    capabilities.source = CapabilitySource::Reported;
+    Ok(Some(capabilities))
+}
+
+/// Infer capabilities from the routes and settings published in the OpenAPI schema
+async fn infer_from_schema(endpoint: &BackendEndpoint) -> Result<BackendCapabilities, ResponseError> {
+    let response = endpoint
+        .request(Method::GET, "/openapi.json")
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .map_err(ResponseError::unreachable)?;
+
+    if !response.status().is_success() {
+        return Err(ResponseError::Rejected {
+            message: format!("Backend /openapi.json returned {}", response.status()),
+        });
+    }
+
+    let schema: serde_json::Value = read_json_limited(response, DEFAULT_MAX_RESPONSE_BYTES).await?;
+
+    Ok(capabilities_from_schema(&schema))
+}
+
+fn capabilities_from_schema(schema: &serde_json::Value) -> BackendCapabilities {
+    let has_path = |wanted: &str| {
+        schema["paths"]
+            .as_object()
+            .map(|paths| paths.keys().any(|path| path.trim_end_matches('/') == wanted))
+            .unwrap_or(false)
+    };
+    let has_setting = |wanted: &str| {
+        schema["components"]["schemas"]
+            .as_object()
+            .map(|schemas| {
+                schemas
+                    .values()
+                    .any(|s| s["properties"].get(wanted).is_some())
+            })
+            .unwrap_or(false)
+    };
+
+    BackendCapabilities {
+        streaming: has_path("/api/query/stream"),
+        rerank: has_setting("rerank_weight"),
+        hybrid: has_setting("hybrid_k"),
+        ingest_async: has_path("/api/documents/jobs"),
+        source: CapabilitySource::Inferred,
+    }
+}
+
+/// Detect what the current backend supports, preferring its own report over inference
+pub async fn detect_capabilities(endpoint: &BackendEndpoint) -> BackendCapabilities {
+    match fetch_reported(endpoint).await {
+        Ok(Some(capabilities)) => return capabilities,
+        Ok(None) => log::info!("Backend has no /api/capabilities, inferring from schema"),
+        Err(e) => log::warn!("Failed to fetch backend capabilities: {}", e),
+    }
+
+    infer_from_schema(endpoint).await.unwrap_or_else(|e| {
+        log::warn!("Failed to infer backend capabilities: {}", e);
+        BackendCapabilities::unknown()
+    })
+}
+
+/// Capabilities of the current backend, detected once per URL unless `refresh` is set
+pub async fn capabilities(endpoint: &BackendEndpoint, cache: &CapabilityCache, refresh: bool) -> BackendCapabilities {
+    let backend_url = endpoint.get().to_string();
+
+    if !refresh {
+        if let Some(cached) = cache.get(&backend_url) {
+            return cached;
+        }
+    }
+
+    let capabilities = detect_capabilities(endpoint).await;
+    // Unknown results come from an unreachable backend and are retried next time
+    if capabilities.source != CapabilitySource::Unknown {
+        cache.insert(&backend_url, capabilities.clone());
+    }
+    capabilities
+}
+
+// Tauri Commands
+
+#[tauri::command]
+pub async fn get_backend_capabilities(
+    endpoint: tauri::State<'_, BackendEndpoint>,
+    cache: tauri::State<'_, CapabilityCache>,
+    refresh: Option<bool>,
+) -> Result<BackendCapabilities, String> {
+    Ok(capabilities(&endpoint, &cache, refresh.unwrap_or(false)).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use crate::test_support::{MockResponse, MockServer};
+
+    fn endpoint_for(server: &MockServer) -> BackendEndpoint {
+        BackendEndpoint::new(server.url.parse().unwrap())
+    }
+
+    fn schema() -> serde_json::Value {
+        json!({
+            "paths": {
+                "/api/query": {},
+                "/api/query/stream/": {},
+                "/api/documents/upload": {},
+            },
+            "components": {"schemas": {
+                "QueryRequest": {"properties": {"question": {}, "hybrid_k": {}}},
+            }},
+        })
+    }
+
+    #[tokio::test]
+    async fn reported_capabilities_are_parsed() {
+        let backend = MockServer::start(|_| {
+            MockResponse::json(200, json!({"streaming": true, "rerank": true}))
+        })
+        .await;
+
+        let caps = detect_capabilities(&endpoint_for(&backend)).await;
+        assert_eq!(caps.source, CapabilitySource::Reported);
+        assert!(caps.streaming && caps.rerank);
+        // Fields the backend leaves out are off
+        assert!(!caps.hybrid && !caps.ingest_async);
+        assert!(backend.requests_to("/openapi.json").is_empty());
+    }
+
+    #[tokio::test]
+    async fn missing_endpoint_falls_back_to_the_schema() {
+        let backend = MockServer::start(|request| match request.path.as_str() {
+            "/openapi.json" => MockResponse::json(200, schema()),
+            _ => MockResponse::json(404, json!({"detail": "Not Found"})),
+        })
+        .await;
+
+        let caps = detect_capabilities(&endpoint_for(&backend)).await;
+        assert_eq!(caps.source, CapabilitySource::Inferred);
+        assert!(caps.streaming);
+        assert!(caps.hybrid);
+        assert!(!caps.rerank);
+        assert!(!caps.ingest_async);
+    }
+
+    #[test]
+    fn schema_inference_tolerates_missing_sections() {
+        let caps = capabilities_from_schema(&json!({"openapi": "3.1.0"}));
+        assert_eq!(caps.source, CapabilitySource::Inferred);
+        assert!(!caps.streaming && !caps.rerank && !caps.hybrid && !caps.ingest_async);
+
+        let caps = capabilities_from_schema(&json!({
+            "paths": {"/api/documents/jobs": {}},
+            "components": {"schemas": {"Settings": {"properties": {"rerank_weight": {}}}}},
+        }));
+        assert!(caps.ingest_async && caps.rerank);
+    }
+
+    #[tokio::test]
+    async fn results_are_cached_per_backend_until_refreshed() {
+        let backend = MockServer::start(|_| MockResponse::json(200, json!({"streaming": true}))).await;
+        let endpoint = endpoint_for(&backend);
+        let cache = CapabilityCache::default();
+
+        assert!(capabilities(&endpoint, &cache, false).await.streaming);
+        assert!(capabilities(&endpoint, &cache, false).await.streaming);
+        assert_eq!(backend.requests().len(), 1);
+
+        capabilities(&endpoint, &cache, true).await;
+        assert_eq!(backend.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn unreachable_backends_are_not_cached() {
+        let backend = MockServer::start(|_| MockResponse::text(503, "starting")).await;
+        let endpoint = endpoint_for(&backend);
+        let cache = CapabilityCache::default();
+
+        let caps = capabilities(&endpoint, &cache, false).await;
+        assert_eq!(caps.source, CapabilitySource::Unknown);
+        assert!(cache.get(endpoint.get().as_str()).is_none());
+
+        capabilities(&endpoint, &cache, false).await;
+        assert_eq!(backend.requests_to("/api/capabilities").len(), 2);
+    }
+}
@@ -0,0 +1,453 @@
+// Follow-up Suggestions
+// Suggested next questions from the cited sources, extractive or via a small local model
+
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use ts_rs::TS;
+
+use crate::ollama;
+use crate::settings::{self, Validator};
+use crate::titles::{self, collapse_whitespace, strip_think_blocks};
+
+const FOLLOWUP_MODE_KEY: &str = "followup_mode";
+
+/// Persisted settings owned by this module
+pub const SETTINGS: &[Validator] = &[Validator {
+    key: FOLLOWUP_MODE_KEY,
+    check: check_mode,
+}];
+
+fn check_mode(value: &serde_json::Value) -> Result<Option<serde_json::Value>, String> {
+    settings::parse::<FollowupMode>(value).map(|_| None)
+}
+
+/// Small model used in model mode
+pub const DEFAULT_FOLLOWUP_MODEL: &str = titles::DEFAULT_TITLE_MODEL;
+
+const MAX_FOLLOWUPS: usize = 3;
+const FOLLOWUP_MAX_TOKENS: u32 = 96;
+
+/// Answer text passed to the model, in characters
+const MAX_ANSWER_CHARS: usize = 1500;
+
+/// Longest heading or entity phrase turned into a question, in words
+const MAX_PHRASE_WORDS: usize = 8;
+
+/// Capitalized words that end a phrase rather than join it ("The Army" -> "Army")
+const PHRASE_STOPWORDS: &[&str] = &[
+    "A", "An", "The", "This", "That", "These", "Those", "In", "On", "For", "If", "When", "Per", "See",
+];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum FollowupMode {
+    /// Questions templated from headings and entities in the sources; no model call
+    #[default]
+    Extractive,
+    /// Questions written by a small Ollama model, falling back to extractive
+    Model,
+}
+
+/// A cited source, as returned with the backend's answer
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct FollowupSource {
+    pub file_name: String,
+    #[serde(default)]
+    pub excerpt: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct FollowupSuggestions {
+    pub questions: Vec<String>,
+    /// Mode that actually produced the questions
+    pub mode: FollowupMode,
+}
+
+/// Lowercased words only, so "What is X?" and "what is x" compare equal
+fn normalize_question(question: &str) -> String {
+    let cleaned: String = question
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { ' ' })
+        .collect();
+    collapse_whitespace(&cleaned)
+}
+
+/// Collects up to MAX_FOLLOWUPS questions, skipping ones already asked or suggested
+struct Suggestions {
+    questions: Vec<String>,
+    seen: Vec<String>,
+}
+
+impl Suggestions {
+    fn new(previous_questions: &[String]) -> Self {
+        Self {
+            questions: Vec::new(),
+            seen: previous_questions.iter().map(|q| normalize_question(q)).collect(),
+        }
+    }
+
+    fn push(&mut self, question: String) {
+        let key = normalize_question(&question);
+        if self.is_full() || key.is_empty() || self.seen.contains(&key) {
+            return;
+        }
+        self.seen.push(key);
+        self.questions.push(question);
+    }
+
+    fn is_full(&self) -> bool {
+        self.questions.len() >= MAX_FOLLOWUPS
+    }
+}
+
+/// Markdown, numbered ("3.2 Storage Limits") or colon-terminated headings in an excerpt
+fn headings(excerpt: &str) -> Vec<String> {
+    excerpt
+        .lines()
+        .map(str::trim)
+        .filter_map(|line| {
+            let heading = if let Some(rest) = line.strip_prefix('#') {
+                rest.trim_start_matches('#')
+            } else if let Some(rest) = line.strip_suffix(':') {
+                rest
+            } else {
+                let (number, rest) = line.split_once(' ')?;
+                let numbered = number.chars().any(|c| c.is_ascii_digit())
+                    && number.chars().all(|c| c.is_ascii_digit() || c == '.');
+                if !numbered || line.ends_with('.') {
+                    return None;
+                }
+                rest
+            };
+
+            let heading = collapse_whitespace(heading.trim_matches(['*', '_', ' ']));
+            let words = heading.split_whitespace().count();
+            let first = heading.split_whitespace().next()?;
+            let starts_upper = first.chars().next().is_some_and(char::is_uppercase);
+            let usable = starts_upper && !PHRASE_STOPWORDS.contains(&first);
+            (usable && (1..=MAX_PHRASE_WORDS).contains(&words)).then_some(heading)
+        })
+        .collect()
+}
+
+fn is_acronym(word: &str) -> bool {
+    word.chars().filter(char::is_ascii_uppercase).count() >= 2
+        && word.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// Runs of capitalized words ("Field Manual", "Fort Liberty") and acronyms, most frequent first
+fn entities(texts: &[&str]) -> Vec<String> {
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    let mut add = |phrase: &[&str]| {
+        let keep = match phrase {
+            [single] => is_acronym(single),
+            _ => (2..=MAX_PHRASE_WORDS).contains(&phrase.len()),
+        };
+        if !keep {
+            return;
+        }
+        let phrase = phrase.join(" ");
+        match counts.iter_mut().find(|(p, _)| *p == phrase) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((phrase, 1)),
+        }
+    };
+
+    for line in texts.iter().flat_map(|text| text.lines()) {
+        let mut current: Vec<&str> = Vec::new();
+        for raw in line.split_whitespace() {
+            let word = raw.trim_matches(|c: char| !c.is_alphanumeric() && c != '-');
+            let capitalized = word.len() > 1 && word.chars().next().is_some_and(char::is_uppercase);
+            let joins = capitalized && !PHRASE_STOPWORDS.contains(&word);
+            if joins {
+                current.push(word);
+            }
+            if !joins || raw.ends_with(['.', ',', ';', ':', '?', '!', ')']) {
+                add(&current);
+                current.clear();
+            }
+        }
+        add(&current);
+    }
+
+    // Stable sort keeps first-appearance order among equal counts
+    counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    counts.into_iter().map(|(phrase, _)| phrase).collect()
+}
+
+fn file_stem(file_name: &str) -> Option<String> {
+    let name = file_name.rsplit(['/', '\\']).next().unwrap_or(file_name);
+    let stem = name.rsplit_once('.').map_or(name, |(stem, _)| stem);
+    let stem = collapse_whitespace(&stem.replace(['_', '-'], " "));
+    (!stem.is_empty()).then_some(stem)
+}
+
+/// Template questions from the sources' headings and entities, then their file names
+pub fn extractive_followups(answer: &str, sources: &[FollowupSource], previous_questions: &[String]) -> Vec<String> {
+    let mut suggestions = Suggestions::new(previous_questions);
+
+    for heading in sources.iter().flat_map(|s| headings(&s.excerpt)) {
+        suggestions.push(format!("What does the {} section cover?", heading));
+    }
+
+    let mut texts = vec![answer];
+    texts.extend(sources.iter().map(|s| s.excerpt.as_str()));
+    for entity in entities(&texts) {
+        suggestions.push(format!("What else do the documents say about {}?", entity));
+    }
+
+    for stem in sources.iter().filter_map(|s| file_stem(&s.file_name)) {
+        suggestions.push(format!("What are the key points of {}?", stem));
+    }
+
+    suggestions.questions
+}
+
+/// Keep lines that read as questions, minus numbering and bullets
+fn parse_model_questions(raw: &str) -> Vec<String> {
+    strip_think_blocks(raw)
+        .lines()
+        .filter_map(|line| {
+            let line = line
+                .trim()
+                .trim_start_matches(|c: char| c.is_ascii_digit() || matches!(c, '.' | ')' | '-' | '*' | ' '));
+            let question = collapse_whitespace(line.trim_matches(['"', '*', '`']));
+            let length = question.chars().count();
+            (question.ends_with('?') && (10..=160).contains(&length)).then_some(question)
+        })
+        .collect()
+}
+
+async fn model_followups(
+    base_url: &str,
+    answer: &str,
+    sources: &[FollowupSource],
+    previous_questions: &[String],
+    model_name: &str,
+) -> Result<Vec<String>, String> {
+    let excerpt: String = answer.chars().take(MAX_ANSWER_CHARS).collect();
+    let files: Vec<&str> = sources.iter().map(|s| s.file_name.as_str()).collect();
+    let prompt = format!(
+        "Suggest three short follow-up questions a reader might ask next about the answer \
+         below. Write one question per line and nothing else.\n\nSources: {}\n\nAnswer: {}",
+        files.join(", "),
+        excerpt.trim()
+    );
+
+    let timeout = Duration::from_secs(20);
+    let raw = ollama::generate_text_at(base_url, model_name, &prompt, FOLLOWUP_MAX_TOKENS, timeout).await?;
+
+    let mut suggestions = Suggestions::new(previous_questions);
+    for question in parse_model_questions(&raw) {
+        suggestions.push(question);
+    }
+    if suggestions.questions.is_empty() {
+        return Err(format!("Follow-up model returned no questions: {:?}", raw));
+    }
+
+    // Top up a short list so the UI always gets a full set when the sources allow it
+    if !suggestions.is_full() {
+        let asked: Vec<String> = previous_questions.iter().chain(&suggestions.questions).cloned().collect();
+        for question in extractive_followups(answer, sources, &asked) {
+            suggestions.push(question);
+        }
+    }
+    Ok(suggestions.questions)
+}
+
+/// Suggest follow-up questions, falling back to extractive mode if the model is unavailable
+pub async fn suggest(
+    answer: &str,
+    sources: &[FollowupSource],
+    previous_questions: &[String],
+    mode: FollowupMode,
+) -> FollowupSuggestions {
+    suggest_at(ollama::OLLAMA_API_URL, answer, sources, previous_questions, mode).await
+}
+
+async fn suggest_at(
+    base_url: &str,
+    answer: &str,
+    sources: &[FollowupSource],
+    previous_questions: &[String],
+    mode: FollowupMode,
+) -> FollowupSuggestions {
+    if mode == FollowupMode::Model {
+        match model_followups(base_url, answer, sources, previous_questions, DEFAULT_FOLLOWUP_MODEL).await {
+            Ok(questions) => return FollowupSuggestions { questions, mode },
+            Err(e) => log::warn!("Falling back to extractive follow-ups: {}", e),
+        }
+    }
+
+    FollowupSuggestions {
+        questions: extractive_followups(answer, sources, previous_questions),
+        mode: FollowupMode::Extractive,
+    }
+}
+
+// Tauri Commands
+
+/// `mode` overrides the saved follow-up mode for this call
+#[tauri::command]
+pub async fn suggest_followups(
+    app: AppHandle,
+    answer: String,
+    sources: Vec<FollowupSource>,
+    previous_questions: Option<Vec<String>>,
+    mode: Option<FollowupMode>,
+) -> Result<FollowupSuggestions, String> {
+    let mode = mode
+        .or_else(|| settings::get(&app, FOLLOWUP_MODE_KEY))
+        .unwrap_or_default();
+    let previous_questions = previous_questions.unwrap_or_default();
+    Ok(suggest(&answer, &sources, &previous_questions, mode).await)
+}
+
+#[tauri::command]
+pub fn get_followup_mode(app: AppHandle) -> FollowupMode {
+    settings::get(&app, FOLLOWUP_MODE_KEY).unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn set_followup_mode(app: AppHandle, mode: FollowupMode) -> Result<(), String> {
+    settings::set(&app, FOLLOWUP_MODE_KEY, &mode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockResponse, MockServer};
+
+    fn source(file_name: &str, excerpt: &str) -> FollowupSource {
+        FollowupSource {
+            file_name: file_name.to_string(),
+            excerpt: excerpt.to_string(),
+        }
+    }
+
+    fn sources() -> Vec<FollowupSource> {
+        vec![
+            source(
+                "docs/FM_3-21.pdf",
+                "## Storage Limits\nUnits at Fort Liberty follow the SOP. The SOP sets limits per Field Manual.",
+            ),
+            source("notes/range_safety.txt", "3.2 Range Safety\nCheck the range card first."),
+        ]
+    }
+
+    fn previous(questions: &[&str]) -> Vec<String> {
+        questions.iter().map(|q| q.to_string()).collect()
+    }
+
+    #[test]
+    fn extractive_mode_prefers_headings_then_entities_then_files() {
+        let questions = extractive_followups("Storage follows the SOP.", &sources(), &[]);
+        assert_eq!(
+            questions,
+            [
+                "What does the Storage Limits section cover?",
+                "What does the Range Safety section cover?",
+                "What else do the documents say about SOP?",
+            ]
+        );
+
+        let few_sources = [source("unit_handbook.docx", "")];
+        assert_eq!(
+            extractive_followups("", &few_sources, &[]),
+            ["What are the key points of unit handbook?"]
+        );
+    }
+
+    #[test]
+    fn suggestions_skip_previous_questions_ignoring_case_and_punctuation() {
+        let asked = previous(&["what does the storage-limits section cover", "What else do the documents say about SOP"]);
+        let questions = extractive_followups("", &sources(), &asked);
+
+        assert_eq!(questions[0], "What does the Range Safety section cover?");
+        assert!(questions.iter().all(|q| !q.contains("Storage Limits section") && !q.ends_with("about SOP?")));
+        assert_eq!(questions.len(), MAX_FOLLOWUPS);
+    }
+
+    #[test]
+    fn entities_ignore_stopwords_and_rank_by_frequency() {
+        let found = entities(&["The Field Manual covers NATO and NATO drills at Fort Liberty."]);
+        assert_eq!(found, ["NATO", "Field Manual", "Fort Liberty"]);
+    }
+
+    #[test]
+    fn model_output_is_reduced_to_questions() {
+        let raw = "<think>pick three</think>\n1. What is the storage limit?\n- **Who signs the SOP?**\nHere you go\n3) Why?";
+        assert_eq!(parse_model_questions(raw), ["What is the storage limit?", "Who signs the SOP?"]);
+    }
+
+    async fn ollama(response: &'static str) -> MockServer {
+        MockServer::start(move |_| MockResponse::json(200, serde_json::json!({"response": response, "done": true}))).await
+    }
+
+    #[tokio::test]
+    async fn model_mode_uses_the_model_questions() {
+        let server = ollama("1. How often is the SOP reviewed?\n2. Who approves storage waivers?\n3. What happens after a failed inspection?").await;
+
+        let suggestions = suggest_at(&server.url, "answer", &sources(), &[], FollowupMode::Model).await;
+        assert_eq!(suggestions.mode, FollowupMode::Model);
+        assert_eq!(
+            suggestions.questions,
+            [
+                "How often is the SOP reviewed?",
+                "Who approves storage waivers?",
+                "What happens after a failed inspection?"
+            ]
+        );
+
+        let request = &server.requests_to("/api/generate")[0];
+        assert_eq!(request.json()["model"], DEFAULT_FOLLOWUP_MODEL);
+        assert_eq!(request.json()["options"]["num_predict"], FOLLOWUP_MAX_TOKENS);
+    }
+
+    #[tokio::test]
+    async fn a_short_model_list_is_topped_up_from_the_sources() {
+        let server = ollama("How often is the SOP reviewed?\nhow often is the SOP reviewed").await;
+        let asked = previous(&["What does the Storage Limits section cover?"]);
+
+        let suggestions = suggest_at(&server.url, "", &sources(), &asked, FollowupMode::Model).await;
+        assert_eq!(suggestions.mode, FollowupMode::Model);
+        assert_eq!(
+            suggestions.questions,
+            [
+                "How often is the SOP reviewed?",
+                "What does the Range Safety section cover?",
+                "What else do the documents say about SOP?",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn model_mode_falls_back_to_extractive() {
+        let extractive = extractive_followups("", &sources(), &[]);
+
+        // Only questions the user already asked
+        let server = ollama("What is the storage limit?").await;
+        let asked = previous(&["What is the storage limit?"]);
+        let suggestions = suggest_at(&server.url, "", &sources(), &asked, FollowupMode::Model).await;
+        assert_eq!(suggestions.mode, FollowupMode::Extractive);
+
+        let server = MockServer::start(|_| MockResponse::text(404, "model not found")).await;
+        let suggestions = suggest_at(&server.url, "", &sources(), &[], FollowupMode::Model).await;
+        assert_eq!(suggestions.mode, FollowupMode::Extractive);
+        assert_eq!(suggestions.questions, extractive);
+
+        let server = MockServer::start(|_| MockResponse::hang_up()).await;
+        let suggestions = suggest_at(&server.url, "", &sources(), &[], FollowupMode::Model).await;
+        assert_eq!(suggestions.mode, FollowupMode::Extractive);
+        assert_eq!(suggestions.questions, extractive);
+    }
+
+    #[tokio::test]
+    async fn extractive_mode_never_calls_the_model() {
+        let server = ollama("Should not be used?").await;
+        let suggestions = suggest_at(&server.url, "", &sources(), &[], FollowupMode::Extractive).await;
+        assert_eq!(suggestions.mode, FollowupMode::Extractive);
+        assert!(server.requests().is_empty());
+    }
+}
@@ -0,0 +1,328 @@
+// Hardware Probes
+// Host resource checks (disk space, GPU memory)
+
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Outcome of a failed disk space check
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub enum DiskSpaceError {
+    /// The volume holding the path has less free space than required
+    Insufficient {
+        #[ts(type = "number")]
+        required: u64,
+        #[ts(type = "number")]
+        available: u64,
+    },
+    /// Free space could not be determined
+    ProbeFailed(String),
+}
+
+/// Reports free bytes for a path; `available_space` outside of tests
+pub type SpaceProbe = fn(&Path) -> Result<u64, String>;
+
+/// Free bytes on the volume that holds `path`.
+///
+/// The path does not need to exist yet; its nearest existing ancestor is probed.
+pub fn available_space(path: &Path) -> Result<u64, String> {
+    let existing = nearest_existing(path)
+        .ok_or_else(|| format!("No existing directory found for {}", path.display()))?;
+
+    fs2::available_space(&existing)
+        .map_err(|e| format!("Failed to query free space for {}: {}", existing.display(), e))
+}
+
+/// Ensure at least `required_bytes` are free on the volume that holds `path`
+pub fn check_disk_space(probe: SpaceProbe, path: &Path, required_bytes: u64) -> Result<u64, DiskSpaceError> {
+    let available = probe(path).map_err(DiskSpaceError::ProbeFailed)?;
+
+    if available < required_bytes {
+        log::warn!(
+            "Insufficient disk space at {}: {} bytes required, {} available",
+            path.display(),
+            required_bytes,
+            available
+        );
+        return Err(DiskSpaceError::Insufficient {
+            required: required_bytes,
+            available,
+        });
+    }
+
+    Ok(available)
+}
+
+fn nearest_existing(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .find(|ancestor| !ancestor.as_os_str().is_empty() && ancestor.exists())
+        .map(Path::to_path_buf)
+}
+
+/// Run an nvidia-smi query in CSV mode without headers or units
+async fn nvidia_smi(query: &str) -> Result<String, String> {
+    let output = tokio::process::Command::new("nvidia-smi")
+        .args([query, "--format=csv,noheader,nounits"])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run nvidia-smi: {}", e))?;
+
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("nvidia-smi failed: {}", error.trim()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Free VRAM in MiB on the emptiest NVIDIA GPU, via nvidia-smi
+pub async fn gpu_free_vram_mb() -> Result<u64, String> {
+    nvidia_smi("--query-gpu=memory.free")
+        .await?
+        .lines()
+        .filter_map(|line| line.trim().parse::<u64>().ok())
+        .max()
+        .ok_or_else(|| "nvidia-smi reported no GPUs".to_string())
+}
+
+/// App component a GPU process belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum GpuComponent {
+    Ollama,
+    Backend,
+    Desktop,
+}
+
+/// A compute process running on a GPU
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct GpuProcess {
+    pub gpu_uuid: String,
+    pub pid: u32,
+    pub process_name: String,
+    #[ts(type = "number")]
+    pub used_mb: u64,
+    pub component: Option<GpuComponent>,
+}
+
+/// Processes sharing one GPU
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct GpuUsage {
+    pub uuid: String,
+    pub name: String,
+    #[ts(type = "number")]
+    pub total_mb: u64,
+    #[ts(type = "number")]
+    pub free_mb: u64,
+    pub processes: Vec<GpuProcess>,
+}
+
+impl GpuUsage {
+    fn components(&self) -> Vec<GpuComponent> {
+        let mut components: Vec<GpuComponent> =
+            self.processes.iter().filter_map(|p| p.component).collect();
+        components.sort_by_key(|c| *c as u8);
+        components.dedup();
+        components
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ContentionReport {
+    pub gpus: Vec<GpuUsage>,
+    /// True when more than one app component runs on the same GPU
+    pub contended: bool,
+    pub recommendation: Option<String>,
+}
+
+/// Split one CSV row of nvidia-smi output into trimmed fields
+fn csv_fields(line: &str) -> Vec<&str> {
+    line.split(',').map(str::trim).collect()
+}
+
+/// Parse `--query-gpu=uuid,name,memory.total,memory.free` rows
+fn parse_gpus(output: &str) -> Vec<GpuUsage> {
+    output
+        .lines()
+        .filter_map(|line| match csv_fields(line).as_slice() {
+            [uuid, name, total, free] => Some(GpuUsage {
+                uuid: uuid.to_string(),
+                name: name.to_string(),
+                total_mb: total.parse().ok()?,
+                free_mb: free.parse().ok()?,
+                processes: Vec::new(),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Parse `--query-compute-apps=gpu_uuid,pid,process_name,used_memory` rows
+fn parse_compute_apps(output: &str) -> Vec<GpuProcess> {
+    output
+        .lines()
+        .filter_map(|line| match csv_fields(line).as_slice() {
+            [gpu_uuid, pid, process_name, used_mb] => {
+                let pid = pid.parse().ok()?;
+                Some(GpuProcess {
+                    gpu_uuid: gpu_uuid.to_string(),
+                    pid,
+                    process_name: process_name.to_string(),
+                    // "[N/A]" on platforms that cannot report per-process memory
+                    used_mb: used_mb.parse().unwrap_or(0),
+                    component: attribute_process(pid, process_name),
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Map a GPU process to the app component that owns it, by pid or executable name
+fn attribute_process(pid: u32, process_name: &str) -> Option<GpuComponent> {
+    if pid == std::process::id() {
+        return Some(GpuComponent::Desktop);
+    }
+
+    let executable = Path::new(process_name)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    if executable.starts_with("ollama") {
+        Some(GpuComponent::Ollama)
+    } else if executable.starts_with("python") || executable.starts_with("uvicorn") {
+        Some(GpuComponent::Backend)
+    } else {
+        None
+    }
+}
+
+fn contention_report(mut gpus: Vec<GpuUsage>, processes: Vec<GpuProcess>) -> ContentionReport {
+    for process in processes {
+        if let Some(gpu) = gpus.iter_mut().find(|g| g.uuid == process.gpu_uuid) {
+            gpu.processes.push(process);
+        }
+    }
+
+    let shared = gpus.iter().find(|gpu| gpu.components().len() > 1);
+    let recommendation = shared.map(|gpu| {
+        let components = gpu.components();
+        if components.contains(&GpuComponent::Ollama) && components.contains(&GpuComponent::Backend) {
+            format!(
+                "Ollama and the backend share {} ({} MiB free); run backend embeddings on CPU or use a smaller model",
+                gpu.name, gpu.free_mb
+            )
+        } else {
+            format!(
+                "Multiple app components share {} ({} MiB free); move one to another device",
+                gpu.name, gpu.free_mb
+            )
+        }
+    });
+
+    ContentionReport {
+        contended: shared.is_some(),
+        recommendation,
+        gpus,
+    }
+}
+
+/// Enumerate GPU compute processes and report app components competing for a device
+pub async fn check_gpu_contention() -> Result<ContentionReport, String> {
+    let gpus = parse_gpus(&nvidia_smi("--query-gpu=uuid,name,memory.total,memory.free").await?);
+    if gpus.is_empty() {
+        return Err("nvidia-smi reported no GPUs".to_string());
+    }
+    let processes = parse_compute_apps(
+        &nvidia_smi("--query-compute-apps=gpu_uuid,pid,process_name,used_memory").await?,
+    );
+
+    let report = contention_report(gpus, processes);
+    if let Some(recommendation) = &report.recommendation {
+        log::warn!("GPU contention: {}", recommendation);
+    }
+    Ok(report)
+}
+
+// Tauri Commands
+
+#[tauri::command]
+pub async fn gpu_contention_check() -> Result<ContentionReport, String> {
+    check_gpu_contention().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GPUS: &str = "\
+GPU-1a2b, NVIDIA GeForce RTX 4090, 24564, 3120
+GPU-3c4d, NVIDIA RTX A4000, 16376, 16000
+";
+
+    const COMPUTE_APPS: &str = "\
+GPU-1a2b, 4312, /usr/local/bin/ollama, 9876
+GPU-1a2b, 5120, /opt/venv/bin/python3.11, 6120
+GPU-1a2b, 6001, C:\\Windows\\System32\\dwm.exe, [N/A]
+GPU-3c4d, 7002, ollama_llama_server, 512
+not, a, row
+";
+
+    #[test]
+    fn compute_apps_are_attributed_to_components() {
+        let processes = parse_compute_apps(COMPUTE_APPS);
+        assert_eq!(processes.len(), 4);
+
+        let summary: Vec<(u32, Option<GpuComponent>, u64)> =
+            processes.iter().map(|p| (p.pid, p.component, p.used_mb)).collect();
+        assert_eq!(
+            summary,
+            [
+                (4312, Some(GpuComponent::Ollama), 9876),
+                (5120, Some(GpuComponent::Backend), 6120),
+                // Unreported memory is 0, unknown executables belong to no component
+                (6001, None, 0),
+                (7002, Some(GpuComponent::Ollama), 512),
+            ]
+        );
+        assert_eq!(processes[1].gpu_uuid, "GPU-1a2b");
+        assert_eq!(processes[1].process_name, "/opt/venv/bin/python3.11");
+    }
+
+    #[test]
+    fn this_process_is_the_desktop_app() {
+        let row = format!("GPU-1a2b, {}, tactical-rag-desktop, 300", std::process::id());
+        assert_eq!(parse_compute_apps(&row)[0].component, Some(GpuComponent::Desktop));
+    }
+
+    #[test]
+    fn gpus_are_parsed_and_bad_rows_skipped() {
+        let gpus = parse_gpus(&format!("{}GPU-5e6f, Broken, [N/A], [N/A]\n", GPUS));
+        assert_eq!(gpus.len(), 2);
+        assert_eq!(gpus[0].name, "NVIDIA GeForce RTX 4090");
+        assert_eq!((gpus[0].total_mb, gpus[0].free_mb), (24564, 3120));
+    }
+
+    #[test]
+    fn ollama_and_backend_on_one_gpu_is_contention() {
+        let report = contention_report(parse_gpus(GPUS), parse_compute_apps(COMPUTE_APPS));
+
+        assert!(report.contended);
+        assert_eq!(report.gpus[0].processes.len(), 3);
+        assert_eq!(report.gpus[0].components(), [GpuComponent::Ollama, GpuComponent::Backend]);
+        assert_eq!(report.gpus[1].components(), [GpuComponent::Ollama]);
+        let recommendation = report.recommendation.unwrap();
+        assert!(recommendation.contains("NVIDIA GeForce RTX 4090 (3120 MiB free)"), "{}", recommendation);
+        assert!(recommendation.contains("embeddings on CPU"), "{}", recommendation);
+    }
+
+    #[test]
+    fn components_on_separate_gpus_do_not_contend() {
+        let apps = "GPU-1a2b, 4312, ollama, 9876\nGPU-3c4d, 5120, python, 6120\n";
+        let report = contention_report(parse_gpus(GPUS), parse_compute_apps(apps));
+
+        assert!(!report.contended);
+        assert_eq!(report.recommendation, None);
+    }
+}
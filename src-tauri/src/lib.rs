@@ -1,8 +1,32 @@
 mod sidecar;
 mod ollama;
+mod ndjson;
+mod endpoint;
+mod hardware;
+mod titles;
+mod followups;
+mod settings;
+mod profiles;
+mod models;
+mod capabilities;
+mod features;
+mod events;
+mod audit;
+mod paths;
+mod documents;
+mod proxy;
+mod smoke;
+mod operations;
+mod startup;
+mod safe_mode;
+mod bindings;
+#[cfg(test)]
+mod test_support;
 
 use std::sync::{Arc, Mutex};
+use endpoint::BackendEndpoint;
 use sidecar::BackendSidecar;
+use startup::StartupTask;
 use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -22,28 +46,154 @@ pub fn run() {
         )?;
       }
 
-      // Initialize backend sidecar
-      let sidecar = BackendSidecar::new(app.handle().clone());
-      let sidecar_state = Arc::new(Mutex::new(Some(sidecar)));
-      app.manage(sidecar_state);
+      // --validate-ipc logs event payloads that drift from the TypeScript bindings
+      bindings::set_validation(std::env::args().any(|arg| arg == bindings::VALIDATE_IPC_FLAG));
 
-      // Auto-start backend in development mode (disabled for now)
-      // Backend sidecar will be started manually or via Docker
+      // Safe mode (--safe-mode or repeated startup crashes) skips heavy tasks
+      let flag = std::env::args().any(|arg| arg == safe_mode::SAFE_MODE_FLAG);
+      let mut safe_mode = safe_mode::begin(app.handle(), flag);
+
+      // Initialize state in dependency order; a failed task skips its dependents
+      // instead of aborting startup, so the window always shows
+      let report = startup::run(app.handle(), vec![
+        // Reset persisted values that no longer pass their owner's checks
+        StartupTask::new("settings", &[], |app| {
+          let validators = [
+            endpoint::SETTINGS,
+            profiles::SETTINGS,
+            proxy::SETTINGS,
+            paths::SETTINGS,
+            audit::SETTINGS,
+            models::SETTINGS,
+            followups::SETTINGS,
+            operations::SETTINGS,
+          ]
+          .concat();
+          app.manage(settings::SettingResets(settings::validate(app, &validators)));
+          Ok(())
+        }),
+        // Proxy settings apply to every HTTP client built after this
+        StartupTask::new("proxy", &["settings"], |app| {
+          proxy::load(app);
+          Ok(())
+        }),
+        // Shared backend URL, persisted in the settings store
+        StartupTask::new("endpoint", &["settings", "proxy"], |app| {
+          app.manage(BackendEndpoint::load(app));
+          Ok(())
+        }),
+        StartupTask::new("capability_cache", &[], |app| {
+          app.manage(features::CapabilityCache::default());
+          Ok(())
+        }),
+        StartupTask::new("model_list_tracker", &[], |app| {
+          app.manage(models::ModelListTracker::default());
+          Ok(())
+        }),
+        // Pulls, smoke tests and benchmarks report and cancel through one registry
+        StartupTask::new("operations", &["settings"], |app| {
+          app.manage(operations::Operations::load(app));
+          Ok(())
+        }),
+        StartupTask::new("event_registry", &[], |app| {
+          app.manage(events::EventRegistry::default());
+          Ok(())
+        }),
+        // Local audit trail of destructive actions
+        StartupTask::new("audit_log", &["settings"], |app| {
+          app.manage(audit::AuditLog::open(app));
+          Ok(())
+        }),
+        // Initialize backend sidecar and start monitoring its health (and its replicas').
+        // The only background subsystem the desktop starts, so safe mode skips it
+        StartupTask::new("sidecar", &["endpoint"], |app| {
+          let endpoint = app.state::<BackendEndpoint>().inner().clone();
+          let sidecar = BackendSidecar::new(app.clone(), endpoint);
+          sidecar.monitor();
+          app.manage(Arc::new(Mutex::new(Some(sidecar))));
+          Ok(())
+        })
+        .heavy(),
+      ], safe_mode.is_active());
+
+      // Sidecar commands report "not initialized" if its task did not run
+      if app.try_state::<Arc<Mutex<Option<BackendSidecar>>>>().is_none() {
+        app.manage(Arc::new(Mutex::new(None::<BackendSidecar>)));
+      }
+
+      safe_mode.record_skipped(&report);
+      app.manage(safe_mode);
+      app.manage(report);
+
+      // The backend process itself is not launched yet (the sidecar only monitors it)
+      // Start it manually or via Docker
       if cfg!(debug_assertions) {
-        log::info!("Development mode: Backend sidecar available for manual start");
+        log::info!("Development mode: start the backend manually or via Docker");
       }
 
       Ok(())
     })
+    // Drop event subscriptions of closed windows
+    .on_window_event(|window, event| {
+      if let tauri::WindowEvent::Destroyed = event {
+        if let Some(registry) = window.try_state::<events::EventRegistry>() {
+          registry.remove_window(window.label());
+        }
+      }
+    })
     // Register Tauri commands
     .invoke_handler(tauri::generate_handler![
       sidecar::start_backend,
       sidecar::stop_backend,
       sidecar::get_backend_status,
+      endpoint::get_backend_url,
+      endpoint::get_backend_connection,
+      endpoint::set_backend_url,
+      profiles::list_profiles,
+      profiles::save_profile,
+      profiles::delete_profile,
+      profiles::activate_profile,
+      models::get_hotswap_snapshot,
+      models::check_model_list_changed,
+      models::switch_model,
+      models::get_model_fallback_chain,
+      models::set_model_fallback_chain,
+      capabilities::get_model_capabilities,
+      features::get_backend_capabilities,
+      hardware::gpu_contention_check,
+      audit::get_audit_log,
+      audit::export_audit_log,
+      audit::verify_audit_log,
+      paths::pick_authorized_root,
+      paths::list_authorized_roots,
+      paths::revoke_authorized_root,
+      documents::document_fingerprint,
+      proxy::get_proxy_settings,
+      proxy::set_proxy_settings,
+      smoke::pipeline_smoke_test,
+      operations::list_active_operations,
+      operations::cancel_operation,
+      events::subscribe_events,
+      events::unsubscribe_events,
+      startup::get_startup_report,
+      settings::get_settings_resets,
+      safe_mode::get_safe_mode_reason,
+      safe_mode::exit_safe_mode_and_restart,
       ollama::get_ollama_status,
       ollama::pull_qwen_model,
+      ollama::pull_model_stream,
+      ollama::cancel_pull,
       ollama::verify_qwen,
       ollama::get_recommended_qwen_model,
+      ollama::set_model_residency,
+      ollama::get_model_residency,
+      ollama::measure_model_latency,
+      ollama::probe_ollama_service,
+      ollama::benchmark_ollama,
+      titles::generate_conversation_title,
+      followups::suggest_followups,
+      followups::get_followup_mode,
+      followups::set_followup_mode,
     ])
     .run(tauri::generate_context!())
     .expect("error while running tauri application");
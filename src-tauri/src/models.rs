@@ -0,0 +1,660 @@
+// Backend Models
+// Model registry data from the backend combined with local GPU state
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use ts_rs::TS;
+use tauri::AppHandle;
+
+use crate::audit::AuditLog;
+use crate::capabilities;
+use crate::endpoint::{read_json_limited, BackendEndpoint, ResponseError, DEFAULT_MAX_RESPONSE_BYTES};
+use crate::events::{self, ModelSwitched, ModelsChanged};
+use crate::hardware;
+use crate::settings::{self, Validator};
+
+const FALLBACK_CHAIN_KEY: &str = "model_fallback_chain";
+
+/// Persisted settings owned by this module
+pub const SETTINGS: &[Validator] = &[Validator {
+    key: FALLBACK_CHAIN_KEY,
+    check: check_fallback_chain,
+}];
+
+fn check_fallback_chain(value: &serde_json::Value) -> Result<Option<serde_json::Value>, String> {
+    let chain: Vec<String> = settings::parse(value)?;
+    if chain.iter().any(|id| id.trim().is_empty()) {
+        return Err("empty model id".to_string());
+    }
+    Ok(None)
+}
+
+/// Model entry from the backend's /api/models/ registry
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ModelInfo {
+    pub id: String,
+    pub name: String,
+    pub backend: String,
+    pub parameters: String,
+    pub size: String,
+    pub speed_rating: u8,
+    pub quality_rating: u8,
+    pub min_vram_gb: u32,
+    pub recommended_vram_gb: u32,
+    pub description: String,
+    #[serde(default)]
+    pub use_cases: Vec<String>,
+    pub available: bool,
+    pub default: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ModelList {
+    pub models: Vec<ModelInfo>,
+    pub count: usize,
+    pub default_model_id: String,
+}
+
+impl ModelList {
+    /// Content hash of the list; changes when a model is added, removed or edited
+    pub fn version(&self) -> String {
+        let mut models: Vec<&ModelInfo> = self.models.iter().collect();
+        models.sort_by(|a, b| a.id.cmp(&b.id));
+        let canonical = serde_json::to_vec(&(models, &self.default_model_id)).unwrap_or_default();
+        hex::encode(&Sha256::digest(&canonical)[..8])
+    }
+}
+
+/// Model list version last shown to the UI, per backend URL
+#[derive(Default)]
+pub struct ModelListTracker {
+    last_seen: Mutex<Option<(String, String)>>,
+}
+
+impl ModelListTracker {
+    /// Record `version` as seen, returning whether it changed and the previous version
+    /// from the same backend. The first observation is only a baseline.
+    fn observe(&self, backend_url: &str, version: &str) -> (bool, Option<String>) {
+        let mut last_seen = self.last_seen.lock().unwrap();
+        let (changed, previous) = match last_seen.as_ref() {
+            None => (false, None),
+            Some((url, seen)) if url == backend_url => (seen != version, Some(seen.clone())),
+            Some(_) => (true, None),
+        };
+        *last_seen = Some((backend_url.to_string(), version.to_string()));
+        (changed, previous)
+    }
+}
+
+/// Result of comparing the backend's model list with the last one seen
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ModelListCheck {
+    pub changed: bool,
+    pub version: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ModelSelectResponse {
+    success: bool,
+    model_id: String,
+    model_name: String,
+    message: String,
+    #[serde(default)]
+    requires_restart: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SwitchFailure {
+    pub model_id: String,
+    pub error: String,
+}
+
+/// Result of a model switch, including any fallback that happened
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ModelSwitchResponse {
+    pub requested_model_id: String,
+    pub model_id: String,
+    pub model_name: String,
+    pub message: String,
+    pub requires_restart: bool,
+    pub fell_back: bool,
+    pub failures: Vec<SwitchFailure>,
+}
+
+/// A snapshot field that either loaded or failed independently of the others
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Fetched<T> {
+    Ok { value: T },
+    Failed { error: String },
+}
+
+impl<T> Fetched<T> {
+    fn value(&self) -> Option<&T> {
+        match self {
+            Fetched::Ok { value } => Some(value),
+            Fetched::Failed { .. } => None,
+        }
+    }
+}
+
+/// Everything the hotswap UI needs to render, gathered in one call
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct HotswapSnapshot {
+    pub models: Fetched<Vec<ModelInfo>>,
+    pub default_model_id: Option<String>,
+    pub model_list_version: Option<String>,
+    #[ts(as = "Fetched<f64>")]
+    pub gpu_free_vram_mb: Fetched<u64>,
+    /// Model id -> whether its minimum VRAM fits in free GPU memory
+    pub will_fit: HashMap<String, bool>,
+}
+
+/// Fetch the backend's model registry
+pub async fn fetch_models(endpoint: &BackendEndpoint) -> Result<ModelList, ResponseError> {
+    let response = endpoint
+        .request(Method::GET, "/api/models/")
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .map_err(ResponseError::unreachable)?;
+
+    if !response.status().is_success() {
+        return Err(ResponseError::Rejected {
+            message: format!("Backend /api/models/ returned {}", response.status()),
+        });
+    }
+
+    read_json_limited(response, DEFAULT_MAX_RESPONSE_BYTES).await
+}
+
+/// Ask the backend to select a model
+async fn select_model(endpoint: &BackendEndpoint, model_id: &str) -> Result<ModelSelectResponse, ResponseError> {
+    let response = endpoint
+        .request(Method::POST, "/api/models/select")
+        .json(&serde_json::json!({ "model_id": model_id }))
+        .timeout(Duration::from_secs(30))
+        .send()
+        .await
+        .map_err(ResponseError::unreachable)?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body: serde_json::Value = response.json().await.unwrap_or_default();
+        let detail = body["detail"].as_str().unwrap_or("no details").to_string();
+        return Err(ResponseError::Rejected {
+            message: format!("{} ({})", detail, status),
+        });
+    }
+
+    let selected: ModelSelectResponse = read_json_limited(response, DEFAULT_MAX_RESPONSE_BYTES).await?;
+
+    if selected.success {
+        Ok(selected)
+    } else {
+        Err(ResponseError::Rejected {
+            message: selected.message,
+        })
+    }
+}
+
+/// Switch to `model_id`, optionally walking the configured fallback chain on failure.
+///
+/// An unreachable backend ends the walk, since no other model could be selected either.
+pub async fn switch_to_model(
+    endpoint: &BackendEndpoint,
+    model_id: &str,
+    fallback_chain: &[String],
+) -> Result<ModelSwitchResponse, ResponseError> {
+    let candidates = std::iter::once(model_id)
+        .chain(fallback_chain.iter().map(String::as_str).filter(|id| *id != model_id));
+
+    let mut failures = Vec::new();
+    for candidate in candidates {
+        match select_model(endpoint, candidate).await {
+            Ok(selected) => {
+                let fell_back = candidate != model_id;
+                if fell_back {
+                    log::warn!("Model {} unavailable, fell back to {}", model_id, candidate);
+                }
+                return Ok(ModelSwitchResponse {
+                    requested_model_id: model_id.to_string(),
+                    model_id: selected.model_id,
+                    model_name: selected.model_name,
+                    message: selected.message,
+                    requires_restart: selected.requires_restart,
+                    fell_back,
+                    failures,
+                });
+            }
+            Err(ResponseError::Unreachable { message }) => {
+                log::warn!("Backend unreachable while switching to {}: {}", candidate, message);
+                return Err(ResponseError::Unreachable {
+                    message: format!("Backend unreachable, model not switched: {}", message),
+                });
+            }
+            Err(error) => {
+                log::warn!("Failed to switch to model {}: {}", candidate, error);
+                failures.push(SwitchFailure {
+                    model_id: candidate.to_string(),
+                    error: error.to_string(),
+                });
+            }
+        }
+    }
+
+    let summary: Vec<String> = failures
+        .iter()
+        .map(|f| format!("{}: {}", f.model_id, f.error))
+        .collect();
+    Err(ResponseError::Rejected {
+        message: format!("Failed to switch model ({})", summary.join("; ")),
+    })
+}
+
+async fn fetch_with_timeout<T, E: ToString>(
+    limit: Duration,
+    what: &str,
+    fetch: impl Future<Output = Result<T, E>>,
+) -> Fetched<T> {
+    match tokio::time::timeout(limit, fetch).await {
+        Ok(Ok(value)) => Fetched::Ok { value },
+        Ok(Err(error)) => Fetched::Failed {
+            error: error.to_string(),
+        },
+        Err(_) => Fetched::Failed {
+            error: format!("Timed out fetching {}", what),
+        },
+    }
+}
+
+const MODEL_LIST_TIMEOUT: Duration = Duration::from_secs(6);
+const GPU_MEMORY_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Gather the model list and free VRAM concurrently, each with its own timeout
+pub async fn hotswap_snapshot(endpoint: &BackendEndpoint) -> HotswapSnapshot {
+    gather_snapshot(
+        fetch_with_timeout(MODEL_LIST_TIMEOUT, "model list", fetch_models(endpoint)),
+        fetch_with_timeout(GPU_MEMORY_TIMEOUT, "GPU memory", hardware::gpu_free_vram_mb()),
+    )
+    .await
+}
+
+async fn gather_snapshot(
+    models: impl Future<Output = Fetched<ModelList>>,
+    gpu_free_vram_mb: impl Future<Output = Fetched<u64>>,
+) -> HotswapSnapshot {
+    let (models, gpu_free_vram_mb) = tokio::join!(models, gpu_free_vram_mb);
+
+    let default_model_id = models.value().map(|list| list.default_model_id.clone());
+    let model_list_version = models.value().map(ModelList::version);
+    let will_fit = match (models.value(), gpu_free_vram_mb.value()) {
+        (Some(list), Some(free_mb)) => list
+            .models
+            .iter()
+            .map(|m| (m.id.clone(), u64::from(m.min_vram_gb) * 1024 <= *free_mb))
+            .collect(),
+        _ => HashMap::new(),
+    };
+
+    let models = match models {
+        Fetched::Ok { value } => Fetched::Ok { value: value.models },
+        Fetched::Failed { error } => {
+            log::warn!("Hotswap snapshot: {}", error);
+            Fetched::Failed { error }
+        }
+    };
+    if let Fetched::Failed { error } = &gpu_free_vram_mb {
+        log::warn!("Hotswap snapshot: {}", error);
+    }
+
+    HotswapSnapshot {
+        models,
+        default_model_id,
+        model_list_version,
+        gpu_free_vram_mb,
+        will_fit,
+    }
+}
+
+/// Fetch the model list and compare it with the last one seen, returning the change to announce
+async fn check_model_list(
+    endpoint: &BackendEndpoint,
+    tracker: &ModelListTracker,
+) -> Result<(ModelListCheck, Option<ModelsChanged>), ResponseError> {
+    let list = fetch_models(endpoint).await?;
+    let version = list.version();
+
+    let (changed, previous) = tracker.observe(endpoint.get().as_str(), &version);
+    let change = changed.then(|| {
+        log::info!("Backend model list changed ({} models)", list.count);
+        ModelsChanged {
+            version: version.clone(),
+            previous,
+            count: list.count,
+        }
+    });
+
+    Ok((ModelListCheck { changed, version }, change))
+}
+
+// Tauri Commands
+
+#[tauri::command]
+pub async fn get_hotswap_snapshot(
+    endpoint: tauri::State<'_, BackendEndpoint>,
+    tracker: tauri::State<'_, ModelListTracker>,
+) -> Result<HotswapSnapshot, String> {
+    let snapshot = hotswap_snapshot(&endpoint).await;
+    if let Some(version) = &snapshot.model_list_version {
+        tracker.observe(endpoint.get().as_str(), version);
+    }
+    Ok(snapshot)
+}
+
+/// Compare the backend's model list with the last one seen, emitting "models-changed" if it differs
+#[tauri::command]
+pub async fn check_model_list_changed(
+    app: AppHandle,
+    endpoint: tauri::State<'_, BackendEndpoint>,
+    tracker: tauri::State<'_, ModelListTracker>,
+) -> Result<ModelListCheck, ResponseError> {
+    let (check, change) = check_model_list(&endpoint, &tracker).await?;
+    if let Some(change) = change {
+        events::emit(&app, change);
+    }
+    Ok(check)
+}
+
+#[tauri::command]
+pub async fn switch_model(
+    app: AppHandle,
+    endpoint: tauri::State<'_, BackendEndpoint>,
+    audit: tauri::State<'_, AuditLog>,
+    model_id: String,
+    fallback: Option<bool>,
+) -> Result<ModelSwitchResponse, ResponseError> {
+    let chain = if fallback.unwrap_or(false) {
+        settings::get::<Vec<String>>(&app, FALLBACK_CHAIN_KEY).unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+
+    let result = switch_to_model(&endpoint, &model_id, &chain).await;
+    audit.record("switch_model", &model_id, &chain, &result);
+    let switched = result?;
+
+    let capabilities = capabilities::model_capabilities(&switched.model_id).await;
+    events::emit(
+        &app,
+        ModelSwitched {
+            model_id: switched.model_id.clone(),
+            model_name: switched.model_name.clone(),
+            fell_back: switched.fell_back,
+            capabilities,
+        },
+    );
+
+    Ok(switched)
+}
+
+#[tauri::command]
+pub fn get_model_fallback_chain(app: AppHandle) -> Vec<String> {
+    settings::get(&app, FALLBACK_CHAIN_KEY).unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn set_model_fallback_chain(
+    app: AppHandle,
+    audit: tauri::State<'_, AuditLog>,
+    chain: Vec<String>,
+) -> Result<(), String> {
+    let result = settings::set(&app, FALLBACK_CHAIN_KEY, &chain);
+    audit.record("set_model_fallback_chain", FALLBACK_CHAIN_KEY, &chain, &result);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockResponse, MockServer};
+
+    fn model(id: &str, min_vram_gb: u32) -> serde_json::Value {
+        serde_json::json!({
+            "id": id,
+            "name": id,
+            "backend": "ollama",
+            "parameters": "7B",
+            "size": "4.7GB",
+            "speed_rating": 4,
+            "quality_rating": 3,
+            "min_vram_gb": min_vram_gb,
+            "recommended_vram_gb": min_vram_gb + 2,
+            "description": "",
+            "available": true,
+            "default": id == "small",
+        })
+    }
+
+    async fn registry() -> MockServer {
+        MockServer::start(|_| {
+            MockResponse::json(
+                200,
+                serde_json::json!({
+                    "models": [model("small", 4), model("large", 16)],
+                    "count": 2,
+                    "default_model_id": "small",
+                }),
+            )
+        })
+        .await
+    }
+
+    async fn gpu(result: Result<u64, String>, delay: Duration) -> Result<u64, String> {
+        tokio::time::sleep(delay).await;
+        result
+    }
+
+    #[tokio::test]
+    async fn snapshot_fetches_concurrently_and_flags_models_that_fit() {
+        let backend = registry().await;
+        let endpoint = BackendEndpoint::new(backend.url.parse().unwrap());
+        let delay = Duration::from_millis(150);
+
+        let started = std::time::Instant::now();
+        let snapshot = gather_snapshot(
+            fetch_with_timeout(MODEL_LIST_TIMEOUT, "model list", async {
+                tokio::time::sleep(delay).await;
+                fetch_models(&endpoint).await
+            }),
+            fetch_with_timeout(GPU_MEMORY_TIMEOUT, "GPU memory", gpu(Ok(8 * 1024), delay)),
+        )
+        .await;
+
+        assert!(started.elapsed() < delay * 2, "fetches ran one after the other");
+        assert_eq!(snapshot.models.value().map(Vec::len), Some(2));
+        assert_eq!(snapshot.default_model_id.as_deref(), Some("small"));
+        assert!(snapshot.model_list_version.is_some());
+        assert_eq!(snapshot.will_fit.get("small"), Some(&true));
+        assert_eq!(snapshot.will_fit.get("large"), Some(&false));
+    }
+
+    #[tokio::test]
+    async fn snapshot_keeps_the_model_list_when_the_gpu_query_fails() {
+        let backend = registry().await;
+        let endpoint = BackendEndpoint::new(backend.url.parse().unwrap());
+
+        let snapshot = gather_snapshot(
+            fetch_with_timeout(MODEL_LIST_TIMEOUT, "model list", fetch_models(&endpoint)),
+            fetch_with_timeout(
+                Duration::from_millis(50),
+                "GPU memory",
+                std::future::pending::<Result<u64, String>>(),
+            ),
+        )
+        .await;
+
+        assert!(matches!(snapshot.models, Fetched::Ok { .. }));
+        match &snapshot.gpu_free_vram_mb {
+            Fetched::Failed { error } => assert_eq!(error, "Timed out fetching GPU memory"),
+            Fetched::Ok { .. } => panic!("GPU query should have timed out"),
+        }
+        assert!(snapshot.will_fit.is_empty());
+    }
+
+    #[tokio::test]
+    async fn snapshot_keeps_gpu_memory_when_the_backend_fails() {
+        let backend = MockServer::start(|_| MockResponse::text(503, "starting")).await;
+        let endpoint = BackendEndpoint::new(backend.url.parse().unwrap());
+
+        let snapshot = gather_snapshot(
+            fetch_with_timeout(MODEL_LIST_TIMEOUT, "model list", fetch_models(&endpoint)),
+            fetch_with_timeout(GPU_MEMORY_TIMEOUT, "GPU memory", gpu(Ok(24 * 1024), Duration::ZERO)),
+        )
+        .await;
+
+        match &snapshot.models {
+            Fetched::Failed { error } => assert!(error.contains("503"), "{}", error),
+            Fetched::Ok { .. } => panic!("model list should have failed"),
+        }
+        assert_eq!(snapshot.gpu_free_vram_mb.value(), Some(&(24 * 1024)));
+        assert_eq!(snapshot.default_model_id, None);
+        assert_eq!(snapshot.model_list_version, None);
+        assert!(snapshot.will_fit.is_empty());
+    }
+
+    fn chain(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn switch_falls_back_until_a_model_loads() {
+        let backend = MockServer::start(|request| match request.json()["model_id"].as_str() {
+            Some("missing") => MockResponse::json(404, serde_json::json!({"detail": "Model not found"})),
+            Some("broken") => MockResponse::json(
+                200,
+                serde_json::json!({"success": false, "model_id": "broken", "model_name": "", "message": "Out of memory"}),
+            ),
+            Some(id) => MockResponse::json(
+                200,
+                serde_json::json!({"success": true, "model_id": id, "model_name": "Third", "message": "Switched"}),
+            ),
+            None => MockResponse::text(400, "no model_id"),
+        })
+        .await;
+        let endpoint = BackendEndpoint::new(backend.url.parse().unwrap());
+
+        let switched = switch_to_model(&endpoint, "missing", &chain(&["broken", "missing", "third"]))
+            .await
+            .unwrap();
+
+        assert_eq!(switched.requested_model_id, "missing");
+        assert_eq!(switched.model_id, "third");
+        assert!(switched.fell_back);
+        let failed: Vec<&str> = switched.failures.iter().map(|f| f.model_id.as_str()).collect();
+        assert_eq!(failed, ["missing", "broken"]);
+        assert_eq!(switched.failures[0].error, "Model not found (404 Not Found)");
+        assert_eq!(switched.failures[1].error, "Out of memory");
+        // The requested model is not retried when it also appears in the chain
+        assert_eq!(backend.requests_to("/api/models/select").len(), 3);
+    }
+
+    #[tokio::test]
+    async fn switch_fails_with_every_attempt_when_the_chain_runs_out() {
+        let backend = MockServer::start(|_| MockResponse::json(404, serde_json::json!({"detail": "Model not found"}))).await;
+        let endpoint = BackendEndpoint::new(backend.url.parse().unwrap());
+
+        match switch_to_model(&endpoint, "a", &chain(&["b"])).await {
+            Err(ResponseError::Rejected { message }) => {
+                assert_eq!(message, "Failed to switch model (a: Model not found (404 Not Found); b: Model not found (404 Not Found))")
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn switch_stops_at_an_unreachable_backend() {
+        let backend = MockServer::start(|_| MockResponse::hang_up()).await;
+        let endpoint = BackendEndpoint::new(backend.url.parse().unwrap());
+
+        let error = switch_to_model(&endpoint, "a", &chain(&["b", "c"])).await.unwrap_err();
+        match error {
+            ResponseError::Unreachable { message } => {
+                assert!(message.starts_with("Backend unreachable, model not switched"), "{}", message)
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(backend.requests_to("/api/models/select").len(), 1);
+    }
+
+    fn list(models: Vec<serde_json::Value>, default_model_id: &str) -> serde_json::Value {
+        serde_json::json!({"count": models.len(), "models": models, "default_model_id": default_model_id})
+    }
+
+    fn version_of(list: serde_json::Value) -> String {
+        serde_json::from_value::<ModelList>(list).unwrap().version()
+    }
+
+    #[test]
+    fn model_list_version_ignores_order_but_not_content() {
+        let base = version_of(list(vec![model("small", 4), model("large", 16)], "small"));
+
+        assert_eq!(base, version_of(list(vec![model("large", 16), model("small", 4)], "small")));
+        assert_ne!(base, version_of(list(vec![model("small", 4), model("large", 24)], "small")));
+        assert_ne!(base, version_of(list(vec![model("small", 4), model("large", 16)], "large")));
+        assert_ne!(base, version_of(list(vec![model("small", 4)], "small")));
+    }
+
+    #[test]
+    fn tracker_compares_versions_from_the_same_backend() {
+        let tracker = ModelListTracker::default();
+
+        assert_eq!(tracker.observe("http://a", "v1"), (false, None));
+        assert_eq!(tracker.observe("http://a", "v1"), (false, Some("v1".to_string())));
+        assert_eq!(tracker.observe("http://a", "v2"), (true, Some("v1".to_string())));
+        // A different backend always counts as a change, with nothing to compare against
+        assert_eq!(tracker.observe("http://b", "v2"), (true, None));
+    }
+
+    #[tokio::test]
+    async fn check_announces_a_changed_model_list_once() {
+        let current = std::sync::Arc::new(Mutex::new(list(vec![model("small", 4)], "small")));
+        let served = current.clone();
+        let backend = MockServer::start(move |_| MockResponse::json(200, served.lock().unwrap().clone())).await;
+        let endpoint = BackendEndpoint::new(backend.url.parse().unwrap());
+        let tracker = ModelListTracker::default();
+
+        let (first, change) = check_model_list(&endpoint, &tracker).await.unwrap();
+        assert!(!first.changed);
+        assert!(change.is_none());
+
+        *current.lock().unwrap() = list(vec![model("small", 4), model("large", 16)], "small");
+        let (second, change) = check_model_list(&endpoint, &tracker).await.unwrap();
+        assert!(second.changed);
+        assert_ne!(second.version, first.version);
+        let change = change.unwrap();
+        assert_eq!(change.version, second.version);
+        assert_eq!(change.previous, Some(first.version));
+        assert_eq!(change.count, 2);
+
+        let (third, change) = check_model_list(&endpoint, &tracker).await.unwrap();
+        assert!(!third.changed);
+        assert!(change.is_none());
+    }
+
+    #[tokio::test]
+    async fn failed_check_keeps_the_last_seen_version() {
+        let backend = MockServer::start(|_| MockResponse::text(503, "starting")).await;
+        let endpoint = BackendEndpoint::new(backend.url.parse().unwrap());
+        let tracker = ModelListTracker::default();
+        tracker.observe(endpoint.get().as_str(), "v1");
+
+        assert!(check_model_list(&endpoint, &tracker).await.is_err());
+        assert_eq!(tracker.observe(endpoint.get().as_str(), "v1"), (false, Some("v1".to_string())));
+    }
+}
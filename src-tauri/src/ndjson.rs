@@ -0,0 +1,130 @@
+// Newline-Delimited JSON Decoder
+// Reassembles JSON values from arbitrarily fragmented network chunks
+
+use serde::de::DeserializeOwned;
+
+/// Incremental decoder for NDJSON streams such as Ollama's streaming endpoints.
+///
+/// Bytes are buffered until a full line is available, so an object split
+/// across several chunks, or several objects packed into one chunk, both
+/// decode correctly and in order.
+#[derive(Debug, Default)]
+pub struct NdjsonDecoder {
+    buffer: Vec<u8>,
+}
+
+impl NdjsonDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a chunk and return every complete value it finished
+    pub fn push<T: DeserializeOwned>(&mut self, chunk: &[u8]) -> Result<Vec<T>, String> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut values = Vec::new();
+        while let Some(newline) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=newline).collect();
+            if let Some(value) = Self::decode_line(&line)? {
+                values.push(value);
+            }
+        }
+
+        Ok(values)
+    }
+
+    /// Decode whatever remains once the stream has ended without a final newline
+    pub fn finish<T: DeserializeOwned>(&mut self) -> Result<Option<T>, String> {
+        let line = std::mem::take(&mut self.buffer);
+        Self::decode_line(&line)
+    }
+
+    fn decode_line<T: DeserializeOwned>(line: &[u8]) -> Result<Option<T>, String> {
+        if line.iter().all(|b| b.is_ascii_whitespace()) {
+            return Ok(None);
+        }
+
+        serde_json::from_slice(line)
+            .map(Some)
+            .map_err(|e| format!("Invalid JSON line in stream: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use serde_json::{json, Value};
+    use crate::test_support::{MockResponse, MockServer};
+
+    const STREAM: &[u8] = "{\"response\":\"caf\u{e9}\"}\n{\"done\":false}\r\n\n{\"done\":true}".as_bytes();
+
+    fn expected() -> Vec<Value> {
+        vec![json!({"response": "caf\u{e9}"}), json!({"done": false}), json!({"done": true})]
+    }
+
+    fn decode_in(chunks: &[&[u8]]) -> Vec<Value> {
+        let mut decoder = NdjsonDecoder::new();
+        let mut values = Vec::new();
+        for chunk in chunks {
+            values.extend(decoder.push::<Value>(chunk).unwrap());
+        }
+        values.extend(decoder.finish::<Value>().unwrap());
+        values
+    }
+
+    #[test]
+    fn decodes_across_every_split_point() {
+        // Includes splits inside the two-byte UTF-8 sequence and the CRLF
+        for split in 0..=STREAM.len() {
+            let (head, tail) = STREAM.split_at(split);
+            assert_eq!(decode_in(&[head, tail]), expected(), "split at {}", split);
+        }
+    }
+
+    #[test]
+    fn decodes_byte_by_byte_and_all_at_once() {
+        let bytes: Vec<&[u8]> = STREAM.chunks(1).collect();
+        assert_eq!(decode_in(&bytes), expected());
+        assert_eq!(decode_in(&[STREAM]), expected());
+    }
+
+    #[test]
+    fn values_are_returned_as_soon_as_their_line_ends() {
+        let mut decoder = NdjsonDecoder::new();
+        assert!(decoder.push::<Value>(b"{\"a\":").unwrap().is_empty());
+        assert_eq!(decoder.push::<Value>(b"1}\n{\"b\"").unwrap(), vec![json!({"a": 1})]);
+        // The stream ended partway through the second object
+        assert!(decoder.finish::<Value>().unwrap_err().starts_with("Invalid JSON line in stream"));
+    }
+
+    #[test]
+    fn invalid_lines_are_errors_and_blank_ones_are_skipped() {
+        let mut decoder = NdjsonDecoder::new();
+        assert!(decoder.push::<Value>(b"  \n\n").unwrap().is_empty());
+        assert!(decoder.push::<Value>(b"not json\n").is_err());
+        assert_eq!(decoder.finish::<Value>(), Ok(None));
+    }
+
+    #[tokio::test]
+    async fn decodes_a_fragmented_http_stream() {
+        let chunks = STREAM.chunks(5).map(<[u8]>::to_vec).collect();
+        let server = MockServer::start(move |_| {
+            MockResponse::chunks(Vec::clone(&chunks)).chunk_delay(Duration::from_millis(2))
+        })
+        .await;
+
+        let mut response = reqwest::get(&server.url).await.unwrap();
+        let mut decoder = NdjsonDecoder::new();
+        let mut values = Vec::new();
+        let mut reads = 0;
+        while let Some(chunk) = response.chunk().await.unwrap() {
+            reads += 1;
+            values.extend(decoder.push::<Value>(&chunk).unwrap());
+        }
+        values.extend(decoder.finish::<Value>().unwrap());
+
+        assert!(reads > 1);
+        assert_eq!(values, expected());
+    }
+}
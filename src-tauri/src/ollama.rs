@@ -1,10 +1,33 @@
 // Ollama Detection and Configuration
 // Simplified for Qwen model integration
 
+use std::fmt;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use tauri::AppHandle;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::events::{self, OperationKind, PullFinished, PullLayerProgress, PullOutcome, PullProgress};
+use crate::hardware::{self, DiskSpaceError, SpaceProbe};
+use crate::ndjson::NdjsonDecoder;
+use crate::operations::{self, OperationHandle, OperationHost, Operations};
+use crate::proxy;
+
+pub const OLLAMA_API_URL: &str = "http://127.0.0.1:11434";
+const OLLAMA_ADDR: &str = "127.0.0.1:11434";
+const OLLAMA_REGISTRY_URL: &str = "https://registry.ollama.ai";
+
+/// Approximate download sizes of the models we recommend, in bytes
+const KNOWN_MODEL_SIZES: &[(&str, u64)] = &[
+    ("qwen2.5:3b-instruct-q4_K_M", 1_930_000_000),
+    ("qwen2.5:7b-instruct-q4_K_M", 4_680_000_000),
+    ("qwen2.5:14b-instruct-q4_K_M", 8_990_000_000),
+    ("qwen2.5:32b-instruct-q4_K_M", 19_850_000_000),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct OllamaStatus {
     pub installed: bool,
     pub running: bool,
@@ -75,10 +98,10 @@ pub fn detect_ollama() -> OllamaStatus {
     status
 }
 
-/// Check if Ollama service is running by trying to connect
+/// Check if Ollama service is running by trying to connect once
 fn check_ollama_service() -> bool {
     // Try to connect to Ollama API
-    match std::net::TcpStream::connect("127.0.0.1:11434") {
+    match std::net::TcpStream::connect(OLLAMA_ADDR) {
         Ok(_) => {
             log::info!("Ollama service is running on localhost:11434");
             true
@@ -90,6 +113,80 @@ fn check_ollama_service() -> bool {
     }
 }
 
+/// Retry policy for the Ollama readiness probe
+#[derive(Debug, Clone, Copy)]
+pub struct ProbeConfig {
+    pub max_attempts: u32,
+    pub interval: Duration,
+    pub max_interval: Duration,
+}
+
+impl Default for ProbeConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            interval: Duration::from_millis(250),
+            max_interval: Duration::from_secs(2),
+        }
+    }
+}
+
+/// Outcome of waiting for the Ollama service to accept connections
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ProbeResult {
+    pub running: bool,
+    pub attempts: u32,
+    #[ts(type = "number")]
+    pub waited_ms: u64,
+}
+
+/// Wait for the Ollama service to accept connections, retrying with jittered backoff
+pub async fn wait_for_ollama_service(config: ProbeConfig) -> ProbeResult {
+    wait_for_service_at(OLLAMA_ADDR, config).await
+}
+
+async fn wait_for_service_at(addr: &str, config: ProbeConfig) -> ProbeResult {
+    let started = Instant::now();
+    let mut delay = config.interval;
+    let mut attempts = 0;
+
+    while attempts < config.max_attempts.max(1) {
+        attempts += 1;
+
+        let connect = tokio::net::TcpStream::connect(addr);
+        if let Ok(Ok(_)) = tokio::time::timeout(Duration::from_secs(1), connect).await {
+            log::info!("Ollama service reachable after {} attempt(s)", attempts);
+            return ProbeResult {
+                running: true,
+                attempts,
+                waited_ms: started.elapsed().as_millis() as u64,
+            };
+        }
+
+        if attempts < config.max_attempts {
+            log::debug!("Ollama not reachable (attempt {}), retrying", attempts);
+            tokio::time::sleep(delay + jitter(delay / 2)).await;
+            delay = (delay * 2).min(config.max_interval);
+        }
+    }
+
+    log::warn!("Ollama service not reachable after {} attempts", attempts);
+    ProbeResult {
+        running: false,
+        attempts,
+        waited_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+/// Pseudo-random duration in [0, max) so concurrent probes don't retry in lockstep
+fn jitter(max: Duration) -> Duration {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    max.mul_f64((nanos % 1000) as f64 / 1000.0)
+}
+
 /// Get list of installed Ollama models
 fn get_installed_models() -> Vec<String> {
     match Command::new("ollama").arg("list").output() {
@@ -116,36 +213,381 @@ fn get_installed_models() -> Vec<String> {
     }
 }
 
-/// Pull a model from Ollama registry
-pub async fn pull_model(model_name: &str) -> Result<(), String> {
+#[derive(Debug, Deserialize)]
+struct TagsResponse {
+    #[serde(default)]
+    models: Vec<TagsModel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagsModel {
+    name: String,
+}
+
+/// Names of the models installed in the Ollama instance at `base_url`, via /api/tags
+pub async fn installed_models(base_url: &str) -> Result<Vec<String>, String> {
+    let response = proxy::client()
+        .get(format!("{}/api/tags", base_url))
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Ollama: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Ollama /api/tags returned {}", response.status()));
+    }
+
+    let tags: TagsResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse /api/tags response: {}", e))?;
+    Ok(tags.models.into_iter().map(|m| m.name).collect())
+}
+
+/// Error returned when a model pull cannot start or fails
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(tag = "kind")]
+pub enum PullError {
+    InsufficientDisk {
+        #[ts(type = "number")]
+        required: u64,
+        #[ts(type = "number")]
+        available: u64,
+    },
+    Failed { message: String },
+}
+
+impl fmt::Display for PullError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PullError::InsufficientDisk { required, available } => write!(
+                f,
+                "Insufficient disk space: {} bytes required, {} available",
+                required, available
+            ),
+            PullError::Failed { message } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl From<String> for PullError {
+    fn from(message: String) -> Self {
+        PullError::Failed { message }
+    }
+}
+
+/// Directory Ollama stores model blobs in
+pub fn models_dir() -> PathBuf {
+    if let Some(dir) = std::env::var_os("OLLAMA_MODELS") {
+        return PathBuf::from(dir);
+    }
+
+    let home = if cfg!(target_os = "windows") {
+        std::env::var_os("USERPROFILE")
+    } else {
+        std::env::var_os("HOME")
+    };
+
+    home.map(PathBuf::from)
+        .unwrap_or_default()
+        .join(".ollama")
+        .join("models")
+}
+
+/// Expected download size of a model, if known
+pub fn known_model_size(model_name: &str) -> Option<u64> {
+    KNOWN_MODEL_SIZES
+        .iter()
+        .find(|(name, _)| *name == model_name)
+        .map(|(_, size)| *size)
+}
+
+#[derive(Debug, Deserialize)]
+struct RegistryManifest {
+    config: Option<RegistryLayer>,
+    #[serde(default)]
+    layers: Vec<RegistryLayer>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RegistryLayer {
+    #[serde(default)]
+    size: u64,
+}
+
+/// Total size of a model's layers according to the Ollama registry manifest
+async fn registry_model_size(model_name: &str) -> Result<u64, String> {
+    let (repository, tag) = model_name.split_once(':').unwrap_or((model_name, "latest"));
+    if repository.contains('.') && repository.contains('/') {
+        // Models hosted on other registries (e.g. hf.co/...) aren't resolvable here
+        return Err(format!("{} is not hosted on the Ollama registry", model_name));
+    }
+    let repository = if repository.contains('/') {
+        repository.to_string()
+    } else {
+        format!("library/{}", repository)
+    };
+
+    let client = proxy::client();
+    let response = client
+        .get(format!("{}/v2/{}/manifests/{}", OLLAMA_REGISTRY_URL, repository, tag))
+        .header(
+            reqwest::header::ACCEPT,
+            "application/vnd.docker.distribution.manifest.v2+json",
+        )
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Ollama registry: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Registry manifest lookup returned {}", response.status()));
+    }
+
+    let manifest: RegistryManifest = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse registry manifest: {}", e))?;
+
+    Ok(manifest.config.map(|c| c.size).unwrap_or(0) + manifest.layers.iter().map(|l| l.size).sum::<u64>())
+}
+
+/// Expected download size from the known-size table, then the registry manifest
+async fn expected_model_size(model_name: &str) -> Option<u64> {
+    if let Some(size) = known_model_size(model_name) {
+        return Some(size);
+    }
+
+    match registry_model_size(model_name).await {
+        Ok(size) if size > 0 => Some(size),
+        Ok(_) => None,
+        Err(e) => {
+            log::info!("Could not determine size of {}: {}", model_name, e);
+            None
+        }
+    }
+}
+
+/// Map an Ollama pull failure, recognizing out-of-space errors
+fn pull_failure(message: &str, expected_size: Option<u64>, free_space: SpaceProbe) -> PullError {
+    let lower = message.to_lowercase();
+    if lower.contains("no space left") || lower.contains("not enough space") || lower.contains("disk full") {
+        return PullError::InsufficientDisk {
+            required: expected_size.unwrap_or(0),
+            available: free_space(&models_dir()).unwrap_or(0),
+        };
+    }
+
+    PullError::Failed {
+        message: format!("Failed to pull model: {}", message.trim()),
+    }
+}
+
+/// Refuse to start a pull that would not fit in the Ollama models directory
+fn ensure_space_for_pull(
+    model_name: &str,
+    dir: &Path,
+    expected_size: Option<u64>,
+    free_space: SpaceProbe,
+) -> Result<(), PullError> {
+    let Some(required) = expected_size else {
+        log::info!("Unknown size for {}, skipping disk space check", model_name);
+        return Ok(());
+    };
+
+    match hardware::check_disk_space(free_space, dir, required) {
+        Ok(_) => Ok(()),
+        Err(DiskSpaceError::Insufficient { required, available }) => {
+            Err(PullError::InsufficientDisk { required, available })
+        }
+        Err(DiskSpaceError::ProbeFailed(e)) => {
+            log::warn!("Skipping disk space check for {}: {}", model_name, e);
+            Ok(())
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PullResponseLine {
+    #[serde(default)]
+    status: String,
+    digest: Option<String>,
+    total: Option<u64>,
+    completed: Option<u64>,
+    error: Option<String>,
+}
+
+/// Run an Ollama pull, handing each status line to `on_line`.
+///
+/// Unless `force` is set, the pull is refused when the model is known not to
+/// fit in the free space `free_space` reports for the Ollama models directory.
+/// Registry auth and redirects are handled by Ollama itself.
+async fn stream_pull_at(
+    base_url: &str,
+    model_name: &str,
+    force: bool,
+    free_space: SpaceProbe,
+    mut on_line: impl FnMut(&PullResponseLine),
+) -> Result<(), PullError> {
+    let expected_size = expected_model_size(model_name).await;
+    if !force {
+        ensure_space_for_pull(model_name, &models_dir(), expected_size, free_space)?;
+    }
+
     log::info!("Pulling model: {}", model_name);
 
-    let output = tokio::process::Command::new("ollama")
-        .arg("pull")
-        .arg(model_name)
-        .output()
+    let client = proxy::client();
+    let mut response = client
+        .post(format!("{}/api/pull", base_url))
+        .json(&serde_json::json!({ "model": model_name, "stream": true }))
+        .send()
         .await
-        .map_err(|e| format!("Failed to execute ollama pull: {}", e))?;
+        .map_err(|e| format!("Failed to reach Ollama: {}", e))?;
 
-    if output.status.success() {
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(pull_failure(&format!("{} {}", status, body), expected_size, free_space));
+    }
+
+    let mut decoder = NdjsonDecoder::new();
+    let mut succeeded = false;
+    let mut handle_line = |line: PullResponseLine| -> Result<(), PullError> {
+        if let Some(error) = &line.error {
+            return Err(pull_failure(error, expected_size, free_space));
+        }
+        succeeded |= line.status == "success";
+        on_line(&line);
+        Ok(())
+    };
+
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Pull stream interrupted: {}", e))?
+    {
+        for line in decoder.push::<PullResponseLine>(&chunk)? {
+            handle_line(line)?;
+        }
+    }
+    if let Some(line) = decoder.finish::<PullResponseLine>()? {
+        handle_line(line)?;
+    }
+
+    if succeeded {
         log::info!("Successfully pulled model: {}", model_name);
         Ok(())
     } else {
-        let error = String::from_utf8_lossy(&output.stderr);
-        Err(format!("Failed to pull model: {}", error))
+        Err(format!("Pull of {} ended before completing", model_name).into())
     }
 }
 
+fn emit_pull_progress(app: &impl OperationHost, model_name: &str, line: &PullResponseLine) {
+    if !operations::legacy_events(app) {
+        return;
+    }
+    app.emit_event(PullProgress {
+        model: model_name.to_string(),
+        status: line.status.clone(),
+        digest: line.digest.clone(),
+        total: line.total,
+        completed: line.completed,
+    });
+}
+
+/// Pull a model from Ollama registry as a cancellable operation
+pub async fn pull_model(app: &AppHandle, model_name: &str, force: bool) -> Result<(), PullError> {
+    let operation = operations::begin(app, OperationKind::ModelPull, model_name);
+    operation
+        .run(app, pull_with_layers(app, &operation, OLLAMA_API_URL, model_name, force, hardware::available_space))
+        .await
+        .map(|_| ())
+}
+
+/// One blob of a model being pulled
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct PullLayer {
+    pub digest: String,
+    #[ts(type = "number")]
+    pub total: u64,
+    #[ts(type = "number")]
+    pub completed: u64,
+    /// Already present locally; Ollama reported it complete on first sight
+    pub cached: bool,
+}
+
+/// Pull a model, reporting bytes across all layers on "operation-progress"
+async fn pull_with_layers(
+    app: &impl OperationHost,
+    operation: &OperationHandle,
+    base_url: &str,
+    model_name: &str,
+    force: bool,
+    free_space: SpaceProbe,
+) -> Result<Vec<PullLayer>, PullError> {
+    let mut layers: Vec<PullLayer> = Vec::new();
+
+    stream_pull_at(base_url, model_name, force, free_space, |line| {
+        emit_pull_progress(app, model_name, line);
+
+        let (Some(digest), Some(total)) = (&line.digest, line.total) else {
+            operation.phase(app, &line.status, None);
+            return;
+        };
+        let completed = line.completed.unwrap_or(0);
+        let index = match layers.iter().position(|layer| &layer.digest == digest) {
+            Some(index) => {
+                let layer = &mut layers[index];
+                layer.total = total;
+                layer.completed = layer.completed.max(completed);
+                index
+            }
+            None => {
+                layers.push(PullLayer {
+                    digest: digest.clone(),
+                    total,
+                    completed,
+                    cached: total > 0 && completed >= total,
+                });
+                layers.len() - 1
+            }
+        };
+
+        let completed: u64 = layers.iter().map(|layer| layer.completed).sum();
+        let total: u64 = layers.iter().map(|layer| layer.total).sum();
+        operation.progress(app, "downloading", completed, Some(total), "bytes");
+
+        if operations::legacy_events(app) {
+            let layer = &layers[index];
+            app.emit_event(PullLayerProgress {
+                handle: operation.id().to_string(),
+                model: model_name.to_string(),
+                digest: layer.digest.clone(),
+                total: layer.total,
+                completed: layer.completed,
+                cached: layer.cached,
+            });
+        }
+    })
+    .await?;
+
+    Ok(layers)
+}
+
 /// Verify Qwen model is available, pull if not
-pub async fn ensure_qwen_model(model_name: &str) -> Result<(), String> {
-    let status = detect_ollama();
+pub async fn ensure_qwen_model(app: &AppHandle, model_name: &str, force: bool) -> Result<(), PullError> {
+    let mut status = detect_ollama();
 
     if !status.installed {
-        return Err("Ollama not installed. Please install from https://ollama.com".to_string());
+        return Err("Ollama not installed. Please install from https://ollama.com".to_string().into());
     }
 
     if !status.running {
-        return Err("Ollama service not running. Please start Ollama.".to_string());
+        // The service may still be starting up
+        if !wait_for_ollama_service(ProbeConfig::default()).await.running {
+            return Err("Ollama service not running. Please start Ollama.".to_string().into());
+        }
+        status = detect_ollama();
     }
 
     // Check if model already exists
@@ -156,7 +598,520 @@ pub async fn ensure_qwen_model(model_name: &str) -> Result<(), String> {
 
     // Model not found, pull it
     log::info!("Qwen model not found, pulling: {}", model_name);
-    pull_model(model_name).await
+    pull_model(app, model_name, force).await
+}
+
+/// A model currently loaded into memory, as reported by /api/ps
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct LoadedModel {
+    pub name: String,
+    #[ts(type = "number")]
+    pub size: u64,
+    #[ts(type = "number")]
+    pub size_vram: u64,
+    pub expires_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PsResponse {
+    #[serde(default)]
+    models: Vec<PsModel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PsModel {
+    name: String,
+    #[serde(default)]
+    size: u64,
+    #[serde(default)]
+    size_vram: u64,
+    expires_at: Option<String>,
+}
+
+/// Model details reported by /api/show
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ModelDetails {
+    /// Feature flags such as "completion", "tools", "vision", "thinking"
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+    #[serde(default)]
+    pub model_info: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ModelDetails {
+    /// Trained context length, reported under an architecture-specific key
+    pub fn context_length(&self) -> Option<u64> {
+        self.model_info
+            .iter()
+            .find(|(key, _)| key.ends_with(".context_length"))
+            .and_then(|(_, value)| value.as_u64())
+    }
+}
+
+/// Get details about an installed model
+pub async fn show_model(model_name: &str) -> Result<ModelDetails, String> {
+    let client = proxy::client();
+    let response = client
+        .post(format!("{}/api/show", OLLAMA_API_URL))
+        .json(&serde_json::json!({ "model": model_name }))
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Ollama: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Ollama /api/show returned {} for {}", response.status(), model_name));
+    }
+
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse /api/show response: {}", e))
+}
+
+/// Parse a keep_alive value into the JSON form Ollama expects.
+///
+/// Bare integers ("-1", "0", "300") are seconds and sent as numbers;
+/// anything else must be a duration like "30m", "1h" or "90s".
+pub fn parse_keep_alive(keep_alive: &str) -> Result<serde_json::Value, String> {
+    let value = keep_alive.trim();
+
+    if let Ok(seconds) = value.parse::<i64>() {
+        return Ok(serde_json::json!(seconds));
+    }
+
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    let unit_start = digits
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(digits.len());
+    let (amount, unit) = digits.split_at(unit_start);
+
+    if amount.is_empty() || amount.parse::<f64>().is_err() {
+        return Err(format!("Invalid keep_alive value: {:?}", keep_alive));
+    }
+
+    match unit {
+        "ms" | "s" | "m" | "h" => Ok(serde_json::json!(value)),
+        _ => Err(format!(
+            "Invalid keep_alive unit in {:?} (expected ms, s, m or h)",
+            keep_alive
+        )),
+    }
+}
+
+/// Load or unload a model by issuing an empty generate request with keep_alive
+pub async fn set_keep_alive(model_name: &str, keep_alive: &str) -> Result<(), String> {
+    set_keep_alive_at(OLLAMA_API_URL, model_name, keep_alive).await
+}
+
+async fn set_keep_alive_at(base_url: &str, model_name: &str, keep_alive: &str) -> Result<(), String> {
+    let keep_alive = parse_keep_alive(keep_alive)?;
+    log::info!("Setting keep_alive for {} to {}", model_name, keep_alive);
+
+    let response = post_keep_alive(base_url, model_name, keep_alive)
+        .await
+        .map_err(|e| format!("Failed to reach Ollama: {}", e))?;
+
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        Err(format!("Ollama rejected keep_alive update ({}): {}", status, body))
+    }
+}
+
+/// Empty generate request that only updates how long the model stays loaded
+async fn post_keep_alive(
+    base_url: &str,
+    model_name: &str,
+    keep_alive: serde_json::Value,
+) -> Result<reqwest::Response, reqwest::Error> {
+    proxy::client()
+        .post(format!("{}/api/generate", base_url))
+        .json(&serde_json::json!({
+            "model": model_name,
+            "keep_alive": keep_alive,
+            "stream": false,
+        }))
+        .timeout(Duration::from_secs(300))
+        .send()
+        .await
+}
+
+/// Get the models currently loaded into memory
+pub async fn get_loaded_models() -> Result<Vec<LoadedModel>, String> {
+    get_loaded_models_at(OLLAMA_API_URL).await
+}
+
+async fn get_loaded_models_at(base_url: &str) -> Result<Vec<LoadedModel>, String> {
+    let client = proxy::client();
+    let response = client
+        .get(format!("{}/api/ps", base_url))
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Ollama: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err(format!("Ollama /api/ps returned {}", response.status()));
+    }
+
+    let ps: PsResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse /api/ps response: {}", e))?;
+
+    Ok(ps
+        .models
+        .into_iter()
+        .map(|m| LoadedModel {
+            name: m.name,
+            size: m.size,
+            size_vram: m.size_vram,
+            expires_at: m.expires_at,
+        })
+        .collect())
+}
+
+/// Cold vs warm first-token latency for a model
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct LatencyProfile {
+    pub model: String,
+    #[ts(type = "number")]
+    pub cold_ttft_ms: u64,
+    #[ts(type = "number")]
+    pub warm_ttft_ms: u64,
+    pub tokens_per_sec: Option<f64>,
+}
+
+/// Why a latency measurement did not complete
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(tag = "kind")]
+pub enum LatencyError {
+    /// Ollama could not be reached
+    Unreachable { message: String },
+    /// Ollama answered but could not unload or load the model
+    LoadFailed { model: String, message: String },
+    /// The generation stream broke off or ended before completing
+    Interrupted { message: String },
+}
+
+impl fmt::Display for LatencyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LatencyError::Unreachable { message } | LatencyError::Interrupted { message } => write!(f, "{}", message),
+            LatencyError::LoadFailed { model, message } => write!(f, "Model {} failed to load: {}", model, message),
+        }
+    }
+}
+
+/// Timing of a single streamed generation
+#[derive(Debug, Clone)]
+struct GenerationTiming {
+    ttft: Duration,
+    tokens_per_sec: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GenerateResponseLine {
+    #[serde(default)]
+    response: String,
+    #[serde(default)]
+    done: bool,
+    eval_count: Option<u64>,
+    eval_duration: Option<u64>,
+    prompt_eval_count: Option<u64>,
+    prompt_eval_duration: Option<u64>,
+    error: Option<String>,
+}
+
+/// Tokens per second from an Ollama token count and duration in nanoseconds
+fn token_rate(count: Option<u64>, duration_ns: Option<u64>) -> Option<f64> {
+    match (count, duration_ns) {
+        (Some(count), Some(duration)) if duration > 0 => Some(count as f64 / (duration as f64 / 1e9)),
+        _ => None,
+    }
+}
+
+/// Stream a short generation and time the arrival of the first token
+async fn time_generation(
+    base_url: &str,
+    model_name: &str,
+    prompt: &str,
+    num_predict: u32,
+) -> Result<GenerationTiming, LatencyError> {
+    let client = proxy::client();
+    let started = Instant::now();
+    let load_failed = |message: String| LatencyError::LoadFailed {
+        model: model_name.to_string(),
+        message,
+    };
+    let interrupted = |message: String| LatencyError::Interrupted { message };
+
+    let mut response = client
+        .post(format!("{}/api/generate", base_url))
+        .json(&serde_json::json!({
+            "model": model_name,
+            "prompt": prompt,
+            "stream": true,
+            "options": { "num_predict": num_predict },
+        }))
+        .timeout(Duration::from_secs(600))
+        .send()
+        .await
+        .map_err(|e| LatencyError::Unreachable {
+            message: format!("Failed to reach Ollama: {}", e),
+        })?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(load_failed(format!("{} {}", status, body.trim())));
+    }
+
+    let mut decoder = NdjsonDecoder::new();
+    let mut ttft = None;
+
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| interrupted(format!("Generation stream interrupted: {}", e)))?
+    {
+        for line in decoder.push::<GenerateResponseLine>(&chunk).map_err(interrupted)? {
+            if let Some(error) = line.error {
+                return Err(load_failed(error));
+            }
+
+            if ttft.is_none() && !line.response.is_empty() {
+                ttft = Some(started.elapsed());
+            }
+
+            if line.done {
+                return Ok(GenerationTiming {
+                    ttft: ttft.unwrap_or_else(|| started.elapsed()),
+                    tokens_per_sec: token_rate(line.eval_count, line.eval_duration),
+                });
+            }
+        }
+    }
+
+    Err(interrupted(format!("Generation stream for {} ended before completing", model_name)))
+}
+
+/// Run a single non-streaming generation on the Ollama server at `base_url` and return the response text
+pub async fn generate_text_at(
+    base_url: &str,
+    model_name: &str,
+    prompt: &str,
+    num_predict: u32,
+    timeout: Duration,
+) -> Result<String, String> {
+    let client = proxy::client();
+    let response = client
+        .post(format!("{}/api/generate", base_url))
+        .json(&serde_json::json!({
+            "model": model_name,
+            "prompt": prompt,
+            "stream": false,
+            "options": { "num_predict": num_predict },
+        }))
+        .timeout(timeout)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Ollama: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("Ollama generate failed ({}): {}", status, body));
+    }
+
+    let line: GenerateResponseLine = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse generate response: {}", e))?;
+
+    match line.error {
+        Some(error) => Err(format!("Ollama generate failed: {}", error)),
+        None => Ok(line.response),
+    }
+}
+
+/// Measure first-token latency after unloading the model, then again while it is resident
+pub async fn measure_latency(model_name: &str) -> Result<LatencyProfile, LatencyError> {
+    measure_latency_at(OLLAMA_API_URL, model_name).await
+}
+
+async fn measure_latency_at(base_url: &str, model_name: &str) -> Result<LatencyProfile, LatencyError> {
+    const PROMPT: &str = "Reply with a short greeting.";
+
+    log::info!("Measuring cold/warm latency for {}", model_name);
+    let unloaded = post_keep_alive(base_url, model_name, serde_json::json!(0))
+        .await
+        .map_err(|e| LatencyError::Unreachable {
+            message: format!("Failed to reach Ollama: {}", e),
+        })?;
+    if !unloaded.status().is_success() {
+        let status = unloaded.status();
+        let body = unloaded.text().await.unwrap_or_default();
+        return Err(LatencyError::LoadFailed {
+            model: model_name.to_string(),
+            message: format!("could not unload it first ({}): {}", status, body.trim()),
+        });
+    }
+
+    let cold = time_generation(base_url, model_name, PROMPT, 32).await?;
+    let warm = time_generation(base_url, model_name, PROMPT, 32).await?;
+
+    Ok(LatencyProfile {
+        model: model_name.to_string(),
+        cold_ttft_ms: cold.ttft.as_millis() as u64,
+        warm_ttft_ms: warm.ttft.as_millis() as u64,
+        tokens_per_sec: warm.tokens_per_sec.or(cold.tokens_per_sec),
+    })
+}
+
+/// Measured generation throughput for a model
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct BenchmarkResult {
+    pub model: String,
+    #[ts(type = "number")]
+    pub prompt_tokens: u64,
+    #[ts(type = "number")]
+    pub generated_tokens: u64,
+    pub prompt_tokens_per_sec: Option<f64>,
+    pub tokens_per_sec: Option<f64>,
+    pub expected_tokens_per_sec: Option<f64>,
+    /// Measured vs expected generation rate, in percent (negative is slower)
+    pub deviation_pct: Option<f64>,
+    /// True when generation is more than `BENCHMARK_TOLERANCE_PCT` slower than expected
+    pub below_expected: bool,
+}
+
+/// How far below the expected rate a benchmark may fall before it is flagged
+const BENCHMARK_TOLERANCE_PCT: f64 = 30.0;
+
+/// Largest benchmark prompt; also bounds the num_ctx requested from Ollama
+const MAX_BENCHMARK_PROMPT_TOKENS: usize = 32_768;
+const MAX_BENCHMARK_GEN_TOKENS: usize = 4_096;
+
+/// Reject empty benchmark sizes and cap oversized ones
+fn benchmark_sizes(prompt_tokens: usize, gen_tokens: usize) -> Result<(usize, usize), String> {
+    if prompt_tokens == 0 || gen_tokens == 0 {
+        return Err("Benchmark prompt and generation sizes must be at least 1 token".to_string());
+    }
+
+    let sizes = (
+        prompt_tokens.min(MAX_BENCHMARK_PROMPT_TOKENS),
+        gen_tokens.min(MAX_BENCHMARK_GEN_TOKENS),
+    );
+    if sizes != (prompt_tokens, gen_tokens) {
+        log::warn!(
+            "Benchmark size {}/{} tokens capped to {}/{}",
+            prompt_tokens,
+            gen_tokens,
+            sizes.0,
+            sizes.1
+        );
+    }
+    Ok(sizes)
+}
+
+/// Run a generation with a prompt of roughly `prompt_tokens` tokens and a fixed
+/// `gen_tokens` output length, and report Ollama's own timing as token rates
+async fn benchmark_at(
+    base_url: &str,
+    model_name: &str,
+    prompt_tokens: usize,
+    gen_tokens: usize,
+    expected_tokens_per_sec: Option<f64>,
+) -> Result<BenchmarkResult, String> {
+    let (prompt_tokens, gen_tokens) = benchmark_sizes(prompt_tokens, gen_tokens)?;
+
+    // A unique prefix keeps Ollama from reusing a cached prompt between runs
+    let nonce = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let filler = " token".repeat(prompt_tokens);
+    let prompt = format!("[{}] Repeat the word after this colon as many times as you can:{}", nonce, filler);
+
+    log::info!("Benchmarking {} ({} prompt, {} generated tokens)", model_name, prompt_tokens, gen_tokens);
+    let client = proxy::client();
+    let response = client
+        .post(format!("{}/api/generate", base_url))
+        .json(&serde_json::json!({
+            "model": model_name,
+            "prompt": prompt,
+            "stream": false,
+            "options": {
+                "num_predict": gen_tokens,
+                "num_ctx": prompt_tokens + gen_tokens + 64,
+                "temperature": 0,
+            },
+        }))
+        .timeout(Duration::from_secs(600))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Ollama: {}", e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("Ollama generate failed ({}): {}", status, body));
+    }
+
+    let line: GenerateResponseLine = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse generate response: {}", e))?;
+    if let Some(error) = line.error {
+        return Err(format!("Ollama generate failed: {}", error));
+    }
+
+    let tokens_per_sec = token_rate(line.eval_count, line.eval_duration);
+    let deviation_pct = match (tokens_per_sec, expected_tokens_per_sec) {
+        (Some(measured), Some(expected)) if expected > 0.0 => Some((measured - expected) / expected * 100.0),
+        _ => None,
+    };
+    let below_expected = deviation_pct.is_some_and(|d| d < -BENCHMARK_TOLERANCE_PCT);
+    if below_expected {
+        log::warn!(
+            "{} generates at {:.1} tok/s, well below the expected {:.1} tok/s",
+            model_name,
+            tokens_per_sec.unwrap_or_default(),
+            expected_tokens_per_sec.unwrap_or_default()
+        );
+    }
+
+    Ok(BenchmarkResult {
+        model: model_name.to_string(),
+        prompt_tokens: line.prompt_eval_count.unwrap_or(0),
+        generated_tokens: line.eval_count.unwrap_or(0),
+        prompt_tokens_per_sec: token_rate(line.prompt_eval_count, line.prompt_eval_duration),
+        tokens_per_sec,
+        expected_tokens_per_sec,
+        deviation_pct,
+        below_expected,
+    })
+}
+
+/// Validate the sizes before registering anything, then benchmark as an operation
+async fn benchmark_operation(
+    app: &impl OperationHost,
+    base_url: &str,
+    model_name: &str,
+    prompt_tokens: usize,
+    gen_tokens: usize,
+    expected_tokens_per_sec: Option<f64>,
+) -> Result<BenchmarkResult, String> {
+    let (prompt_tokens, gen_tokens) = benchmark_sizes(prompt_tokens, gen_tokens)?;
+    let operation = operations::begin(app, OperationKind::Benchmark, model_name);
+    operation.phase(app, "generating", Some(format!("{} prompt, {} generated tokens", prompt_tokens, gen_tokens)));
+    operation
+        .run(app, benchmark_at(base_url, model_name, prompt_tokens, gen_tokens, expected_tokens_per_sec))
+        .await
 }
 
 // Tauri Commands
@@ -167,17 +1122,684 @@ pub fn get_ollama_status() -> OllamaStatus {
 }
 
 #[tauri::command]
-pub async fn pull_qwen_model(model: String) -> Result<(), String> {
-    pull_model(&model).await
+pub async fn pull_qwen_model(app: AppHandle, model: String, force: Option<bool>) -> Result<(), PullError> {
+    pull_model(&app, &model, force.unwrap_or(false)).await
 }
 
+/// Start pulling a model in the background, returning its operation id
 #[tauri::command]
-pub async fn verify_qwen() -> Result<(), String> {
+pub fn pull_model_stream(app: AppHandle, model: String, force: Option<bool>) -> String {
+    let operation = operations::begin(&app, OperationKind::ModelPull, &model);
+    let id = operation.id().to_string();
+
+    tauri::async_runtime::spawn(async move {
+        let result = operation
+            .run(
+                &app,
+                pull_with_layers(&app, &operation, OLLAMA_API_URL, &model, force.unwrap_or(false), hardware::available_space),
+            )
+            .await;
+        if !operations::legacy_events(&app) {
+            return;
+        }
+        let outcome = match result {
+            Ok(layers) => PullOutcome::Completed { layers },
+            Err(_) if operation.was_cancelled() => PullOutcome::Cancelled,
+            Err(error) => PullOutcome::Failed { error },
+        };
+        let handle = operation.id().to_string();
+        events::emit(&app, PullFinished { handle, model, outcome });
+    });
+
+    id
+}
+
+/// Abort an in-flight pull; same as cancel_operation, kept for existing callers
+#[tauri::command]
+pub fn cancel_pull(operations: tauri::State<'_, Operations>, handle: String) -> Result<(), String> {
+    operations::cancel(&operations, &handle)
+}
+
+#[tauri::command]
+pub async fn verify_qwen(app: AppHandle, force: Option<bool>) -> Result<(), PullError> {
     let recommended = "qwen2.5:14b-instruct-q4_K_M";
-    ensure_qwen_model(recommended).await
+    ensure_qwen_model(&app, recommended, force.unwrap_or(false)).await
 }
 
 #[tauri::command]
 pub fn get_recommended_qwen_model() -> String {
     "qwen2.5:14b-instruct-q4_K_M".to_string()
 }
+
+#[tauri::command]
+pub async fn set_model_residency(model: String, keep_alive: String) -> Result<(), String> {
+    set_keep_alive(&model, &keep_alive).await
+}
+
+#[tauri::command]
+pub async fn get_model_residency() -> Result<Vec<LoadedModel>, String> {
+    get_loaded_models().await
+}
+
+#[tauri::command]
+pub async fn measure_model_latency(model: String) -> Result<LatencyProfile, LatencyError> {
+    measure_latency(&model).await
+}
+
+#[tauri::command]
+pub async fn probe_ollama_service(attempts: Option<u32>, interval_ms: Option<u64>) -> ProbeResult {
+    let mut config = ProbeConfig::default();
+    if let Some(attempts) = attempts {
+        config.max_attempts = attempts;
+    }
+    if let Some(interval_ms) = interval_ms {
+        config.interval = Duration::from_millis(interval_ms);
+    }
+    wait_for_ollama_service(config).await
+}
+
+#[tauri::command]
+pub async fn benchmark_ollama(
+    app: AppHandle,
+    model: String,
+    prompt_tokens: usize,
+    gen_tokens: usize,
+    expected_tokens_per_sec: Option<f64>,
+) -> Result<BenchmarkResult, String> {
+    benchmark_operation(&app, OLLAMA_API_URL, &model, prompt_tokens, gen_tokens, expected_tokens_per_sec).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{AppEvent, OperationProgress};
+    use crate::test_support::{MockResponse, MockServer, RecordingHost};
+    use serde_json::json;
+
+    #[test]
+    fn keep_alive_accepts_seconds_and_durations() {
+        assert_eq!(parse_keep_alive("-1").unwrap(), json!(-1));
+        assert_eq!(parse_keep_alive(" 300 ").unwrap(), json!(300));
+        assert_eq!(parse_keep_alive("30m").unwrap(), json!("30m"));
+        assert_eq!(parse_keep_alive("1.5h").unwrap(), json!("1.5h"));
+        assert_eq!(parse_keep_alive("-1s").unwrap(), json!("-1s"));
+    }
+
+    #[test]
+    fn keep_alive_rejects_unknown_units_and_garbage() {
+        assert!(parse_keep_alive("").is_err());
+        assert!(parse_keep_alive("forever").is_err());
+        assert!(parse_keep_alive("10d").is_err());
+        assert!(parse_keep_alive("m").is_err());
+    }
+
+    #[tokio::test]
+    async fn pinning_sends_keep_alive_with_an_empty_generate() {
+        let ollama = MockServer::start(|_| MockResponse::json(200, json!({"done": true}))).await;
+
+        set_keep_alive_at(&ollama.url, "qwen2.5:14b", "-1").await.unwrap();
+        set_keep_alive_at(&ollama.url, "qwen2.5:14b", "10m").await.unwrap();
+
+        let requests = ollama.requests_to("/api/generate");
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].json(), json!({"model": "qwen2.5:14b", "keep_alive": -1, "stream": false}));
+        assert_eq!(requests[1].json()["keep_alive"], "10m");
+    }
+
+    #[tokio::test]
+    async fn keep_alive_errors_are_reported() {
+        let ollama = MockServer::start(|_| MockResponse::text(404, "model not found")).await;
+
+        let error = set_keep_alive_at(&ollama.url, "missing", "0").await.unwrap_err();
+        assert!(error.contains("model not found"), "{}", error);
+        assert!(set_keep_alive_at(&ollama.url, "missing", "soon").await.is_err());
+        assert_eq!(ollama.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn loaded_models_come_from_ps() {
+        let ollama = MockServer::start(|_| {
+            MockResponse::json(
+                200,
+                json!({"models": [{
+                    "name": "qwen2.5:14b",
+                    "size": 9_000_000_000u64,
+                    "size_vram": 8_000_000_000u64,
+                    "expires_at": "2026-01-01T00:00:00Z",
+                }]}),
+            )
+        })
+        .await;
+
+        let loaded = get_loaded_models_at(&ollama.url).await.unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].size_vram, 8_000_000_000);
+        assert_eq!(loaded[0].expires_at.as_deref(), Some("2026-01-01T00:00:00Z"));
+    }
+
+    /// A streamed generation whose first token arrives `delay` after the headers
+    fn stream_with_first_token_after(delay: Duration) -> MockResponse {
+        MockResponse::chunks(vec![
+            b"{\"response\":\"\",\"done\":false}\n".to_vec(),
+            b"{\"response\":\"Hi\",\"done\":false}\n".to_vec(),
+            b"{\"response\":\"\",\"done\":true,\"eval_count\":10,\"eval_duration\":500000000}\n".to_vec(),
+        ])
+        .chunk_delay(delay)
+    }
+
+    #[tokio::test]
+    async fn latency_unloads_then_times_a_cold_and_a_warm_run() {
+        let ollama = MockServer::start(|request| {
+            if request.json().get("prompt").is_none() {
+                MockResponse::json(200, json!({"done": true}))
+            } else {
+                stream_with_first_token_after(Duration::from_millis(40))
+            }
+        })
+        .await;
+
+        let profile = measure_latency_at(&ollama.url, "qwen2.5:7b").await.unwrap();
+        assert_eq!(profile.model, "qwen2.5:7b");
+        // The empty first line is not a token
+        assert!(profile.cold_ttft_ms >= 40, "{:?}", profile);
+        assert!(profile.warm_ttft_ms >= 40, "{:?}", profile);
+        assert_eq!(profile.tokens_per_sec, Some(20.0));
+
+        let requests = ollama.requests_to("/api/generate");
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].json()["keep_alive"], 0);
+        assert_eq!(requests[1].json()["stream"], true);
+        assert_eq!(requests[2].json()["options"]["num_predict"], 32);
+    }
+
+    #[tokio::test]
+    async fn latency_reports_load_failures() {
+        let ollama = MockServer::start(|request| {
+            if request.json().get("prompt").is_none() {
+                MockResponse::json(200, json!({"done": true}))
+            } else {
+                MockResponse::chunks(vec![b"{\"error\":\"out of memory\"}\n".to_vec()])
+            }
+        })
+        .await;
+
+        let error = measure_latency_at(&ollama.url, "qwen2.5:32b").await.unwrap_err();
+        assert_eq!(
+            error,
+            LatencyError::LoadFailed {
+                model: "qwen2.5:32b".to_string(),
+                message: "out of memory".to_string(),
+            }
+        );
+        assert_eq!(error.to_string(), "Model qwen2.5:32b failed to load: out of memory");
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({"kind": "LoadFailed", "model": "qwen2.5:32b", "message": "out of memory"})
+        );
+    }
+
+    #[tokio::test]
+    async fn latency_reports_http_load_failures() {
+        let ollama = MockServer::start(|request| {
+            if request.json().get("prompt").is_none() {
+                MockResponse::json(200, json!({"done": true}))
+            } else {
+                MockResponse::text(500, r#"{"error":"model requires more system memory"}"#)
+            }
+        })
+        .await;
+
+        match measure_latency_at(&ollama.url, "qwen2.5:32b").await {
+            Err(LatencyError::LoadFailed { model, message }) => {
+                assert_eq!(model, "qwen2.5:32b");
+                assert!(message.starts_with("500"), "{}", message);
+                assert!(message.contains("more system memory"), "{}", message);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn latency_reports_an_unreachable_ollama() {
+        let unreachable = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}", listener.local_addr().unwrap())
+        };
+
+        let error = measure_latency_at(&unreachable, "qwen2.5:7b").await.unwrap_err();
+        assert!(matches!(error, LatencyError::Unreachable { .. }), "{:?}", error);
+        assert_eq!(serde_json::to_value(&error).unwrap()["kind"], "Unreachable");
+    }
+
+    #[tokio::test]
+    async fn latency_fails_when_the_stream_ends_early() {
+        let ollama = MockServer::start(|_| {
+            MockResponse::chunks(vec![b"{\"response\":\"Hi\",\"done\":false}\n".to_vec()])
+        })
+        .await;
+
+        match time_generation(&ollama.url, "qwen2.5:7b", "hello", 8).await {
+            Err(LatencyError::Interrupted { message }) => {
+                assert!(message.contains("ended before completing"), "{}", message)
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn latency_is_not_measured_when_unloading_fails() {
+        let ollama = MockServer::start(|_| MockResponse::text(500, "busy")).await;
+
+        let error = measure_latency_at(&ollama.url, "qwen2.5:7b").await.unwrap_err();
+        assert!(matches!(error, LatencyError::LoadFailed { .. }), "{:?}", error);
+        assert_eq!(ollama.requests().len(), 1);
+    }
+
+    fn no_space(_: &Path) -> Result<u64, String> {
+        Ok(0)
+    }
+
+    fn one_gigabyte(_: &Path) -> Result<u64, String> {
+        Ok(1 << 30)
+    }
+
+    fn broken_probe(path: &Path) -> Result<u64, String> {
+        Err(format!("Failed to query free space for {}", path.display()))
+    }
+
+    #[test]
+    fn pulls_of_unknown_size_are_not_checked() {
+        assert!(ensure_space_for_pull("custom:latest", Path::new("/models"), None, no_space).is_ok());
+    }
+
+    #[test]
+    fn pulls_that_fit_are_allowed() {
+        assert!(ensure_space_for_pull("qwen2.5:3b", Path::new("/models"), Some(1024), one_gigabyte).is_ok());
+    }
+
+    #[test]
+    fn pulls_that_do_not_fit_report_required_and_available() {
+        match ensure_space_for_pull("qwen2.5:32b", Path::new("/models"), Some(2 << 30), one_gigabyte) {
+            Err(PullError::InsufficientDisk { required, available }) => {
+                assert_eq!(required, 2 << 30);
+                assert_eq!(available, 1 << 30);
+            }
+            other => panic!("expected InsufficientDisk, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn a_failed_probe_does_not_block_the_pull() {
+        assert!(ensure_space_for_pull("qwen2.5:32b", Path::new("/models"), Some(2 << 30), broken_probe).is_ok());
+    }
+
+    #[test]
+    fn insufficient_disk_serializes_with_its_kind() {
+        let error = PullError::InsufficientDisk { required: 10, available: 4 };
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            json!({"kind": "InsufficientDisk", "required": 10, "available": 4})
+        );
+    }
+
+    fn quick_probe(max_attempts: u32) -> ProbeConfig {
+        ProbeConfig {
+            max_attempts,
+            interval: Duration::from_millis(10),
+            max_interval: Duration::from_millis(20),
+        }
+    }
+
+    /// An address nothing is listening on (until a test binds it again)
+    fn free_addr() -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        listener.local_addr().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn probe_succeeds_on_the_first_attempt_when_listening() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+
+        let result = wait_for_service_at(&addr, quick_probe(5)).await;
+        assert!(result.running);
+        assert_eq!(result.attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn probe_gives_up_after_max_attempts_with_backoff() {
+        let result = wait_for_service_at(&free_addr(), quick_probe(4)).await;
+        assert!(!result.running);
+        assert_eq!(result.attempts, 4);
+        // Waits of at least 10, 20 and 20ms (capped) between the four attempts
+        assert!(result.waited_ms >= 50, "{:?}", result);
+    }
+
+    #[tokio::test]
+    async fn probe_retries_until_the_service_comes_up() {
+        let addr = free_addr();
+        let listener = {
+            let addr = addr.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(25)).await;
+                let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                drop(listener);
+            })
+        };
+
+        let result = wait_for_service_at(&addr, quick_probe(20)).await;
+        listener.abort();
+        assert!(result.running);
+        assert!(result.attempts > 1, "{:?}", result);
+    }
+
+    #[tokio::test]
+    async fn probe_always_makes_one_attempt() {
+        let result = wait_for_service_at(&free_addr(), quick_probe(0)).await;
+        assert!(!result.running);
+        assert_eq!(result.attempts, 1);
+        assert!(result.waited_ms < 1000);
+    }
+
+    #[test]
+    fn out_of_space_messages_become_insufficient_disk() {
+        let messages = [
+            "write /models/blobs/sha256-1f2e: no space left on device",
+            "Not enough space to download model",
+            "DISK FULL",
+        ];
+        for message in messages {
+            match pull_failure(message, Some(4_680_000_000), one_gigabyte) {
+                PullError::InsufficientDisk { required, available } => {
+                    assert_eq!(required, 4_680_000_000);
+                    assert_eq!(available, 1 << 30);
+                }
+                other => panic!("{:?} mapped to {:?}", message, other),
+            }
+        }
+
+        // Unknown sizes are reported as 0 rather than guessed
+        match pull_failure("no space left on device", None, one_gigabyte) {
+            PullError::InsufficientDisk { required, .. } => assert_eq!(required, 0),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn other_pull_errors_keep_their_message() {
+        match pull_failure("  pull model manifest: file does not exist\n", None, one_gigabyte) {
+            PullError::Failed { message } => {
+                assert_eq!(message, "Failed to pull model: pull model manifest: file does not exist")
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    fn pull_stream(lines: &[&str]) -> MockResponse {
+        MockResponse::chunks(lines.iter().map(|line| format!("{}\n", line).into_bytes()).collect())
+    }
+
+    // A model from KNOWN_MODEL_SIZES, so no registry lookup is made
+    const KNOWN_MODEL: &str = "qwen2.5:7b-instruct-q4_K_M";
+
+    #[tokio::test]
+    async fn out_of_space_in_the_stream_fails_the_pull_as_insufficient_disk() {
+        let ollama = MockServer::start(|_| {
+            pull_stream(&[
+                r#"{"status":"pulling manifest"}"#,
+                r#"{"status":"pulling 2bada8a74506","digest":"sha256:2bada8a74506","total":4683073184,"completed":1024}"#,
+                r#"{"error":"write /models/blobs/sha256-2bada8a74506-partial: no space left on device"}"#,
+            ])
+        })
+        .await;
+
+        let mut statuses = Vec::new();
+        let result = stream_pull_at(&ollama.url, KNOWN_MODEL, true, no_space, |line| statuses.push(line.status.clone())).await;
+
+        match result {
+            Err(PullError::InsufficientDisk { required, .. }) => {
+                assert_eq!(Some(required), known_model_size(KNOWN_MODEL))
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(statuses, ["pulling manifest", "pulling 2bada8a74506"]);
+        assert_eq!(ollama.requests_to("/api/pull")[0].json()["model"], KNOWN_MODEL);
+    }
+
+    #[tokio::test]
+    async fn out_of_space_http_errors_fail_the_pull_as_insufficient_disk() {
+        let ollama = MockServer::start(|_| MockResponse::text(500, r#"{"error":"disk full"}"#)).await;
+
+        let result = stream_pull_at(&ollama.url, KNOWN_MODEL, true, no_space, |_| {}).await;
+        assert!(matches!(result, Err(PullError::InsufficientDisk { .. })), "{:?}", result);
+    }
+
+    #[tokio::test]
+    async fn pulls_that_do_not_fit_are_refused_before_contacting_ollama() {
+        let ollama = MockServer::start(|_| pull_stream(&[r#"{"status":"success"}"#])).await;
+
+        match stream_pull_at(&ollama.url, KNOWN_MODEL, false, no_space, |_| {}).await {
+            Err(PullError::InsufficientDisk { required, available }) => {
+                assert_eq!(Some(required), known_model_size(KNOWN_MODEL));
+                assert_eq!(available, 0);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert!(ollama.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn forced_pulls_proceed_without_free_space() {
+        let ollama = MockServer::start(|_| pull_stream(&[r#"{"status":"success"}"#])).await;
+
+        assert!(stream_pull_at(&ollama.url, KNOWN_MODEL, true, no_space, |_| {}).await.is_ok());
+        assert_eq!(ollama.requests_to("/api/pull").len(), 1);
+    }
+
+    #[tokio::test]
+    async fn pulls_succeed_only_on_a_success_status() {
+        let ollama = MockServer::start(|request| {
+            if request.json()["model"] == KNOWN_MODEL {
+                pull_stream(&[r#"{"status":"pulling manifest"}"#, r#"{"status":"success"}"#])
+            } else {
+                pull_stream(&[r#"{"status":"pulling manifest"}"#])
+            }
+        })
+        .await;
+
+        assert!(stream_pull_at(&ollama.url, KNOWN_MODEL, true, no_space, |_| {}).await.is_ok());
+
+        let truncated = "qwen2.5:3b-instruct-q4_K_M";
+        match stream_pull_at(&ollama.url, truncated, true, no_space, |_| {}).await {
+            Err(PullError::Failed { message }) => assert!(message.contains("ended before completing"), "{}", message),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn benchmark_turns_ollama_timings_into_rates() {
+        let ollama = MockServer::start(|_| {
+            MockResponse::json(
+                200,
+                json!({
+                    "response": "token token",
+                    "done": true,
+                    "prompt_eval_count": 512,
+                    "prompt_eval_duration": 256_000_000u64,
+                    "eval_count": 128,
+                    "eval_duration": 4_000_000_000u64,
+                }),
+            )
+        })
+        .await;
+
+        let result = benchmark_at(&ollama.url, "qwen2.5:7b", 500, 128, Some(50.0)).await.unwrap();
+        assert_eq!(result.prompt_tokens, 512);
+        assert_eq!(result.generated_tokens, 128);
+        assert_eq!(result.prompt_tokens_per_sec, Some(2000.0));
+        assert_eq!(result.tokens_per_sec, Some(32.0));
+        assert_eq!(result.deviation_pct, Some(-36.0));
+        assert!(result.below_expected);
+
+        let options = &ollama.requests_to("/api/generate")[0].json()["options"];
+        assert_eq!(options["num_predict"], 128);
+        assert_eq!(options["num_ctx"], 500 + 128 + 64);
+    }
+
+    #[tokio::test]
+    async fn benchmark_within_tolerance_is_not_flagged() {
+        let ollama = MockServer::start(|_| {
+            MockResponse::json(200, json!({"done": true, "eval_count": 100, "eval_duration": 4_000_000_000u64}))
+        })
+        .await;
+
+        let result = benchmark_at(&ollama.url, "qwen2.5:7b", 16, 100, Some(30.0)).await.unwrap();
+        assert_eq!(result.tokens_per_sec, Some(25.0));
+        assert!(!result.below_expected);
+        // Missing prompt timings leave the prompt rate unknown
+        assert_eq!(result.prompt_tokens_per_sec, None);
+    }
+
+    #[tokio::test]
+    async fn benchmark_sizes_are_capped_and_must_be_positive() {
+        let ollama = MockServer::start(|_| MockResponse::json(200, json!({"done": true}))).await;
+
+        assert!(benchmark_at(&ollama.url, "qwen2.5:7b", 0, 128, None).await.is_err());
+        assert!(benchmark_at(&ollama.url, "qwen2.5:7b", 512, 0, None).await.is_err());
+        assert!(ollama.requests().is_empty());
+
+        benchmark_at(&ollama.url, "qwen2.5:7b", usize::MAX, usize::MAX, None).await.unwrap();
+        let request = ollama.requests_to("/api/generate")[0].json();
+        assert_eq!(request["options"]["num_predict"], MAX_BENCHMARK_GEN_TOKENS);
+        assert_eq!(
+            request["options"]["num_ctx"],
+            MAX_BENCHMARK_PROMPT_TOKENS + MAX_BENCHMARK_GEN_TOKENS + 64
+        );
+        let prompt = request["prompt"].as_str().unwrap();
+        assert_eq!(prompt.matches(" token").count(), MAX_BENCHMARK_PROMPT_TOKENS);
+    }
+
+    #[tokio::test]
+    async fn benchmark_runs_as_an_operation() {
+        let ollama = MockServer::start(|_| {
+            MockResponse::json(200, json!({"done": true, "eval_count": 100, "eval_duration": 4_000_000_000u64}))
+        })
+        .await;
+        let host = RecordingHost::new(false);
+
+        let result = benchmark_operation(&host, &ollama.url, "qwen2.5:7b", 16, 100, None).await.unwrap();
+        assert_eq!(result.tokens_per_sec, Some(25.0));
+
+        let envelopes = host.events(OperationProgress::NAME);
+        let phases: Vec<&str> = envelopes.iter().map(|e| e["phase"].as_str().unwrap()).collect();
+        assert_eq!(phases, ["starting", "generating", "generating"]);
+        assert!(envelopes.iter().all(|e| e["kind"] == "benchmark" && e["label"] == "qwen2.5:7b"));
+        assert_eq!(envelopes[1]["message"], "16 prompt, 100 generated tokens");
+        assert_eq!(envelopes[2]["status"]["state"], "completed");
+
+        // Invalid sizes are rejected before anything is registered
+        assert!(benchmark_operation(&host, &ollama.url, "qwen2.5:7b", 0, 100, None).await.is_err());
+        assert_eq!(host.events(OperationProgress::NAME).len(), 3);
+        assert!(operations::active(&host.operations).is_empty());
+    }
+
+    fn pull_chunks(lines: &[serde_json::Value]) -> Vec<Vec<u8>> {
+        lines.iter().map(|line| format!("{}\n", line).into_bytes()).collect()
+    }
+
+    fn layer_line(digest: &str, total: u64, completed: Option<u64>) -> serde_json::Value {
+        let mut line = json!({"status": format!("pulling {}", digest), "digest": digest, "total": total});
+        if let Some(completed) = completed {
+            line["completed"] = json!(completed);
+        }
+        line
+    }
+
+    #[tokio::test]
+    async fn pull_reports_each_layer_and_which_were_cached() {
+        let chunks = pull_chunks(&[
+            json!({"status": "pulling manifest"}),
+            layer_line("sha256:aaa", 100, Some(100)),
+            layer_line("sha256:bbb", 300, None),
+            layer_line("sha256:bbb", 300, Some(150)),
+            layer_line("sha256:bbb", 300, Some(300)),
+            json!({"status": "verifying sha256 digest"}),
+            json!({"status": "success"}),
+        ]);
+        let ollama = MockServer::start(move |_| MockResponse::chunks(chunks.clone())).await;
+        let host = RecordingHost::new(true);
+
+        let operation = operations::begin(&host, OperationKind::ModelPull, KNOWN_MODEL);
+        let layers = operation
+            .run(&host, pull_with_layers(&host, &operation, &ollama.url, KNOWN_MODEL, true, no_space))
+            .await
+            .unwrap();
+
+        let summary: Vec<_> = layers.iter().map(|l| (l.digest.as_str(), l.completed, l.cached)).collect();
+        assert_eq!(summary, [("sha256:aaa", 100, true), ("sha256:bbb", 300, false)]);
+
+        let layer_events = host.events(PullLayerProgress::NAME);
+        let seen: Vec<_> = layer_events
+            .iter()
+            .map(|e| (e["digest"].as_str().unwrap(), e["completed"].as_u64().unwrap(), e["cached"].as_bool().unwrap()))
+            .collect();
+        assert_eq!(
+            seen,
+            [("sha256:aaa", 100, true), ("sha256:bbb", 0, false), ("sha256:bbb", 150, false), ("sha256:bbb", 300, false)]
+        );
+        assert!(layer_events.iter().all(|e| e["handle"] == operation.id()));
+        assert_eq!(host.events(PullProgress::NAME).len(), 7);
+
+        let envelopes = host.events(OperationProgress::NAME);
+        let bytes: Vec<_> = envelopes
+            .iter()
+            .filter(|e| e["unit"] == "bytes" && e["status"]["state"] == "running")
+            .map(|e| (e["current"].as_u64().unwrap(), e["total"].as_u64().unwrap()))
+            .collect();
+        assert_eq!(bytes, [(100, 100), (100, 400), (250, 400), (400, 400)]);
+        assert_eq!(envelopes.last().unwrap()["status"]["state"], "completed");
+
+        let request = &ollama.requests_to("/api/pull")[0];
+        assert_eq!(request.json(), json!({"model": KNOWN_MODEL, "stream": true}));
+    }
+
+    #[tokio::test]
+    async fn cancelling_a_pull_drops_the_stream() {
+        let mut lines = vec![json!({"status": "pulling manifest"})];
+        lines.extend((1..=20).map(|i| layer_line("sha256:aaa", 2000, Some(i * 100))));
+        lines.push(json!({"status": "success"}));
+        let chunks = pull_chunks(&lines);
+        let ollama = MockServer::start(move |_| {
+            MockResponse::chunks(chunks.clone()).chunk_delay(Duration::from_millis(100))
+        })
+        .await;
+        let host = RecordingHost::new(true);
+
+        let operation = operations::begin(&host, OperationKind::ModelPull, KNOWN_MODEL);
+        let started = Instant::now();
+        let pull = operation.run(&host, pull_with_layers(&host, &operation, &ollama.url, KNOWN_MODEL, true, no_space));
+        let cancel = async {
+            while host.events(PullLayerProgress::NAME).is_empty() {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+            operations::cancel(&host.operations, operation.id()).unwrap();
+        };
+        let (result, ()) = tokio::join!(pull, cancel);
+
+        assert_eq!(result.unwrap_err().to_string(), "Operation cancelled");
+        assert!(operation.was_cancelled());
+        assert!(started.elapsed() < Duration::from_secs(1), "{:?}", started.elapsed());
+
+        // Nothing more is read once the request is dropped
+        let layer_events = host.events(PullLayerProgress::NAME).len();
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(host.events(PullLayerProgress::NAME).len(), layer_events);
+        assert!(layer_events < 5);
+
+        let last = host.events(OperationProgress::NAME).pop().unwrap();
+        assert_eq!(last["status"]["state"], "cancelled");
+        assert_eq!(last["eta_secs"], serde_json::Value::Null);
+        assert!(operations::cancel(&host.operations, operation.id()).is_err());
+    }
+}
@@ -0,0 +1,384 @@
+// Long-running Operations
+// One progress envelope, listing and cancellation for pulls, smoke tests and benchmarks
+
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+use tokio::sync::Notify;
+
+use crate::events::{EventSink, OperationKind, OperationProgress, OperationStatus};
+use crate::settings::{self, Validator};
+
+/// Setting that keeps the pre-envelope events (e.g. "ollama-pull-progress")
+/// flowing next to "operation-progress". Deprecated; defaults to on for one
+/// release so existing listeners can migrate.
+pub const LEGACY_EVENTS_KEY: &str = "legacy_progress_events";
+
+/// Persisted settings owned by this module
+pub const SETTINGS: &[Validator] = &[Validator {
+    key: LEGACY_EVENTS_KEY,
+    check: check_legacy_events,
+}];
+
+fn check_legacy_events(value: &serde_json::Value) -> Result<Option<serde_json::Value>, String> {
+    settings::parse::<bool>(value).map(|_| None)
+}
+
+#[derive(Default)]
+struct CancelSignal {
+    requested: AtomicBool,
+    notify: Notify,
+}
+
+struct ActiveOperation {
+    latest: OperationProgress,
+    cancel: Arc<CancelSignal>,
+}
+
+/// Managed state: operations that have started and not yet finished, by id
+pub struct Operations {
+    next_id: AtomicU64,
+    active: Mutex<HashMap<String, ActiveOperation>>,
+    legacy_events: bool,
+}
+
+impl Operations {
+    pub fn new(legacy_events: bool) -> Self {
+        Self {
+            next_id: AtomicU64::new(0),
+            active: Mutex::new(HashMap::new()),
+            legacy_events,
+        }
+    }
+
+    pub fn load(app: &AppHandle) -> Self {
+        Self::new(settings::get(app, LEGACY_EVENTS_KEY).unwrap_or(true))
+    }
+}
+
+/// Where operations are registered and report progress; the app handle outside of tests
+pub trait OperationHost: EventSink {
+    fn operations(&self) -> Option<&Operations>;
+}
+
+impl OperationHost for AppHandle {
+    fn operations(&self) -> Option<&Operations> {
+        self.try_state::<Operations>().map(|operations| operations.inner())
+    }
+}
+
+/// Whether the deprecated per-subsystem progress events should still be emitted
+pub fn legacy_events(app: &impl OperationHost) -> bool {
+    app.operations().map_or(true, |operations| operations.legacy_events)
+}
+
+/// A running operation; clones report progress for the same operation
+#[derive(Clone)]
+pub struct OperationHandle {
+    id: String,
+    kind: OperationKind,
+    label: String,
+    started: Instant,
+    started_at: String,
+    cancel: Arc<CancelSignal>,
+}
+
+/// Register a new operation and announce it on "operation-progress"
+pub fn begin(app: &impl OperationHost, kind: OperationKind, label: &str) -> OperationHandle {
+    let number = app
+        .operations()
+        .map_or(0, |operations| operations.next_id.fetch_add(1, Ordering::Relaxed))
+        + 1;
+    let operation = OperationHandle {
+        id: format!("op-{}", number),
+        kind,
+        label: label.to_string(),
+        started: Instant::now(),
+        started_at: chrono::Utc::now().to_rfc3339(),
+        cancel: Arc::default(),
+    };
+
+    let progress = operation.snapshot("starting", None, None, None, None, OperationStatus::Running);
+    if let Some(operations) = app.operations() {
+        operations.active.lock().unwrap().insert(
+            operation.id.clone(),
+            ActiveOperation {
+                latest: progress.clone(),
+                cancel: operation.cancel.clone(),
+            },
+        );
+    }
+    app.emit_event(progress);
+    operation
+}
+
+impl OperationHandle {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn was_cancelled(&self) -> bool {
+        self.cancel.requested.load(Ordering::Relaxed)
+    }
+
+    fn snapshot(
+        &self,
+        phase: &str,
+        current: Option<u64>,
+        total: Option<u64>,
+        unit: Option<&str>,
+        message: Option<String>,
+        status: OperationStatus,
+    ) -> OperationProgress {
+        let fraction = match (current, total) {
+            (Some(current), Some(total)) if total > 0 => Some(current.min(total) as f64 / total as f64),
+            _ => None,
+        };
+        // Remaining time at the average rate so far
+        let eta_secs = fraction
+            .filter(|f| *f > 0.0 && *f < 1.0)
+            .map(|f| (self.started.elapsed().as_secs_f64() * (1.0 - f) / f).round() as u64);
+
+        OperationProgress {
+            id: self.id.clone(),
+            kind: self.kind,
+            label: self.label.clone(),
+            phase: phase.to_string(),
+            current,
+            total,
+            unit: unit.map(str::to_string),
+            percent: fraction.map(|f| (f * 1000.0).round() / 10.0),
+            message,
+            started_at: self.started_at.clone(),
+            eta_secs,
+            status,
+        }
+    }
+
+    fn publish(&self, app: &impl OperationHost, progress: OperationProgress) {
+        if let Some(operations) = app.operations() {
+            if let Some(active) = operations.active.lock().unwrap().get_mut(&self.id) {
+                active.latest = progress.clone();
+            }
+        }
+        app.emit_event(progress);
+    }
+
+    /// Report progress as `current` of `total` `unit`s within a phase
+    pub fn progress(&self, app: &impl OperationHost, phase: &str, current: u64, total: Option<u64>, unit: &str) {
+        let progress = self.snapshot(phase, Some(current), total, Some(unit), None, OperationStatus::Running);
+        self.publish(app, progress);
+    }
+
+    /// Report a new phase without a measurable amount of work
+    pub fn phase(&self, app: &impl OperationHost, phase: &str, message: Option<String>) {
+        let progress = self.snapshot(phase, None, None, None, message, OperationStatus::Running);
+        self.publish(app, progress);
+    }
+
+    /// Drive `work` to completion unless cancel_operation is called first, then
+    /// report the terminal status. Cancelling drops `work`, which aborts any
+    /// request it has in flight.
+    pub async fn run<T, E>(&self, app: &impl OperationHost, work: impl Future<Output = Result<T, E>>) -> Result<T, E>
+    where
+        E: From<String> + fmt::Display,
+    {
+        let result = tokio::select! {
+            result = work => result,
+            _ = self.cancel.notify.notified() => Err(E::from("Operation cancelled".to_string())),
+        };
+
+        let status = match &result {
+            Ok(_) => OperationStatus::Completed,
+            Err(_) if self.was_cancelled() => OperationStatus::Cancelled,
+            Err(e) => OperationStatus::Failed { error: e.to_string() },
+        };
+        self.finish(app, status);
+        result
+    }
+
+    fn finish(&self, app: &impl OperationHost, status: OperationStatus) {
+        let latest = app
+            .operations()
+            .and_then(|operations| operations.active.lock().unwrap().remove(&self.id))
+            .map(|active| active.latest);
+
+        let progress = match latest {
+            Some(latest) => OperationProgress {
+                eta_secs: None,
+                status,
+                ..latest
+            },
+            None => self.snapshot("finished", None, None, None, None, status),
+        };
+        log::info!("Operation {} ({}) finished: {:?}", self.id, self.label, progress.status);
+        app.emit_event(progress);
+    }
+}
+
+/// Ask an operation to stop; it reports "cancelled" once its work is dropped
+pub fn cancel(operations: &Operations, id: &str) -> Result<(), String> {
+    let active = operations.active.lock().unwrap();
+    let operation = active
+        .get(id)
+        .ok_or_else(|| format!("No active operation {}", id))?;
+    operation.cancel.requested.store(true, Ordering::Relaxed);
+    // notify_one keeps the permit if `run` is not waiting yet
+    operation.cancel.notify.notify_one();
+    log::info!("Cancelling operation {}", id);
+    Ok(())
+}
+
+/// Latest progress of every operation that has not finished, oldest first
+pub fn active(operations: &Operations) -> Vec<OperationProgress> {
+    let mut active: Vec<OperationProgress> = operations
+        .active
+        .lock()
+        .unwrap()
+        .values()
+        .map(|operation| operation.latest.clone())
+        .collect();
+    active.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+    active
+}
+
+// Tauri Commands
+
+#[tauri::command]
+pub fn list_active_operations(operations: tauri::State<'_, Operations>) -> Vec<OperationProgress> {
+    active(&operations)
+}
+
+#[tauri::command]
+pub fn cancel_operation(operations: tauri::State<'_, Operations>, id: String) -> Result<(), String> {
+    cancel(&operations, &id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::AppEvent;
+    use std::time::Duration;
+    use crate::test_support::RecordingHost;
+
+    fn envelopes(host: &RecordingHost, operation: &OperationHandle) -> Vec<serde_json::Value> {
+        host.events(OperationProgress::NAME)
+            .into_iter()
+            .filter(|event| event["id"] == operation.id())
+            .collect()
+    }
+
+    async fn slow(value: &'static str) -> Result<&'static str, String> {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        Ok(value)
+    }
+
+    #[test]
+    fn progress_reports_percent_and_eta_at_the_average_rate() {
+        let host = RecordingHost::new(false);
+        let mut operation = begin(&host, OperationKind::ModelPull, "llama3.1:8b");
+        operation.started = Instant::now() - Duration::from_secs(10);
+
+        let quarter = operation.snapshot("downloading", Some(25), Some(100), Some("bytes"), None, OperationStatus::Running);
+        assert_eq!(quarter.percent, Some(25.0));
+        assert_eq!(quarter.eta_secs, Some(30));
+
+        let third = operation.snapshot("downloading", Some(1), Some(3), Some("bytes"), None, OperationStatus::Running);
+        assert_eq!(third.percent, Some(33.3));
+
+        let over = operation.snapshot("downloading", Some(150), Some(100), Some("bytes"), None, OperationStatus::Running);
+        assert_eq!(over.percent, Some(100.0));
+        assert_eq!(over.eta_secs, None);
+
+        let unknown = operation.snapshot("downloading", Some(5), Some(0), Some("bytes"), None, OperationStatus::Running);
+        assert_eq!((unknown.percent, unknown.eta_secs), (None, None));
+    }
+
+    #[tokio::test]
+    async fn operations_are_listed_until_they_finish() {
+        let host = RecordingHost::new(false);
+        let pull = begin(&host, OperationKind::ModelPull, "llama3.1:8b");
+        let smoke = begin(&host, OperationKind::SmokeTest, "Pipeline smoke test");
+        assert_eq!((pull.id(), smoke.id()), ("op-1", "op-2"));
+
+        pull.progress(&host, "downloading", 5, Some(10), "bytes");
+        let mut listed = active(&host.operations);
+        listed.sort_by(|a, b| a.id.cmp(&b.id));
+        assert_eq!(listed.len(), 2);
+        assert_eq!((listed[0].phase.as_str(), listed[0].current), ("downloading", Some(5)));
+        assert_eq!(listed[1].phase, "starting");
+
+        pull.run(&host, async { Ok::<_, String>(()) }).await.unwrap();
+        let ids: Vec<String> = active(&host.operations).into_iter().map(|op| op.id).collect();
+        assert_eq!(ids, ["op-2"]);
+
+        // The terminal envelope keeps the last phase and progress
+        let last = envelopes(&host, &pull).pop().unwrap();
+        assert_eq!(last["phase"], "downloading");
+        assert_eq!(last["current"], 5);
+        assert_eq!(last["status"]["state"], "completed");
+        assert!(last["eta_secs"].is_null());
+    }
+
+    #[tokio::test]
+    async fn failed_work_finishes_the_operation_as_failed() {
+        let host = RecordingHost::new(false);
+        let operation = begin(&host, OperationKind::Benchmark, "llama3.1:8b");
+
+        let result = operation.run(&host, async { Err::<(), _>("Ollama returned 500".to_string()) }).await;
+        assert_eq!(result.unwrap_err(), "Ollama returned 500");
+
+        let last = envelopes(&host, &operation).pop().unwrap();
+        assert_eq!(last["status"]["state"], "failed");
+        assert_eq!(last["status"]["error"], "Ollama returned 500");
+        assert!(active(&host.operations).is_empty());
+    }
+
+    #[tokio::test]
+    async fn cancel_stops_only_the_operation_with_that_id() {
+        let host = RecordingHost::new(false);
+        let pull = begin(&host, OperationKind::ModelPull, "llama3.1:8b");
+        let smoke = begin(&host, OperationKind::SmokeTest, "Pipeline smoke test");
+        let benchmark = begin(&host, OperationKind::Benchmark, "llama3.1:8b");
+
+        // Cancelled before its work is first polled
+        cancel(&host.operations, smoke.id()).unwrap();
+        let (pulled, smoked, benchmarked) = tokio::join!(
+            pull.run(&host, slow("pull")),
+            smoke.run(&host, slow("smoke")),
+            benchmark.run(&host, slow("benchmark")),
+        );
+
+        assert_eq!(pulled.unwrap(), "pull");
+        assert_eq!(smoked.unwrap_err(), "Operation cancelled");
+        assert_eq!(benchmarked.unwrap(), "benchmark");
+        assert!(smoke.was_cancelled() && !pull.was_cancelled() && !benchmark.was_cancelled());
+
+        let states: Vec<serde_json::Value> = [&pull, &smoke, &benchmark]
+            .iter()
+            .map(|operation| envelopes(&host, operation).pop().unwrap()["status"]["state"].clone())
+            .collect();
+        assert_eq!(states, ["completed", "cancelled", "completed"]);
+        assert!(active(&host.operations).is_empty());
+    }
+
+    #[tokio::test]
+    async fn cancelling_an_unknown_or_finished_operation_fails() {
+        let host = RecordingHost::new(false);
+        let operation = begin(&host, OperationKind::SmokeTest, "Pipeline smoke test");
+        operation.run(&host, async { Ok::<_, String>(()) }).await.unwrap();
+
+        assert!(cancel(&host.operations, operation.id()).is_err());
+        assert_eq!(cancel(&host.operations, "op-99").unwrap_err(), "No active operation op-99");
+    }
+
+    #[test]
+    fn legacy_events_follow_the_registry_setting() {
+        assert!(legacy_events(&RecordingHost::new(true)));
+        assert!(!legacy_events(&RecordingHost::new(false)));
+    }
+}
@@ -0,0 +1,306 @@
+// Path Authorization
+// Filesystem paths from the webview are only honored inside directories the user picked
+
+use std::ffi::OsString;
+use std::fmt;
+use std::path::{Component, Path, PathBuf, Prefix};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_dialog::DialogExt;
+use ts_rs::TS;
+
+use crate::settings::{self, Validator};
+
+const AUTHORIZED_ROOTS_KEY: &str = "authorized_roots";
+
+/// Error returned when a path cannot be used
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(tag = "kind")]
+pub enum PathError {
+    /// The path is not inside any user-approved directory
+    PermissionDenied { path: String },
+    Invalid { path: String, message: String },
+    /// The path was authorized but reading or writing it failed
+    Io { path: String, message: String },
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathError::PermissionDenied { path } => {
+                write!(f, "Access to {} is not authorized", path)
+            }
+            PathError::Invalid { path, message } => write!(f, "Invalid path {}: {}", path, message),
+            PathError::Io { path, message } => write!(f, "Failed to access {}: {}", path, message),
+        }
+    }
+}
+
+impl From<PathError> for String {
+    fn from(error: PathError) -> Self {
+        error.to_string()
+    }
+}
+
+/// Persisted settings owned by this module
+pub const SETTINGS: &[Validator] = &[Validator {
+    key: AUTHORIZED_ROOTS_KEY,
+    check: check_roots,
+}];
+
+/// Drop relative entries; only absolute canonical roots are ever stored
+fn check_roots(value: &serde_json::Value) -> Result<Option<serde_json::Value>, String> {
+    let roots: Vec<PathBuf> = settings::parse(value)?;
+    let absolute: Vec<&PathBuf> = roots.iter().filter(|root| root.is_absolute()).collect();
+
+    if absolute.len() == roots.len() {
+        Ok(None)
+    } else {
+        serde_json::to_value(absolute).map(Some).map_err(|e| e.to_string())
+    }
+}
+
+/// Canonical user-approved directories
+pub fn authorized_roots(app: &AppHandle) -> Vec<PathBuf> {
+    settings::get::<Vec<PathBuf>>(app, AUTHORIZED_ROOTS_KEY).unwrap_or_default()
+}
+
+fn add_root(app: &AppHandle, root: &Path) -> Result<PathBuf, String> {
+    let root = root
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve {}: {}", root.display(), e))?;
+
+    let mut roots = authorized_roots(app);
+    if !roots.contains(&root) {
+        roots.push(root.clone());
+        settings::set(app, AUTHORIZED_ROOTS_KEY, &roots)?;
+        log::info!("Authorized directory {}", root.display());
+    }
+    Ok(root)
+}
+
+/// Resolve a webview-supplied path and ensure it lies inside an authorized root.
+///
+/// Symlinks are resolved before the check, so links pointing outside a root are
+/// rejected. The final component may not exist yet (e.g. an export target), but
+/// its parent must; a dangling symlink in its place is rejected, since writing
+/// through it would create a file wherever it points.
+pub fn authorize(app: &AppHandle, path: &str) -> Result<PathBuf, PathError> {
+    authorize_within(&authorized_roots(app), path)
+}
+
+fn authorize_within(roots: &[PathBuf], path: &str) -> Result<PathBuf, PathError> {
+    let invalid = |message: &str| PathError::Invalid {
+        path: path.to_string(),
+        message: message.to_string(),
+    };
+    let denied = || PathError::PermissionDenied {
+        path: path.to_string(),
+    };
+
+    let requested = Path::new(path);
+    if !requested.is_absolute() {
+        return Err(invalid("path must be absolute"));
+    }
+    if requested.components().any(|c| c == Component::ParentDir) {
+        return Err(denied());
+    }
+    // Resolving a path on another share would contact that server before the root check
+    if let Some(share) = unc_share(requested) {
+        if !roots.iter().any(|root| unc_share(root).as_ref() == Some(&share)) {
+            log::warn!("Rejected access to unauthorized share {}", path);
+            return Err(denied());
+        }
+    }
+
+    let resolved = match requested.canonicalize() {
+        Ok(resolved) => resolved,
+        Err(_) => {
+            match requested.symlink_metadata() {
+                Ok(metadata) if metadata.file_type().is_symlink() => {
+                    log::warn!("Rejected access through dangling symlink {}", path);
+                    return Err(denied());
+                }
+                Ok(_) => return Err(invalid("path exists but cannot be resolved")),
+                Err(_) => {}
+            }
+            let parent = requested.parent().ok_or_else(|| invalid("path has no parent"))?;
+            let name = requested.file_name().ok_or_else(|| invalid("path has no file name"))?;
+            parent
+                .canonicalize()
+                .map_err(|e| invalid(&e.to_string()))?
+                .join(name)
+        }
+    };
+
+    if roots.iter().any(|root| resolved.starts_with(root)) {
+        Ok(resolved)
+    } else {
+        log::warn!("Rejected access to unauthorized path {}", resolved.display());
+        Err(denied())
+    }
+}
+
+/// Server and share of a network path (`\\server\share` and `\\?\UNC\server\share` alike)
+fn unc_share(path: &Path) -> Option<(OsString, OsString)> {
+    match path.components().next() {
+        Some(Component::Prefix(prefix)) => match prefix.kind() {
+            Prefix::UNC(server, share) | Prefix::VerbatimUNC(server, share) => {
+                Some((server.to_ascii_lowercase(), share.to_ascii_lowercase()))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Remove `path` from `roots`, matching by its canonical form when it still exists
+fn remove_root(roots: &mut Vec<PathBuf>, path: &str) -> bool {
+    let requested = Path::new(path);
+    let canonical = requested.canonicalize().ok();
+    let before = roots.len();
+    roots.retain(|root| root != requested && Some(root) != canonical.as_ref());
+    roots.len() != before
+}
+
+// Tauri Commands
+
+/// Let the user pick a directory and authorize it; `None` if the dialog was cancelled
+#[tauri::command]
+pub async fn pick_authorized_root(app: AppHandle) -> Result<Option<String>, String> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .file()
+        .set_title("Choose a folder")
+        .pick_folder(move |folder| {
+            let _ = tx.send(folder);
+        });
+
+    let Some(folder) = rx.await.map_err(|e| format!("Folder dialog failed: {}", e))? else {
+        return Ok(None);
+    };
+    let folder = folder
+        .into_path()
+        .map_err(|e| format!("Unsupported folder selection: {}", e))?;
+
+    add_root(&app, &folder).map(|root| Some(root.to_string_lossy().into_owned()))
+}
+
+#[tauri::command]
+pub fn list_authorized_roots(app: AppHandle) -> Vec<String> {
+    authorized_roots(&app)
+        .iter()
+        .map(|root| root.to_string_lossy().into_owned())
+        .collect()
+}
+
+#[tauri::command]
+pub fn revoke_authorized_root(app: AppHandle, path: String) -> Result<(), String> {
+    let mut roots = authorized_roots(&app);
+    if !remove_root(&mut roots, &path) {
+        return Err(format!("Directory {} is not authorized", path));
+    }
+
+    settings::set(&app, AUTHORIZED_ROOTS_KEY, &roots)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn root() -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        (dir, root)
+    }
+
+    fn is_denied(result: Result<PathBuf, PathError>) -> bool {
+        matches!(result, Err(PathError::PermissionDenied { .. }))
+    }
+
+    #[test]
+    fn allows_existing_and_new_files_inside_a_root() {
+        let (_dir, root) = root();
+        std::fs::write(root.join("notes.txt"), "x").unwrap();
+        let roots = vec![root.clone()];
+
+        let existing = authorize_within(&roots, root.join("notes.txt").to_str().unwrap());
+        assert_eq!(existing.unwrap(), root.join("notes.txt"));
+        let target = authorize_within(&roots, root.join("export.jsonl").to_str().unwrap());
+        assert_eq!(target.unwrap(), root.join("export.jsonl"));
+    }
+
+    #[test]
+    fn rejects_traversal_and_relative_paths() {
+        let (_dir, root) = root();
+        let roots = vec![root.join("inner")];
+        std::fs::create_dir(&roots[0]).unwrap();
+        std::fs::write(root.join("secret.txt"), "x").unwrap();
+
+        let escaping = roots[0].join("..").join("secret.txt");
+        assert!(is_denied(authorize_within(&roots, escaping.to_str().unwrap())));
+        assert!(is_denied(authorize_within(&roots, root.join("secret.txt").to_str().unwrap())));
+        assert!(matches!(
+            authorize_within(&roots, "inner/secret.txt"),
+            Err(PathError::Invalid { .. })
+        ));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn rejects_unc_paths_outside_roots() {
+        let (_dir, root) = root();
+        let roots = vec![root];
+
+        assert!(is_denied(authorize_within(&roots, r"\\server\share\doc.txt")));
+        assert!(is_denied(authorize_within(&roots, r"\\?\UNC\server\share\doc.txt")));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn accepts_the_verbatim_form_of_a_root() {
+        let (dir, root) = root();
+        std::fs::write(root.join("notes.txt"), "x").unwrap();
+        let roots = vec![root.clone()];
+        let expected = root.join("notes.txt");
+
+        // canonicalize() returns the \\?\ form; the picker may hand back either
+        let plain = dir.path().join("notes.txt");
+        let verbatim = format!(r"\\?\{}", plain.display());
+        assert_eq!(authorize_within(&roots, plain.to_str().unwrap()).unwrap(), expected);
+        assert_eq!(authorize_within(&roots, &verbatim).unwrap(), expected);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_symlinks_out_of_a_root() {
+        let (_dir, root) = root();
+        let (_outside_dir, outside) = self::root();
+        std::fs::write(outside.join("secret.txt"), "x").unwrap();
+        let roots = vec![root.clone()];
+
+        let link = root.join("link.txt");
+        std::os::unix::fs::symlink(outside.join("secret.txt"), &link).unwrap();
+        assert!(is_denied(authorize_within(&roots, link.to_str().unwrap())));
+
+        // Dangling: canonicalize fails, and writing would create the target outside
+        let dangling = root.join("dangling.txt");
+        std::os::unix::fs::symlink(outside.join("created.txt"), &dangling).unwrap();
+        assert!(is_denied(authorize_within(&roots, dangling.to_str().unwrap())));
+        assert!(!outside.join("created.txt").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn revokes_by_canonical_path() {
+        let (_dir, root) = root();
+        std::fs::create_dir(root.join("docs")).unwrap();
+        let mut roots = vec![root.join("docs")];
+
+        let alias = root.join("alias");
+        std::os::unix::fs::symlink(root.join("docs"), &alias).unwrap();
+        assert!(remove_root(&mut roots, alias.to_str().unwrap()));
+        assert!(roots.is_empty());
+        assert!(!remove_root(&mut roots, root.to_str().unwrap()));
+    }
+}
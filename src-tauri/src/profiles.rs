@@ -0,0 +1,342 @@
+// Connection Profiles
+// Named backend connections for switching between local, staging and production
+
+use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use tauri::{AppHandle, Manager};
+
+use crate::audit::AuditLog;
+use crate::endpoint::{parse_backend_url, BackendEndpoint};
+use crate::events::{EventSink, ProfileChanged};
+use crate::features::CapabilityCache;
+use crate::settings::{self, SettingsStore, Validator};
+use crate::sidecar::BackendSidecar;
+
+const PROFILES_KEY: &str = "connection_profiles";
+const ACTIVE_PROFILE_KEY: &str = "active_profile";
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ConnectionProfile {
+    pub name: String,
+    pub backend_url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub auth_token: Option<String>,
+    #[serde(default)]
+    pub accept_invalid_certs: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub default_collection: Option<String>,
+    /// Additional backend instances; `backend_url` has priority 0 and lower values are preferred
+    #[serde(default)]
+    pub replicas: Vec<BackendReplica>,
+    /// Stay on the current replica while it is healthy instead of failing back to a preferred one
+    #[serde(default)]
+    pub sticky: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct BackendReplica {
+    pub url: String,
+    pub priority: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ProfileList {
+    pub active: Option<String>,
+    pub profiles: Vec<ConnectionProfile>,
+}
+
+/// Persisted settings owned by this module
+pub const SETTINGS: &[Validator] = &[
+    Validator {
+        key: PROFILES_KEY,
+        check: check_profiles,
+    },
+    Validator {
+        key: ACTIVE_PROFILE_KEY,
+        check: check_active_profile,
+    },
+];
+
+fn validate_profile(profile: &ConnectionProfile) -> Result<(), String> {
+    if profile.name.trim().is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+    parse_backend_url(&profile.backend_url)?;
+    for replica in &profile.replicas {
+        parse_backend_url(&replica.url)?;
+    }
+    Ok(())
+}
+
+/// Drop individual broken profiles rather than the whole list
+fn check_profiles(value: &serde_json::Value) -> Result<Option<serde_json::Value>, String> {
+    let entries: Vec<serde_json::Value> = settings::parse(value)?;
+    let valid: Vec<serde_json::Value> = entries
+        .iter()
+        .filter(|entry| {
+            settings::parse::<ConnectionProfile>(entry)
+                .and_then(|profile| validate_profile(&profile))
+                .is_ok()
+        })
+        .cloned()
+        .collect();
+
+    if valid.len() == entries.len() {
+        Ok(None)
+    } else {
+        Ok(Some(serde_json::Value::Array(valid)))
+    }
+}
+
+fn check_active_profile(value: &serde_json::Value) -> Result<Option<serde_json::Value>, String> {
+    if settings::parse::<String>(value)?.trim().is_empty() {
+        return Err("empty profile name".to_string());
+    }
+    Ok(None)
+}
+
+/// What switching profiles persists, announces and invalidates; the app handle outside of tests
+pub trait ProfileHost: SettingsStore + EventSink {
+    /// Drop health and capabilities observed for the previous backend
+    fn forget_backend_state(&self);
+}
+
+impl ProfileHost for AppHandle {
+    fn forget_backend_state(&self) {
+        if let Some(sidecar) = self.try_state::<Arc<Mutex<Option<BackendSidecar>>>>() {
+            if let Some(sidecar) = sidecar.lock().unwrap().as_ref() {
+                sidecar.reset_health();
+            }
+        }
+        if let Some(cache) = self.try_state::<CapabilityCache>() {
+            cache.clear();
+        }
+    }
+}
+
+/// All saved profiles
+pub fn load_profiles(app: &impl SettingsStore) -> Vec<ConnectionProfile> {
+    settings::get(app, PROFILES_KEY).unwrap_or_default()
+}
+
+/// The persisted active profile, if it still exists
+pub fn active_profile(app: &impl SettingsStore) -> Option<ConnectionProfile> {
+    let name: String = settings::get(app, ACTIVE_PROFILE_KEY)?;
+    load_profiles(app).into_iter().find(|p| p.name == name)
+}
+
+/// Forget the active profile (e.g. after a manual URL change)
+pub fn clear_active(app: &impl SettingsStore) -> Result<(), String> {
+    settings::remove(app, ACTIVE_PROFILE_KEY)
+}
+
+/// Swap the shared endpoint to a profile and drop state cached for the old backend
+fn activate(app: &impl ProfileHost, endpoint: &BackendEndpoint, profile: &ConnectionProfile) -> Result<(), String> {
+    endpoint.apply_profile(profile)?;
+    settings::set(app, ACTIVE_PROFILE_KEY, &profile.name)?;
+    app.forget_backend_state();
+
+    log::info!("Activated connection profile {:?} ({})", profile.name, profile.backend_url);
+    app.emit_event(ProfileChanged {
+        name: profile.name.clone(),
+        backend_url: profile.backend_url.clone(),
+    });
+
+    Ok(())
+}
+
+/// Insert or replace a profile, re-applying it if it is the active one
+fn store_profile(app: &impl ProfileHost, endpoint: &BackendEndpoint, profile: &ConnectionProfile) -> Result<(), String> {
+    validate_profile(profile)?;
+
+    let mut profiles = load_profiles(app);
+    match profiles.iter_mut().find(|p| p.name == profile.name) {
+        Some(existing) => *existing = profile.clone(),
+        None => profiles.push(profile.clone()),
+    }
+    settings::set(app, PROFILES_KEY, &profiles)?;
+
+    // Editing the active profile takes effect immediately
+    if endpoint.profile().as_deref() == Some(profile.name.as_str()) {
+        activate(app, endpoint, profile)?;
+    }
+
+    Ok(())
+}
+
+fn remove_profile(app: &impl SettingsStore, endpoint: &BackendEndpoint, name: &str) -> Result<(), String> {
+    if endpoint.profile().as_deref() == Some(name) {
+        return Err(format!("Cannot delete the active profile {:?}", name));
+    }
+
+    let mut profiles = load_profiles(app);
+    let before = profiles.len();
+    profiles.retain(|p| p.name != name);
+    if profiles.len() == before {
+        return Err(format!("Profile {:?} not found", name));
+    }
+
+    settings::set(app, PROFILES_KEY, &profiles)
+}
+
+// Tauri Commands
+
+#[tauri::command]
+pub fn list_profiles(app: AppHandle, endpoint: tauri::State<'_, BackendEndpoint>) -> ProfileList {
+    ProfileList {
+        active: endpoint.profile(),
+        profiles: load_profiles(&app),
+    }
+}
+
+#[tauri::command]
+pub fn save_profile(
+    app: AppHandle,
+    endpoint: tauri::State<'_, BackendEndpoint>,
+    audit: tauri::State<'_, AuditLog>,
+    profile: ConnectionProfile,
+) -> Result<(), String> {
+    let result = store_profile(&app, &endpoint, &profile);
+    audit.record("save_profile", &profile.name, &profile, &result);
+    result
+}
+
+#[tauri::command]
+pub fn delete_profile(
+    app: AppHandle,
+    endpoint: tauri::State<'_, BackendEndpoint>,
+    audit: tauri::State<'_, AuditLog>,
+    name: String,
+) -> Result<(), String> {
+    let result = remove_profile(&app, &endpoint, &name);
+    audit.record("delete_profile", &name, &name, &result);
+    result
+}
+
+#[tauri::command]
+pub fn activate_profile(
+    app: AppHandle,
+    endpoint: tauri::State<'_, BackendEndpoint>,
+    audit: tauri::State<'_, AuditLog>,
+    name: String,
+) -> Result<(), String> {
+    let result = load_profiles(&app)
+        .into_iter()
+        .find(|p| p.name == name)
+        .ok_or_else(|| format!("Profile {:?} not found", name))
+        .and_then(|profile| activate(&app, &endpoint, &profile));
+
+    audit.record("activate_profile", &name, &name, &result);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::AppEvent;
+    use crate::test_support::RecordingHost;
+    use serde_json::json;
+
+    fn profile(name: &str, backend_url: &str, auth_token: Option<&str>) -> ConnectionProfile {
+        ConnectionProfile {
+            name: name.to_string(),
+            backend_url: backend_url.to_string(),
+            auth_token: auth_token.map(str::to_string),
+            accept_invalid_certs: false,
+            default_collection: None,
+            replicas: Vec::new(),
+            sticky: false,
+        }
+    }
+
+    #[test]
+    fn activating_a_profile_swaps_the_endpoint_and_drops_backend_state() {
+        let host = RecordingHost::new(false);
+        let endpoint = BackendEndpoint::default();
+        let staging = profile("staging", "http://10.0.0.5:8000", Some("secret"));
+
+        activate(&host, &endpoint, &staging).unwrap();
+
+        assert_eq!(endpoint.get().as_str(), "http://10.0.0.5:8000/");
+        assert_eq!(endpoint.profile().as_deref(), Some("staging"));
+        assert_eq!(endpoint.auth_token().as_deref(), Some("secret"));
+        assert_eq!(host.setting(ACTIVE_PROFILE_KEY), Some(json!("staging")));
+        assert_eq!(host.backend_resets(), 1);
+        assert_eq!(
+            host.events(ProfileChanged::NAME),
+            vec![json!({"name": "staging", "backend_url": "http://10.0.0.5:8000"})]
+        );
+    }
+
+    #[test]
+    fn a_profile_with_a_bad_url_changes_nothing() {
+        let host = RecordingHost::new(false);
+        let endpoint = BackendEndpoint::default();
+
+        assert!(activate(&host, &endpoint, &profile("broken", "not a url", None)).is_err());
+
+        assert_eq!(endpoint.get().as_str(), "http://localhost:8000/");
+        assert_eq!(endpoint.profile(), None);
+        assert_eq!(host.setting(ACTIVE_PROFILE_KEY), None);
+        assert_eq!(host.backend_resets(), 0);
+        assert!(host.events(ProfileChanged::NAME).is_empty());
+    }
+
+    #[test]
+    fn editing_only_the_active_profile_reapplies_it() {
+        let host = RecordingHost::new(false);
+        let endpoint = BackendEndpoint::default();
+        store_profile(&host, &endpoint, &profile("local", "http://localhost:8000", None)).unwrap();
+        store_profile(&host, &endpoint, &profile("lab", "http://10.0.0.5:8000", None)).unwrap();
+        assert!(host.events(ProfileChanged::NAME).is_empty());
+
+        activate(&host, &endpoint, &load_profiles(&host)[1]).unwrap();
+        store_profile(&host, &endpoint, &profile("local", "http://localhost:9000", None)).unwrap();
+        assert_eq!(endpoint.get().as_str(), "http://10.0.0.5:8000/");
+        assert_eq!(host.backend_resets(), 1);
+
+        store_profile(&host, &endpoint, &profile("lab", "http://10.0.0.6:8000", Some("rotated"))).unwrap();
+        assert_eq!(endpoint.get().as_str(), "http://10.0.0.6:8000/");
+        assert_eq!(endpoint.auth_token().as_deref(), Some("rotated"));
+        assert_eq!(host.backend_resets(), 2);
+
+        let names: Vec<String> = load_profiles(&host).into_iter().map(|p| p.name).collect();
+        assert_eq!(names, ["local", "lab"]);
+        assert_eq!(load_profiles(&host)[0].backend_url, "http://localhost:9000");
+    }
+
+    #[test]
+    fn the_active_profile_cannot_be_removed() {
+        let host = RecordingHost::new(false);
+        let endpoint = BackendEndpoint::default();
+        store_profile(&host, &endpoint, &profile("local", "http://localhost:8000", None)).unwrap();
+        store_profile(&host, &endpoint, &profile("lab", "http://10.0.0.5:8000", None)).unwrap();
+        activate(&host, &endpoint, &load_profiles(&host)[1]).unwrap();
+
+        assert!(remove_profile(&host, &endpoint, "lab").is_err());
+        assert!(remove_profile(&host, &endpoint, "missing").is_err());
+        remove_profile(&host, &endpoint, "local").unwrap();
+
+        let names: Vec<String> = load_profiles(&host).into_iter().map(|p| p.name).collect();
+        assert_eq!(names, ["lab"]);
+    }
+
+    #[test]
+    fn the_active_profile_is_restored_on_the_next_launch() {
+        let host = RecordingHost::new(false);
+        let endpoint = BackendEndpoint::default();
+        store_profile(&host, &endpoint, &profile("lab", "http://10.0.0.5:8000", Some("secret"))).unwrap();
+        activate(&host, &endpoint, &load_profiles(&host)[0]).unwrap();
+
+        let restored = BackendEndpoint::load(&host);
+        assert_eq!(restored.get().as_str(), "http://10.0.0.5:8000/");
+        assert_eq!(restored.profile().as_deref(), Some("lab"));
+        assert_eq!(restored.auth_token().as_deref(), Some("secret"));
+
+        clear_active(&host).unwrap();
+        assert_eq!(BackendEndpoint::load(&host).get().as_str(), "http://localhost:8000/");
+    }
+}
@@ -0,0 +1,190 @@
+// Proxy Settings
+// Outbound HTTP proxy shared by every client the app builds
+
+use std::sync::RwLock;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use ts_rs::TS;
+
+use crate::endpoint::BackendEndpoint;
+use crate::settings::{self, Validator};
+
+const PROXY_KEY: &str = "proxy";
+
+/// Hosts that never go through the proxy, so a local backend and Ollama keep working
+const LOCAL_HOSTS: &[&str] = &["localhost", "127.0.0.1", "::1"];
+
+/// User proxy configuration; unset fields fall back to HTTP(S)_PROXY / NO_PROXY
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+pub struct ProxySettings {
+    /// http://, https:// or socks5:// proxy URL
+    #[serde(default)]
+    #[ts(optional)]
+    pub url: Option<String>,
+    /// Extra hosts or domains that bypass the proxy
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+}
+
+static PROXY: RwLock<Option<ProxySettings>> = RwLock::new(None);
+
+/// Persisted settings owned by this module
+pub const SETTINGS: &[Validator] = &[Validator {
+    key: PROXY_KEY,
+    check: check_proxy,
+}];
+
+fn check_proxy(value: &serde_json::Value) -> Result<Option<serde_json::Value>, String> {
+    let configured: ProxySettings = settings::parse(value)?;
+    if let Some(url) = &configured.url {
+        build_proxy(url, "")?;
+    }
+    Ok(None)
+}
+
+/// Load the persisted proxy configuration; call before any client is built
+pub fn load(app: &AppHandle) {
+    let configured: ProxySettings = settings::get(app, PROXY_KEY).unwrap_or_default();
+    if let Some(url) = &configured.url {
+        log::info!("Using configured proxy {}", url);
+    }
+    *PROXY.write().unwrap() = Some(configured);
+}
+
+fn env_var(names: &[&str]) -> Option<String> {
+    names
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.trim().is_empty())
+}
+
+/// Effective proxy URL and bypass list from the configuration and environment
+fn resolve(configured: &ProxySettings) -> (Option<String>, String) {
+    let url = configured.url.clone().or_else(|| {
+        env_var(&["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy"])
+    });
+
+    let mut bypass: Vec<String> = LOCAL_HOSTS.iter().map(|h| h.to_string()).collect();
+    bypass.extend(configured.no_proxy.iter().cloned());
+    if let Some(env) = env_var(&["NO_PROXY", "no_proxy"]) {
+        bypass.extend(env.split(',').map(|h| h.trim().to_string()));
+    }
+    bypass.retain(|h| !h.is_empty());
+
+    (url, bypass.join(","))
+}
+
+fn build_proxy(url: &str, bypass: &str) -> Result<reqwest::Proxy, String> {
+    reqwest::Proxy::all(url)
+        .map(|proxy| proxy.no_proxy(reqwest::NoProxy::from_string(bypass)))
+        .map_err(|e| format!("Invalid proxy URL {:?}: {}", url, e))
+}
+
+/// Client builder with the current proxy configuration applied
+pub fn client_builder() -> reqwest::ClientBuilder {
+    let configured = PROXY.read().unwrap().clone().unwrap_or_default();
+    builder_for(&configured)
+}
+
+fn builder_for(configured: &ProxySettings) -> reqwest::ClientBuilder {
+    let builder = reqwest::Client::builder();
+
+    match resolve(configured) {
+        (Some(url), bypass) => match build_proxy(&url, &bypass) {
+            Ok(proxy) => builder.proxy(proxy),
+            Err(e) => {
+                log::warn!("Ignoring proxy: {}", e);
+                builder.no_proxy()
+            }
+        },
+        (None, _) => builder.no_proxy(),
+    }
+}
+
+/// Client for one-off requests, honoring the proxy configuration
+pub fn client() -> reqwest::Client {
+    client_builder().build().unwrap_or_else(|e| {
+        log::warn!("Failed to build HTTP client, using defaults: {}", e);
+        reqwest::Client::new()
+    })
+}
+
+// Tauri Commands
+
+#[tauri::command]
+pub fn get_proxy_settings() -> ProxySettings {
+    PROXY.read().unwrap().clone().unwrap_or_default()
+}
+
+#[tauri::command]
+pub fn set_proxy_settings(
+    app: AppHandle,
+    endpoint: tauri::State<'_, BackendEndpoint>,
+    proxy: ProxySettings,
+) -> Result<(), String> {
+    if let Some(url) = &proxy.url {
+        build_proxy(url, "")?;
+    }
+
+    settings::set(&app, PROXY_KEY, &proxy)?;
+    *PROXY.write().unwrap() = Some(proxy);
+
+    // Backend requests reuse a long-lived client; rebuild it with the new proxy
+    endpoint.reset_connections();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use crate::test_support::{MockResponse, MockServer};
+
+    fn client_via(proxy: &MockServer, no_proxy: &[&str]) -> reqwest::Client {
+        builder_for(&ProxySettings {
+            url: Some(proxy.url.clone()),
+            no_proxy: no_proxy.iter().map(|h| h.to_string()).collect(),
+        })
+        .timeout(Duration::from_secs(5))
+        .build()
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn local_hosts_bypass_the_proxy_and_external_ones_use_it() {
+        let proxy = MockServer::start(|_| MockResponse::text(200, "via proxy")).await;
+        let backend = MockServer::start(|_| MockResponse::text(200, "direct")).await;
+        let client = client_via(&proxy, &[]);
+
+        let local = client.get(format!("{}/api/health", backend.url)).send().await.unwrap();
+        assert_eq!(local.text().await.unwrap(), "direct");
+        let port = backend.url.rsplit(':').next().unwrap();
+        let named = client.get(format!("http://localhost:{}/api/health", port)).send().await.unwrap();
+        assert_eq!(named.text().await.unwrap(), "direct");
+
+        let external = client.get("http://models.example.com/v2/manifest").send().await.unwrap();
+        assert_eq!(external.text().await.unwrap(), "via proxy");
+
+        // A forward proxy receives the absolute URL
+        let proxied: Vec<String> = proxy.requests().into_iter().map(|r| r.path).collect();
+        assert_eq!(proxied, ["http://models.example.com/v2/manifest"]);
+        assert_eq!(backend.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn configured_no_proxy_hosts_connect_directly() {
+        let proxy = MockServer::start(|_| MockResponse::text(200, "via proxy")).await;
+        let client = client_via(&proxy, &["127.0.0.2"]);
+
+        // Nothing listens there, so a direct attempt fails instead of reaching the proxy
+        assert!(client.get("http://127.0.0.2:9/").send().await.is_err());
+        assert!(proxy.requests().is_empty());
+    }
+
+    #[test]
+    fn invalid_proxy_urls_are_rejected() {
+        assert!(check_proxy(&serde_json::json!({"url": "http://proxy.corp:3128"})).is_ok());
+        assert!(check_proxy(&serde_json::json!({"url": "socks5://127.0.0.1:1080", "no_proxy": ["corp"]})).is_ok());
+        assert!(check_proxy(&serde_json::json!({"url": "not a url"})).is_err());
+    }
+}
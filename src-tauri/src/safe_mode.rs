@@ -0,0 +1,337 @@
+// Safe Mode
+// Start without heavy subsystems after repeated startup crashes or on request
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+
+use crate::startup::StartupReport;
+
+/// Command-line flag that forces safe mode
+pub const SAFE_MODE_FLAG: &str = "--safe-mode";
+
+const MARKER_FILE: &str = "startup.marker";
+
+/// Consecutive crashed startups that trigger safe mode
+const CRASH_THRESHOLD: u32 = 2;
+
+/// Uptime after which a startup counts as successful and the marker is cleared
+const STARTUP_GRACE: Duration = Duration::from_secs(30);
+
+/// Written at startup and removed once the app has stayed up for STARTUP_GRACE;
+/// finding it on the next launch means that startup crashed
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+struct CrashMarker {
+    consecutive_crashes: u32,
+}
+
+/// What happens to the marker once a launch has stayed up for STARTUP_GRACE
+#[derive(Debug, PartialEq)]
+enum AfterGrace {
+    /// A normal startup succeeded, so the crash count resets
+    Clear,
+    /// A forced safe-mode session says nothing about normal startup; put back
+    /// the marker it found so an ongoing crash loop is still counted
+    Restore(Option<CrashMarker>),
+    /// Crash-loop safe mode keeps the marker until the user leaves safe mode
+    Keep,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SafeModeReason {
+    /// Started with --safe-mode
+    Flag,
+    CrashLoop { consecutive_crashes: u32 },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SafeModeStatus {
+    pub reason: SafeModeReason,
+    /// Startup tasks held back by safe mode. Embedding, indexing and their
+    /// schedulers live in the backend process, so this lists desktop-side tasks.
+    pub skipped: Vec<String>,
+}
+
+/// Managed state: why safe mode is active, if it is
+pub struct SafeMode {
+    status: Option<SafeModeStatus>,
+    marker: PathBuf,
+}
+
+impl SafeMode {
+    pub fn is_active(&self) -> bool {
+        self.status.is_some()
+    }
+
+    /// Record the tasks skipped by the startup graph so the UI can explain them
+    pub fn record_skipped(&mut self, report: &StartupReport) {
+        if let Some(status) = &mut self.status {
+            status.skipped = report.safe_mode_skipped.clone();
+        }
+    }
+}
+
+fn marker_path(app: &AppHandle) -> PathBuf {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(MARKER_FILE))
+        .unwrap_or_else(|e| {
+            log::warn!("No app data directory for the crash marker, using working directory: {}", e);
+            PathBuf::from(MARKER_FILE)
+        })
+}
+
+fn write_marker(path: &Path, marker: &CrashMarker) {
+    let written = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .map_err(|e| e.to_string())
+        .and_then(|_| serde_json::to_vec(marker).map_err(|e| e.to_string()))
+        .and_then(|bytes| std::fs::write(path, bytes).map_err(|e| e.to_string()));
+    if let Err(e) = written {
+        log::warn!("Failed to write crash marker {}: {}", path.display(), e);
+    }
+}
+
+fn clear_marker(path: &Path) {
+    match std::fs::remove_file(path) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => log::warn!("Failed to clear crash marker {}: {}", path.display(), e),
+    }
+}
+
+/// Decide whether this launch runs in safe mode and arm the crash marker.
+///
+/// A marker left by the previous launch means it crashed before the grace
+/// period ended; two in a row trigger safe mode. In crash-loop safe mode the
+/// marker is kept until the user leaves safe mode, so a restart does not put
+/// them straight back into the loop. A session forced with --safe-mode neither
+/// adds to nor resets the count of earlier crashed startups.
+pub fn begin(app: &AppHandle, flag: bool) -> SafeMode {
+    let marker = marker_path(app);
+    let (reason, after_grace) = arm(&marker, flag);
+
+    if after_grace != AfterGrace::Keep {
+        let path = marker.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(STARTUP_GRACE).await;
+            settle(&path, after_grace);
+        });
+    }
+
+    SafeMode {
+        status: reason.map(|reason| SafeModeStatus {
+            reason,
+            skipped: Vec::new(),
+        }),
+        marker,
+    }
+}
+
+/// Count the previous launch if it left a marker, write this launch's marker
+/// and pick the safe-mode reason
+fn arm(marker: &Path, flag: bool) -> (Option<SafeModeReason>, AfterGrace) {
+    let previous = std::fs::read(marker)
+        .ok()
+        .map(|bytes| serde_json::from_slice::<CrashMarker>(&bytes).unwrap_or_default());
+    let consecutive_crashes = previous
+        .as_ref()
+        .map_or(0, |previous| previous.consecutive_crashes + 1);
+    write_marker(marker, &CrashMarker { consecutive_crashes });
+
+    let (reason, after_grace) = if flag {
+        (Some(SafeModeReason::Flag), AfterGrace::Restore(previous))
+    } else if consecutive_crashes >= CRASH_THRESHOLD {
+        (Some(SafeModeReason::CrashLoop { consecutive_crashes }), AfterGrace::Keep)
+    } else {
+        (None, AfterGrace::Clear)
+    };
+
+    match &reason {
+        Some(reason) => log::warn!("Starting in safe mode: {:?}", reason),
+        None if consecutive_crashes > 0 => {
+            log::warn!("Previous startup did not finish ({} in a row)", consecutive_crashes)
+        }
+        None => {}
+    }
+
+    (reason, after_grace)
+}
+
+fn settle(marker: &Path, after_grace: AfterGrace) {
+    match after_grace {
+        AfterGrace::Clear | AfterGrace::Restore(None) => clear_marker(marker),
+        AfterGrace::Restore(Some(previous)) => write_marker(marker, &previous),
+        AfterGrace::Keep => {}
+    }
+}
+
+/// Arguments for relaunching in normal mode
+fn relaunch_args(args: impl IntoIterator<Item = String>) -> Vec<String> {
+    args.into_iter().filter(|arg| arg != SAFE_MODE_FLAG).collect()
+}
+
+// Tauri Commands
+
+/// Why the app is in safe mode and what it skipped; `None` in normal mode
+#[tauri::command]
+pub fn get_safe_mode_reason(safe_mode: tauri::State<'_, SafeMode>) -> Option<SafeModeStatus> {
+    safe_mode.status.clone()
+}
+
+/// Clear the crash marker and relaunch without --safe-mode
+#[tauri::command]
+pub fn exit_safe_mode_and_restart(app: AppHandle, safe_mode: tauri::State<'_, SafeMode>) -> Result<(), String> {
+    clear_marker(&safe_mode.marker);
+
+    let exe = std::env::current_exe().map_err(|e| format!("Failed to locate executable: {}", e))?;
+    std::process::Command::new(exe)
+        .args(relaunch_args(std::env::args().skip(1)))
+        .spawn()
+        .map_err(|e| format!("Failed to restart: {}", e))?;
+
+    log::info!("Leaving safe mode");
+    app.exit(0);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::startup::{TaskReport, TaskStatus};
+
+    fn marker_in(dir: &tempfile::TempDir) -> PathBuf {
+        dir.path().join("data").join(MARKER_FILE)
+    }
+
+    fn read_marker(path: &Path) -> Option<CrashMarker> {
+        std::fs::read(path).ok().map(|bytes| serde_json::from_slice(&bytes).unwrap())
+    }
+
+    fn crashes(count: u32) -> Option<CrashMarker> {
+        Some(CrashMarker { consecutive_crashes: count })
+    }
+
+    #[test]
+    fn two_crashed_startups_in_a_row_trigger_safe_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let marker = marker_in(&dir);
+
+        // Each launch that never reaches the grace period leaves its marker behind
+        assert_eq!(arm(&marker, false), (None, AfterGrace::Clear));
+        assert_eq!(read_marker(&marker), crashes(0));
+        assert_eq!(arm(&marker, false), (None, AfterGrace::Clear));
+        assert_eq!(read_marker(&marker), crashes(1));
+
+        let (reason, after_grace) = arm(&marker, false);
+        assert!(matches!(reason, Some(SafeModeReason::CrashLoop { consecutive_crashes: 2 })));
+        assert_eq!(after_grace, AfterGrace::Keep);
+
+        // Restarting without leaving safe mode stays in it
+        let (reason, _) = arm(&marker, false);
+        assert!(matches!(reason, Some(SafeModeReason::CrashLoop { consecutive_crashes: 3 })));
+    }
+
+    #[test]
+    fn a_startup_that_survives_the_grace_period_resets_the_count() {
+        let dir = tempfile::tempdir().unwrap();
+        let marker = marker_in(&dir);
+
+        arm(&marker, false);
+        let (reason, after_grace) = arm(&marker, false);
+        assert!(reason.is_none());
+        settle(&marker, after_grace);
+        assert_eq!(read_marker(&marker), None);
+
+        assert_eq!(arm(&marker, false), (None, AfterGrace::Clear));
+    }
+
+    #[test]
+    fn an_unreadable_marker_still_counts_as_a_crash() {
+        let dir = tempfile::tempdir().unwrap();
+        let marker = marker_in(&dir);
+        std::fs::create_dir_all(marker.parent().unwrap()).unwrap();
+        std::fs::write(&marker, b"{truncated").unwrap();
+
+        arm(&marker, false);
+        assert_eq!(read_marker(&marker), crashes(1));
+    }
+
+    #[test]
+    fn a_forced_session_keeps_the_crash_count_it_found() {
+        let dir = tempfile::tempdir().unwrap();
+        let marker = marker_in(&dir);
+
+        // One crashed normal startup, then a --safe-mode session that stays up
+        arm(&marker, false);
+        let (reason, after_grace) = arm(&marker, true);
+        assert!(matches!(reason, Some(SafeModeReason::Flag)));
+        settle(&marker, after_grace);
+        assert_eq!(read_marker(&marker), crashes(0));
+
+        // The next normal startup that crashes completes the loop
+        arm(&marker, false);
+        let (reason, _) = arm(&marker, false);
+        assert!(matches!(reason, Some(SafeModeReason::CrashLoop { consecutive_crashes: 2 })));
+
+        // Without earlier crashes a forced session leaves no marker
+        let clean = tempfile::tempdir().unwrap();
+        let marker = marker_in(&clean);
+        let (_, after_grace) = arm(&marker, true);
+        settle(&marker, after_grace);
+        assert_eq!(read_marker(&marker), None);
+    }
+
+    #[test]
+    fn skip_list_names_only_what_safe_mode_held_back() {
+        let task = |name: &str, status| TaskReport {
+            name: name.to_string(),
+            status,
+            duration_ms: 0,
+        };
+        let report = StartupReport {
+            tasks: vec![
+                task("settings", TaskStatus::Completed),
+                task("audit_log", TaskStatus::Failed { error: "disk".to_string() }),
+                task("sidecar", TaskStatus::Skipped { reason: "safe mode".to_string() }),
+            ],
+            safe_mode_skipped: vec!["sidecar".to_string()],
+            total_ms: 0,
+        };
+
+        let mut safe_mode = SafeMode {
+            status: Some(SafeModeStatus {
+                reason: SafeModeReason::Flag,
+                skipped: Vec::new(),
+            }),
+            marker: PathBuf::new(),
+        };
+        safe_mode.record_skipped(&report);
+        assert_eq!(safe_mode.status.unwrap().skipped, ["sidecar"]);
+
+        let mut normal = SafeMode {
+            status: None,
+            marker: PathBuf::new(),
+        };
+        normal.record_skipped(&report);
+        assert!(!normal.is_active());
+    }
+
+    #[test]
+    fn leaving_safe_mode_clears_the_marker_and_drops_the_flag() {
+        let dir = tempfile::tempdir().unwrap();
+        let marker = marker_in(&dir);
+        for _ in 0..3 {
+            arm(&marker, false);
+        }
+
+        clear_marker(&marker);
+        let args = relaunch_args(["--safe-mode", "--profile", "field"].map(String::from));
+        assert_eq!(args, ["--profile", "field"]);
+        assert_eq!(arm(&marker, args.iter().any(|arg| arg == SAFE_MODE_FLAG)), (None, AfterGrace::Clear));
+    }
+}
@@ -0,0 +1,243 @@
+// Persisted Settings
+// Typed access to values kept in the tauri-plugin-store settings file
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+use ts_rs::TS;
+
+pub const SETTINGS_STORE: &str = "settings.json";
+
+/// Outcome of checking a stored value: `Ok(None)` keeps it, `Ok(Some(value))`
+/// replaces it with a repaired value, `Err` removes it so its owner falls back to
+/// the default
+pub type Check = fn(&serde_json::Value) -> Result<Option<serde_json::Value>, String>;
+
+/// A persisted key and the check its stored value must pass
+#[derive(Clone, Copy)]
+pub struct Validator {
+    pub key: &'static str,
+    pub check: Check,
+}
+
+/// A setting that was reset or repaired on load
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SettingReset {
+    pub key: String,
+    pub reason: String,
+    /// True if part of the value was kept, false if it was reset to the default
+    pub repaired: bool,
+}
+
+/// Settings reset during validation at startup
+#[derive(Debug, Clone, Default)]
+pub struct SettingResets(pub Vec<SettingReset>);
+
+/// Check that a stored value deserializes as `T`
+pub fn parse<T: DeserializeOwned>(value: &serde_json::Value) -> Result<T, String> {
+    T::deserialize(value).map_err(|e| format!("unexpected shape: {}", e))
+}
+
+/// Where settings are persisted; the app's store file outside of tests
+pub trait SettingsStore {
+    fn load_value(&self, key: &str) -> Option<serde_json::Value>;
+
+    /// Write `value`, or remove the key when it is `None`, and flush to disk
+    fn save_value(&self, key: &str, value: Option<serde_json::Value>) -> Result<(), String>;
+}
+
+impl SettingsStore for AppHandle {
+    fn load_value(&self, key: &str) -> Option<serde_json::Value> {
+        self.store(SETTINGS_STORE).ok()?.get(key)
+    }
+
+    fn save_value(&self, key: &str, value: Option<serde_json::Value>) -> Result<(), String> {
+        let store = self
+            .store(SETTINGS_STORE)
+            .map_err(|e| format!("Failed to open settings store: {}", e))?;
+        match value {
+            Some(value) => store.set(key, value),
+            None => {
+                store.delete(key);
+            }
+        }
+        store.save().map_err(|e| format!("Failed to save settings: {}", e))
+    }
+}
+
+/// Read a setting, returning None when it is missing or has the wrong shape
+pub fn get<T: DeserializeOwned>(app: &impl SettingsStore, key: &str) -> Option<T> {
+    let value = app.load_value(key)?;
+
+    match serde_json::from_value(value) {
+        Ok(parsed) => Some(parsed),
+        Err(e) => {
+            log::warn!("Ignoring malformed setting {}: {}", key, e);
+            None
+        }
+    }
+}
+
+/// Write a setting and flush the store to disk
+pub fn set<T: Serialize>(app: &impl SettingsStore, key: &str, value: &T) -> Result<(), String> {
+    let value = serde_json::to_value(value).map_err(|e| format!("Failed to serialize {}: {}", key, e))?;
+    app.save_value(key, Some(value))
+}
+
+/// Remove a setting and flush the store to disk
+pub fn remove(app: &impl SettingsStore, key: &str) -> Result<(), String> {
+    app.save_value(key, None)
+}
+
+/// Validate persisted settings before anything reads them, resetting or repairing
+/// values that no longer pass their owner's check
+pub fn validate(app: &AppHandle, validators: &[Validator]) -> Vec<SettingReset> {
+    let store = match app.store(SETTINGS_STORE) {
+        Ok(store) => store,
+        Err(e) => {
+            log::warn!("Skipping settings validation, failed to open store: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut values: serde_json::Map<String, serde_json::Value> = store.entries().into_iter().collect();
+    let resets = check_values(&mut values, validators);
+
+    for reset in &resets {
+        log::warn!("Reset setting {}: {}", reset.key, reset.reason);
+        match values.get(&reset.key) {
+            Some(repaired) => store.set(reset.key.clone(), repaired.clone()),
+            None => {
+                store.delete(&reset.key);
+            }
+        }
+    }
+    if !resets.is_empty() {
+        if let Err(e) = store.save() {
+            log::warn!("Failed to save validated settings: {}", e);
+        }
+    }
+    resets
+}
+
+/// Run each validator against its key in `values`, replacing repaired values and
+/// removing invalid ones
+fn check_values(
+    values: &mut serde_json::Map<String, serde_json::Value>,
+    validators: &[Validator],
+) -> Vec<SettingReset> {
+    let mut resets = Vec::new();
+    for validator in validators {
+        let Some(value) = values.get(validator.key) else {
+            continue;
+        };
+        match (validator.check)(value) {
+            Ok(None) => continue,
+            Ok(Some(repaired)) => {
+                values.insert(validator.key.to_string(), repaired);
+                resets.push(SettingReset {
+                    key: validator.key.to_string(),
+                    reason: "invalid entries were removed".to_string(),
+                    repaired: true,
+                });
+            }
+            Err(reason) => {
+                values.remove(validator.key);
+                resets.push(SettingReset {
+                    key: validator.key.to_string(),
+                    reason,
+                    repaired: false,
+                });
+            }
+        }
+    }
+    resets
+}
+
+// Tauri Commands
+
+/// Settings that were reset or repaired when the app started
+#[tauri::command]
+pub fn get_settings_resets(resets: tauri::State<'_, SettingResets>) -> Vec<SettingReset> {
+    resets.0.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn all_validators() -> Vec<Validator> {
+        [
+            crate::endpoint::SETTINGS,
+            crate::profiles::SETTINGS,
+            crate::proxy::SETTINGS,
+            crate::paths::SETTINGS,
+            crate::audit::SETTINGS,
+            crate::models::SETTINGS,
+            crate::followups::SETTINGS,
+            crate::operations::SETTINGS,
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn invalid_fields_reset_while_valid_ones_are_kept() {
+        let root = std::env::temp_dir();
+        let good_profile = json!({"name": "staging", "backend_url": "https://staging.internal:8443"});
+        let blob = json!({
+            "backend_url": "ftp://rag.internal",
+            "authorized_roots": [root, "relative/docs"],
+            "audit_hmac_key": "not-hex",
+            "followup_mode": "telepathic",
+            "connection_profiles": [good_profile, {"name": "broken", "backend_url": "localhost:8000"}],
+            "active_profile": "staging",
+            "proxy": {"url": "http://proxy.internal:3128"},
+            "model_fallback_chain": ["qwen2.5:14b", "llama3.1:8b"],
+            "legacy_progress_events": false,
+            "window_width": 1280,
+        });
+        let serde_json::Value::Object(mut values) = blob.clone() else {
+            unreachable!()
+        };
+
+        let resets = check_values(&mut values, &all_validators());
+
+        let summary: Vec<(&str, bool)> = resets.iter().map(|r| (r.key.as_str(), r.repaired)).collect();
+        assert_eq!(
+            summary,
+            [
+                ("backend_url", false),
+                ("connection_profiles", true),
+                ("authorized_roots", true),
+                ("audit_hmac_key", false),
+                ("followup_mode", false),
+            ]
+        );
+        assert!(resets[0].reason.contains("Unsupported backend URL scheme"), "{}", resets[0].reason);
+        assert!(resets[3].reason.starts_with("not hex"), "{}", resets[3].reason);
+
+        // Invalid values are gone, so their owners fall back to defaults
+        for key in ["backend_url", "audit_hmac_key", "followup_mode"] {
+            assert!(!values.contains_key(key), "{}", key);
+        }
+        // Lists keep their valid entries
+        assert_eq!(values["connection_profiles"], json!([good_profile]));
+        assert_eq!(values["authorized_roots"], json!([root]));
+        // Everything else is untouched, including keys no validator owns
+        for key in ["active_profile", "proxy", "model_fallback_chain", "legacy_progress_events", "window_width"] {
+            assert_eq!(values[key], blob[key], "{}", key);
+        }
+    }
+
+    #[test]
+    fn valid_settings_produce_no_resets() {
+        let mut values = serde_json::Map::new();
+        values.insert("backend_url".to_string(), json!("http://localhost:8000"));
+        values.insert("followup_mode".to_string(), json!("model"));
+
+        assert!(check_values(&mut values, &all_validators()).is_empty());
+        assert_eq!(values.len(), 2);
+    }
+}
@@ -1,43 +1,70 @@
 // Backend Sidecar Process Management
 // Handles lifecycle of the FastAPI backend as a Tauri sidecar
 
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime};
+use reqwest::Method;
 use tauri::AppHandle;
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::endpoint::BackendEndpoint;
+use crate::events::{EventSink, ResumeRecovery, RoutingChanged};
+
+/// Interval between background health checks
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Extra delay between checks that means the machine was suspended
+const RESUME_THRESHOLD: Duration = Duration::from_secs(30);
+
+/// Quick re-checks after a resume while the network comes back up
+const RESUME_RECHECKS: u32 = 5;
+const RESUME_RECHECK_DELAY: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct BackendStatus {
+    /// A backend process was started by the app; no process is bundled yet
     pub running: bool,
+    /// The background health check is polling the endpoint
+    pub monitoring: bool,
     pub healthy: bool,
     pub port: u16,
     pub last_check: String,
     pub error: Option<String>,
+    pub profile: Option<String>,
 }
 
 pub struct BackendSidecar {
     status: Arc<Mutex<BackendStatus>>,
+    endpoint: BackendEndpoint,
     app_handle: AppHandle,
+    /// Bumped on every start and stop so an older monitor loop exits
+    monitor_generation: Arc<AtomicU64>,
 }
 
 impl BackendSidecar {
-    pub fn new(app_handle: AppHandle) -> Self {
+    pub fn new(app_handle: AppHandle, endpoint: BackendEndpoint) -> Self {
         let status = Arc::new(Mutex::new(BackendStatus {
             running: false,
+            monitoring: false,
             healthy: false,
-            port: 8000,
+            port: endpoint.port(),
             last_check: chrono::Utc::now().to_rfc3339(),
             error: None,
+            profile: endpoint.profile(),
         }));
 
         Self {
             status,
+            endpoint,
             app_handle,
+            monitor_generation: Arc::new(AtomicU64::new(0)),
         }
     }
 
-    /// Start the backend sidecar process
-    pub async fn start(&self) -> Result<(), String> {
+    /// Start the backend sidecar process; does nothing if it is already running
+    pub fn start(&self) -> Result<(), String> {
         log::info!("Starting backend sidecar...");
 
         // For V4.0, we'll connect to Docker backend during development
@@ -46,17 +73,33 @@ impl BackendSidecar {
         // Update status
         {
             let mut status = self.status.lock().unwrap();
+            if status.running {
+                return Ok(());
+            }
             status.running = true;
             status.last_check = chrono::Utc::now().to_rfc3339();
         }
 
         // Start health monitoring
-        self.start_health_monitor();
+        self.monitor();
 
         log::info!("Backend sidecar started");
         Ok(())
     }
 
+    /// Watch the endpoint's health without claiming a backend process was started;
+    /// does nothing if it is already monitoring
+    pub fn monitor(&self) {
+        {
+            let mut status = self.status.lock().unwrap();
+            if status.monitoring {
+                return;
+            }
+            status.monitoring = true;
+        }
+        self.start_health_monitor();
+    }
+
     /// Stop the backend sidecar process
     pub fn stop(&self) -> Result<(), String> {
         log::info!("Stopping backend sidecar...");
@@ -64,8 +107,10 @@ impl BackendSidecar {
         {
             let mut status = self.status.lock().unwrap();
             status.running = false;
+            status.monitoring = false;
             status.healthy = false;
         }
+        self.monitor_generation.fetch_add(1, Ordering::Relaxed);
 
         log::info!("Backend sidecar stopped");
         Ok(())
@@ -73,93 +118,187 @@ impl BackendSidecar {
 
     /// Get current backend status
     pub fn get_status(&self) -> BackendStatus {
-        self.status.lock().unwrap().clone()
+        let mut status = self.status.lock().unwrap().clone();
+        status.port = self.endpoint.port();
+        status.profile = self.endpoint.profile();
+        status
     }
 
-    /// Check if backend is healthy
-    async fn check_health(&self) -> bool {
-        let client = reqwest::Client::new();
-
-        match client
-            .get("http://localhost:8000/api/health")
-            .timeout(Duration::from_secs(5))
-            .send()
-            .await
-        {
-            Ok(response) => {
-                let healthy = response.status().is_success();
-                log::debug!("Health check: {}", if healthy { "OK" } else { "FAIL" });
-                healthy
-            }
-            Err(e) => {
-                log::warn!("Health check failed: {}", e);
-                false
-            }
-        }
+    /// Forget health observed for the previous backend after the endpoint changes
+    pub fn reset_health(&self) {
+        let mut status = self.status.lock().unwrap();
+        status.healthy = false;
+        status.error = None;
+        status.last_check = chrono::Utc::now().to_rfc3339();
     }
 
-    /// Start background health monitoring
+    /// Start background health monitoring. This also probes every replica of the
+    /// active profile, so failover works without any request failing first.
     fn start_health_monitor(&self) {
         let status = Arc::clone(&self.status);
+        let endpoint = self.endpoint.clone();
+        let app_handle = self.app_handle.clone();
+        let generation = Arc::clone(&self.monitor_generation);
+        let started = generation.fetch_add(1, Ordering::Relaxed) + 1;
 
         tauri::async_runtime::spawn(async move {
-            loop {
-                tokio::time::sleep(Duration::from_secs(10)).await;
+            let mut clock = ResumeDetector::new(Instant::now(), SystemTime::now());
 
-                // Check if backend is still running
-                let is_running = {
-                    let s = status.lock().unwrap();
-                    s.running
-                };
+            loop {
+                tokio::time::sleep(HEALTH_CHECK_INTERVAL).await;
 
-                if !is_running {
+                // Stop once the sidecar is stopped or restarted with a new monitor
+                if generation.load(Ordering::Relaxed) != started {
                     break;
                 }
 
-                // Perform health check
-                let client = reqwest::Client::new();
-                let healthy = match client
-                    .get("http://localhost:8000/api/health")
-                    .timeout(Duration::from_secs(5))
-                    .send()
-                    .await
-                {
-                    Ok(response) => response.status().is_success(),
-                    Err(_) => false,
-                };
-
-                // Update status
-                {
-                    let mut s = status.lock().unwrap();
-                    s.healthy = healthy;
-                    s.last_check = chrono::Utc::now().to_rfc3339();
-                    if !healthy {
-                        s.error = Some("Backend health check failed".to_string());
-                    } else {
-                        s.error = None;
-                    }
-                }
+                let suspended = clock.suspended_for(Instant::now(), SystemTime::now());
+                check_health(&app_handle, &status, &endpoint, suspended, RESUME_RECHECK_DELAY).await;
+
+                // Measure the next interval from here, so slow checks are not mistaken for a suspend
+                clock = ResumeDetector::new(Instant::now(), SystemTime::now());
             }
         });
     }
 }
 
+/// Clock readings taken when the monitor last finished its work
+#[derive(Debug, Clone, Copy)]
+struct ResumeDetector {
+    last_instant: Instant,
+    last_wall: SystemTime,
+}
+
+impl ResumeDetector {
+    fn new(last_instant: Instant, last_wall: SystemTime) -> Self {
+        Self {
+            last_instant,
+            last_wall,
+        }
+    }
+
+    /// Time the machine appears to have been suspended since the last reading, if any.
+    ///
+    /// Sleeping tasks do not run during suspend, so the wall clock (or, on platforms
+    /// where it keeps counting, the monotonic clock) jumps well past the interval.
+    fn suspended_for(&self, now_instant: Instant, now_wall: SystemTime) -> Option<Duration> {
+        let monotonic = now_instant.saturating_duration_since(self.last_instant);
+        let wall = now_wall.duration_since(self.last_wall).unwrap_or_default();
+        let elapsed = monotonic.max(wall);
+
+        if elapsed > HEALTH_CHECK_INTERVAL + RESUME_THRESHOLD {
+            Some(elapsed - HEALTH_CHECK_INTERVAL)
+        } else {
+            None
+        }
+    }
+}
+
+/// One monitor tick: a regular health check, or a recovery if the machine was suspended
+async fn check_health(
+    events: &impl EventSink,
+    status: &Mutex<BackendStatus>,
+    endpoint: &BackendEndpoint,
+    suspended: Option<Duration>,
+    recheck_delay: Duration,
+) {
+    match suspended {
+        Some(suspended) => recover_after_resume(events, status, endpoint, suspended, recheck_delay).await,
+        None => {
+            let healthy = probe_health(events, endpoint).await;
+            record_health(status, healthy);
+        }
+    }
+}
+
+/// Check every replica, re-route on health changes and report whether the routed one is healthy
+async fn probe_health(events: &impl EventSink, endpoint: &BackendEndpoint) -> bool {
+    let (healthy, changes) = probe_replicas(endpoint).await;
+    for change in changes {
+        events.emit_event(change);
+    }
+    healthy
+}
+
+/// Check every replica and record the results, returning whether the routed
+/// replica is healthy along with any routing changes that caused
+async fn probe_replicas(endpoint: &BackendEndpoint) -> (bool, Vec<RoutingChanged>) {
+    let mut results = Vec::new();
+    let mut changes = Vec::new();
+
+    for url in endpoint.replica_urls() {
+        let healthy = match endpoint
+            .request_to(&url, Method::GET, "/api/health")
+            .timeout(Duration::from_secs(5))
+            .send()
+            .await
+        {
+            Ok(response) => response.status().is_success(),
+            Err(_) => false,
+        };
+
+        changes.extend(endpoint.record_health(&url, healthy));
+        results.push((url, healthy));
+    }
+
+    let routed = endpoint.get();
+    let healthy = results.iter().any(|(url, healthy)| *healthy && *url == routed);
+    (healthy, changes)
+}
+
+fn record_health(status: &Mutex<BackendStatus>, healthy: bool) {
+    let mut s = status.lock().unwrap();
+    s.healthy = healthy;
+    s.last_check = chrono::Utc::now().to_rfc3339();
+    if !healthy {
+        s.error = Some("Backend health check failed".to_string());
+    } else {
+        s.error = None;
+    }
+}
+
+/// Drop stale connections and re-check health in quick succession after a resume
+async fn recover_after_resume(
+    events: &impl EventSink,
+    status: &Mutex<BackendStatus>,
+    endpoint: &BackendEndpoint,
+    suspended: Duration,
+    recheck_delay: Duration,
+) {
+    log::info!(
+        "System resume detected after ~{}s, re-checking backend",
+        suspended.as_secs()
+    );
+    endpoint.reset_connections();
+
+    let mut health_checks = 0;
+    let mut healthy = false;
+    while health_checks < RESUME_RECHECKS && !healthy {
+        if health_checks > 0 {
+            tokio::time::sleep(recheck_delay).await;
+        }
+        health_checks += 1;
+        healthy = probe_health(events, endpoint).await;
+    }
+    record_health(status, healthy);
+
+    events.emit_event(ResumeRecovery {
+        suspended_secs: suspended.as_secs(),
+        connections_reset: true,
+        health_checks,
+        healthy,
+    });
+}
+
 // Tauri Commands
 
 #[tauri::command]
-pub async fn start_backend(
+pub fn start_backend(
     state: tauri::State<'_, Arc<Mutex<Option<BackendSidecar>>>>,
 ) -> Result<(), String> {
-    // Clone Arc to avoid holding lock across await
-    let sidecar_clone = {
-        let sidecar_opt = state.lock().unwrap();
-        sidecar_opt.as_ref().map(|s| Arc::new(Mutex::new(s.status.clone())))
-    };
-
-    if sidecar_clone.is_some() {
-        // Simulate backend start (connect to Docker)
-        log::info!("Backend sidecar started");
-        Ok(())
+    let sidecar_opt = state.lock().unwrap();
+    if let Some(sidecar) = sidecar_opt.as_ref() {
+        sidecar.start()
     } else {
         Err("Backend sidecar not initialized".to_string())
     }
@@ -188,3 +327,203 @@ pub fn get_backend_status(
         Err("Backend sidecar not initialized".to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicUsize};
+    use crate::events::AppEvent;
+    use crate::features::{self, CapabilityCache};
+    use crate::profiles::{BackendReplica, ConnectionProfile};
+    use crate::test_support::{MockResponse, MockServer, RecordingHost};
+
+    async fn health_server(up: Arc<AtomicBool>) -> MockServer {
+        MockServer::start(move |_| {
+            if up.load(Ordering::Relaxed) {
+                MockResponse::json(200, serde_json::json!({"status": "healthy"}))
+            } else {
+                MockResponse::text(503, "unavailable")
+            }
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn probing_fails_over_to_a_healthy_replica_and_back() {
+        let primary_up = Arc::new(AtomicBool::new(false));
+        let primary = health_server(primary_up.clone()).await;
+        let replica = health_server(Arc::new(AtomicBool::new(true))).await;
+
+        let endpoint = BackendEndpoint::default();
+        endpoint
+            .apply_profile(&ConnectionProfile {
+                name: "site".to_string(),
+                backend_url: primary.url.clone(),
+                auth_token: None,
+                accept_invalid_certs: false,
+                default_collection: None,
+                replicas: vec![BackendReplica {
+                    url: replica.url.clone(),
+                    priority: 1,
+                }],
+                sticky: false,
+            })
+            .unwrap();
+
+        let (healthy, changes) = probe_replicas(&endpoint).await;
+        assert!(healthy);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(endpoint.get().as_str(), format!("{}/", replica.url));
+        assert_eq!(replica.requests_to("/api/health").len(), 1);
+
+        primary_up.store(true, Ordering::Relaxed);
+        let (healthy, changes) = probe_replicas(&endpoint).await;
+        assert!(healthy);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(endpoint.get().as_str(), format!("{}/", primary.url));
+    }
+
+    #[tokio::test]
+    async fn a_changed_endpoint_reaches_the_next_probe_and_command() {
+        let old = health_server(Arc::new(AtomicBool::new(true))).await;
+        let new = health_server(Arc::new(AtomicBool::new(true))).await;
+        let endpoint = BackendEndpoint::new(old.url.parse().unwrap());
+        // The health monitor holds its own clone, as it does after start()
+        let monitor = endpoint.clone();
+        let cache = CapabilityCache::default();
+
+        assert!(probe_replicas(&monitor).await.0);
+        features::capabilities(&endpoint, &cache, false).await;
+
+        // What set_backend_url does
+        endpoint.set(new.url.parse().unwrap());
+        assert!(probe_replicas(&monitor).await.0);
+        features::capabilities(&endpoint, &cache, false).await;
+
+        assert_eq!(new.requests_to("/api/health").len(), 1);
+        assert_eq!(new.requests_to("/api/capabilities").len(), 1);
+        assert_eq!(old.requests_to("/api/health").len(), 1);
+        assert_eq!(old.requests_to("/api/capabilities").len(), 1);
+        assert_eq!(monitor.get().as_str(), format!("{}/", new.url));
+    }
+
+    /// Health server that fails until it has answered `failures` requests
+    async fn recovering_server(failures: usize) -> MockServer {
+        let answered = AtomicUsize::new(0);
+        MockServer::start(move |_| {
+            if answered.fetch_add(1, Ordering::Relaxed) < failures {
+                MockResponse::text(503, "unavailable")
+            } else {
+                MockResponse::json(200, serde_json::json!({"status": "healthy"}))
+            }
+        })
+        .await
+    }
+
+    fn unchecked_status() -> Mutex<BackendStatus> {
+        Mutex::new(BackendStatus {
+            running: false,
+            monitoring: true,
+            healthy: false,
+            port: 8000,
+            last_check: String::new(),
+            error: None,
+            profile: None,
+        })
+    }
+
+    /// Run one monitor tick after the wall clock jumped ten minutes past the interval
+    async fn tick_after_clock_jump(server: &MockServer, host: &RecordingHost, status: &Mutex<BackendStatus>) {
+        let endpoint = BackendEndpoint::new(server.url.parse().unwrap());
+        let (instant, wall) = (Instant::now(), SystemTime::now());
+        let suspended = ResumeDetector::new(instant, wall).suspended_for(
+            instant + HEALTH_CHECK_INTERVAL,
+            wall + HEALTH_CHECK_INTERVAL + Duration::from_secs(600),
+        );
+        check_health(host, status, &endpoint, suspended, Duration::from_millis(10)).await;
+    }
+
+    #[tokio::test]
+    async fn a_clock_jump_rechecks_until_the_backend_is_healthy() {
+        let server = recovering_server(2).await;
+        let host = RecordingHost::new(false);
+        let status = unchecked_status();
+
+        tick_after_clock_jump(&server, &host, &status).await;
+
+        assert_eq!(server.requests_to("/api/health").len(), 3);
+        assert!(status.lock().unwrap().healthy);
+        assert_eq!(
+            host.events(ResumeRecovery::NAME),
+            vec![serde_json::json!({
+                "suspended_secs": 600,
+                "connections_reset": true,
+                "health_checks": 3,
+                "healthy": true,
+            })]
+        );
+    }
+
+    #[tokio::test]
+    async fn resume_rechecks_give_up_after_the_limit() {
+        let server = recovering_server(usize::MAX).await;
+        let host = RecordingHost::new(false);
+        let status = unchecked_status();
+
+        tick_after_clock_jump(&server, &host, &status).await;
+
+        assert_eq!(server.requests_to("/api/health").len(), RESUME_RECHECKS as usize);
+        let status = status.lock().unwrap();
+        assert!(!status.healthy);
+        assert!(status.error.is_some());
+        let recovery = host.events(ResumeRecovery::NAME);
+        assert_eq!(recovery.len(), 1);
+        assert_eq!(recovery[0]["health_checks"], RESUME_RECHECKS);
+        assert_eq!(recovery[0]["healthy"], false);
+    }
+
+    #[tokio::test]
+    async fn a_regular_tick_checks_once_without_a_recovery() {
+        let server = recovering_server(1).await;
+        let host = RecordingHost::new(false);
+        let status = unchecked_status();
+        let endpoint = BackendEndpoint::new(server.url.parse().unwrap());
+
+        check_health(&host, &status, &endpoint, None, Duration::from_millis(10)).await;
+
+        assert_eq!(server.requests_to("/api/health").len(), 1);
+        assert!(!status.lock().unwrap().healthy);
+        assert!(host.events(ResumeRecovery::NAME).is_empty());
+    }
+
+    #[test]
+    fn regular_interval_is_not_a_resume() {
+        let (instant, wall) = (Instant::now(), SystemTime::now());
+        let clock = ResumeDetector::new(instant, wall);
+        let step = HEALTH_CHECK_INTERVAL + Duration::from_secs(1);
+
+        assert_eq!(clock.suspended_for(instant + step, wall + step), None);
+    }
+
+    #[test]
+    fn wall_clock_jump_is_a_resume() {
+        let (instant, wall) = (Instant::now(), SystemTime::now());
+        let clock = ResumeDetector::new(instant, wall);
+
+        // The monotonic clock stood still while the machine slept for ten minutes
+        let suspended = clock.suspended_for(
+            instant + HEALTH_CHECK_INTERVAL,
+            wall + HEALTH_CHECK_INTERVAL + Duration::from_secs(600),
+        );
+        assert_eq!(suspended, Some(Duration::from_secs(600)));
+    }
+
+    #[test]
+    fn wall_clock_set_back_is_not_a_resume() {
+        let (instant, wall) = (Instant::now(), SystemTime::now());
+        let clock = ResumeDetector::new(instant, wall);
+
+        let suspended = clock.suspended_for(instant + HEALTH_CHECK_INTERVAL, wall - Duration::from_secs(3600));
+        assert_eq!(suspended, None);
+    }
+}
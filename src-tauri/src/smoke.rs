@@ -0,0 +1,505 @@
+// Pipeline Smoke Test
+// One-button read-only check: backend, Ollama, retrieval and a cited answer
+
+use std::fmt;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use ts_rs::TS;
+
+use crate::endpoint::{read_json_limited, BackendEndpoint, ResponseError, DEFAULT_MAX_RESPONSE_BYTES};
+use crate::events::OperationKind;
+use crate::ollama;
+use crate::operations::{self, OperationHost};
+
+/// Generic question, since the corpus is the user's; asks for the documents by name
+/// so a grounded answer cites at least one of the returned sources
+const SMOKE_QUESTION: &str = "Which documents cover which topics? Name the source documents.";
+
+/// Stage that checks retrieval found sources for the question
+const RETRIEVAL_STAGE: &str = "query";
+
+/// Consecutive words an answer must share with an excerpt to count as quoting it
+const QUOTED_WORDS: usize = 4;
+
+/// Stages of a full pipeline check that are not run. Ingesting a known document
+/// would add it to the user's corpus and reindexing rebuilds the whole vector
+/// store; the backend has no scratch collection to do either safely.
+const NOT_RUN: &[&str] = &["ingest", "reindex"];
+
+const SMOKE_STAGE_COUNT: u64 = 4;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SmokeStage {
+    pub name: String,
+    pub passed: bool,
+    #[ts(type = "number")]
+    pub duration_ms: u64,
+    pub detail: String,
+    /// Why the stage failed, e.g. `ResponseTooLarge`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub error: Option<ResponseError>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SmokeTestReport {
+    pub passed: bool,
+    /// Stages in the order they ran; a failed stage is always the last one
+    pub stages: Vec<SmokeStage>,
+    /// Stages skipped because they would change the user's documents or index
+    pub not_run: Vec<String>,
+    /// Stage that fails when retrieval returns no sources
+    pub retrieval_stage: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct QueryResponse {
+    answer: String,
+    #[serde(default)]
+    sources: Vec<QuerySource>,
+}
+
+#[derive(Debug, Deserialize)]
+struct QuerySource {
+    #[serde(default)]
+    file_name: String,
+    #[serde(default)]
+    excerpt: String,
+}
+
+/// Runs stages in order and stops recording after the first failure
+struct SmokeRun<F> {
+    stages: Vec<SmokeStage>,
+    /// Called with each stage name and the number of stages already finished
+    on_stage: F,
+}
+
+impl<F: FnMut(&str, u64)> SmokeRun<F> {
+    async fn stage<T>(
+        &mut self,
+        name: &str,
+        run: impl Future<Output = Result<(T, String), ResponseError>>,
+    ) -> Option<T> {
+        (self.on_stage)(name, self.stages.len() as u64);
+
+        let started = Instant::now();
+        let outcome = run.await;
+        let duration_ms = started.elapsed().as_millis() as u64;
+
+        let (detail, error, value) = match outcome {
+            Ok((value, detail)) => (detail, None, Some(value)),
+            Err(error) => {
+                log::warn!("Smoke test stage {} failed: {}", name, error);
+                (error.to_string(), Some(error), None)
+            }
+        };
+        self.stages.push(SmokeStage {
+            name: name.to_string(),
+            passed: error.is_none(),
+            duration_ms,
+            detail,
+            error,
+        });
+        value
+    }
+
+    fn finish(self) -> SmokeTestReport {
+        SmokeTestReport {
+            passed: self.stages.iter().all(|s| s.passed),
+            stages: self.stages,
+            not_run: NOT_RUN.iter().map(|s| s.to_string()).collect(),
+            retrieval_stage: RETRIEVAL_STAGE.to_string(),
+        }
+    }
+}
+
+async fn check_backend(endpoint: &BackendEndpoint) -> Result<((), String), ResponseError> {
+    let response = endpoint
+        .request(Method::GET, "/api/health")
+        .timeout(Duration::from_secs(5))
+        .send()
+        .await
+        .map_err(ResponseError::unreachable)?;
+
+    let health: serde_json::Value = read_json_limited(response, DEFAULT_MAX_RESPONSE_BYTES).await?;
+    let rejected = |message: String| ResponseError::Rejected { message };
+    match health["status"].as_str() {
+        Some("healthy") => Ok(((), "Backend is healthy".to_string())),
+        Some(status) => Err(rejected(format!(
+            "Backend is {}: {}",
+            status,
+            health["message"].as_str().unwrap_or("no details")
+        ))),
+        None => Err(rejected("Backend health response has no status".to_string())),
+    }
+}
+
+async fn check_ollama(ollama_url: &str) -> Result<((), String), ResponseError> {
+    let models = ollama::installed_models(ollama_url)
+        .await
+        .map_err(|e| ResponseError::Unreachable {
+            message: format!("Ollama is not running: {}", e),
+        })?;
+
+    if !models.iter().any(|m| m.contains("qwen")) {
+        return Err(ResponseError::Rejected {
+            message: format!("Model {} is not installed", ollama::get_recommended_qwen_model()),
+        });
+    }
+    Ok(((), format!("Ollama running with {} models", models.len())))
+}
+
+/// Ask a question without conversation context, so the user's chat history is untouched
+async fn query(endpoint: &BackendEndpoint) -> Result<(QueryResponse, String), ResponseError> {
+    let response = endpoint
+        .request(Method::POST, "/api/query")
+        .json(&serde_json::json!({
+            "question": SMOKE_QUESTION,
+            "mode": "simple",
+            "use_context": false,
+        }))
+        .timeout(Duration::from_secs(120))
+        .send()
+        .await
+        .map_err(ResponseError::unreachable)?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(ResponseError::Rejected {
+            message: format!("Query failed ({}): {}", status, body),
+        });
+    }
+
+    let answer: QueryResponse = read_json_limited(response, DEFAULT_MAX_RESPONSE_BYTES).await?;
+    if answer.sources.is_empty() {
+        return Err(ResponseError::Invalid {
+            message: "Retrieval returned no sources; are any documents indexed?".to_string(),
+        });
+    }
+    let detail = format!("Retrieval returned {} sources", answer.sources.len());
+    Ok((answer, detail))
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// How the answer refers to one of the sources: a `[n]` marker, the file name, or a quoted excerpt
+fn citation(answer: &str, sources: &[QuerySource]) -> Option<String> {
+    let lowered = answer.to_lowercase();
+    let answer_words = words(answer);
+
+    sources.iter().enumerate().find_map(|(index, source)| {
+        let marker = format!("[{}]", index + 1);
+        if answer.contains(&marker) {
+            return Some(format!("cites {} as {}", source.file_name, marker));
+        }
+
+        let file_name = source.file_name.rsplit(['/', '\\']).next().unwrap_or_default().to_lowercase();
+        let stem = file_name.rsplit_once('.').map_or(file_name.as_str(), |(stem, _)| stem);
+        if !stem.is_empty() && lowered.contains(stem) {
+            return Some(format!("names {}", source.file_name));
+        }
+
+        let excerpt_words = words(&source.excerpt);
+        let quotes = excerpt_words
+            .windows(QUOTED_WORDS)
+            .any(|run| answer_words.windows(QUOTED_WORDS).any(|other| other == run));
+        quotes.then(|| format!("quotes {}", source.file_name))
+    })
+}
+
+fn check_answer(answer: &QueryResponse) -> Result<((), String), ResponseError> {
+    let text = answer.answer.trim();
+    if text.is_empty() {
+        return Err(ResponseError::Invalid {
+            message: "Answer is empty".to_string(),
+        });
+    }
+
+    match citation(text, &answer.sources) {
+        Some(citation) => Ok(((), format!("Answer {}", citation))),
+        None => Err(ResponseError::Invalid {
+            message: format!("Answer does not cite any of the {} returned sources", answer.sources.len()),
+        }),
+    }
+}
+
+/// Run every stage in order, stopping at the first failure. Read-only: nothing is
+/// uploaded, indexed or added to the conversation.
+pub async fn run_smoke_test(
+    endpoint: &BackendEndpoint,
+    ollama_url: &str,
+    on_stage: impl FnMut(&str, u64),
+) -> SmokeTestReport {
+    let mut run = SmokeRun {
+        stages: Vec::new(),
+        on_stage,
+    };
+
+    let completed = async {
+        run.stage("backend_health", check_backend(endpoint)).await?;
+        run.stage("ollama_model", check_ollama(ollama_url)).await?;
+        let answer = run.stage(RETRIEVAL_STAGE, query(endpoint)).await?;
+        run.stage("answer", async { check_answer(&answer) }).await
+    }
+    .await;
+
+    if completed.is_some() {
+        log::info!("Pipeline smoke test passed");
+    }
+    run.finish()
+}
+
+/// A smoke run that did not pass, or was cancelled before it finished
+enum SmokeError {
+    Cancelled(String),
+    Failed(SmokeTestReport),
+}
+
+impl From<String> for SmokeError {
+    fn from(message: String) -> Self {
+        SmokeError::Cancelled(message)
+    }
+}
+
+impl fmt::Display for SmokeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SmokeError::Cancelled(message) => write!(f, "{}", message),
+            SmokeError::Failed(report) => match report.stages.iter().find(|s| !s.passed) {
+                Some(stage) => write!(f, "Stage {} failed: {}", stage.name, stage.detail),
+                None => write!(f, "Smoke test failed"),
+            },
+        }
+    }
+}
+
+/// Run the smoke test as an operation. A failed run still returns its report;
+/// the operation ends as failed.
+async fn smoke_operation(
+    app: &impl OperationHost,
+    endpoint: &BackendEndpoint,
+    ollama_url: &str,
+) -> Result<SmokeTestReport, String> {
+    let operation = operations::begin(app, OperationKind::SmokeTest, "pipeline");
+    let on_stage = |name: &str, done: u64| {
+        operation.progress(app, name, done, Some(SMOKE_STAGE_COUNT), "stages");
+    };
+
+    let result = operation
+        .run(app, async {
+            let report = run_smoke_test(endpoint, ollama_url, on_stage).await;
+            if report.passed {
+                Ok(report)
+            } else {
+                Err(SmokeError::Failed(report))
+            }
+        })
+        .await;
+
+    match result {
+        Ok(report) | Err(SmokeError::Failed(report)) => Ok(report),
+        Err(SmokeError::Cancelled(message)) => Err(message),
+    }
+}
+
+// Tauri Commands
+
+/// Runs as an operation, so it reports on "operation-progress" and can be cancelled
+#[tauri::command]
+pub async fn pipeline_smoke_test(
+    app: AppHandle,
+    endpoint: tauri::State<'_, BackendEndpoint>,
+) -> Result<SmokeTestReport, String> {
+    smoke_operation(&app, &endpoint, ollama::OLLAMA_API_URL).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{AppEvent, OperationProgress};
+    use crate::test_support::{MockRequest, MockResponse, MockServer, RecordingHost};
+    use serde_json::json;
+
+    fn backend_answering(query: serde_json::Value) -> impl Fn(&MockRequest) -> MockResponse {
+        move |request| match request.path.as_str() {
+            "/api/health" => MockResponse::json(200, json!({"status": "healthy"})),
+            "/api/query" => MockResponse::json(200, query.clone()),
+            _ => MockResponse::text(404, "not found"),
+        }
+    }
+
+    fn backend(request: &MockRequest) -> MockResponse {
+        backend_answering(json!({
+            "answer": "AFI36-2903.pdf covers dress and appearance.",
+            "sources": [{"file_name": "AFI36-2903.pdf", "excerpt": "Male Airmen are authorized to wear beards"}],
+        }))(request)
+    }
+
+    fn source(file_name: &str, excerpt: &str) -> QuerySource {
+        QuerySource {
+            file_name: file_name.to_string(),
+            excerpt: excerpt.to_string(),
+        }
+    }
+
+    fn ollama_with(models: &'static [&'static str]) -> impl Fn(&MockRequest) -> MockResponse {
+        move |_| {
+            let models: Vec<_> = models.iter().map(|name| json!({"name": name})).collect();
+            MockResponse::json(200, json!({"models": models}))
+        }
+    }
+
+    async fn run(backend: &MockServer, ollama: &MockServer) -> (SmokeTestReport, Vec<(String, u64)>) {
+        let endpoint = BackendEndpoint::new(backend.url.parse().unwrap());
+        let mut seen = Vec::new();
+        let report = run_smoke_test(&endpoint, &ollama.url, |name, done| {
+            seen.push((name.to_string(), done))
+        })
+        .await;
+        (report, seen)
+    }
+
+    fn stage_names(report: &SmokeTestReport) -> Vec<&str> {
+        report.stages.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn passes_without_touching_documents() {
+        let backend = MockServer::start(backend).await;
+        let ollama = MockServer::start(ollama_with(&["qwen2.5:14b-instruct-q5_K_M"])).await;
+
+        let (report, seen) = run(&backend, &ollama).await;
+
+        assert!(report.passed, "{:?}", report);
+        assert_eq!(stage_names(&report), ["backend_health", "ollama_model", "query", "answer"]);
+        assert_eq!(report.not_run, ["ingest", "reindex"]);
+        assert_eq!(report.retrieval_stage, "query");
+        assert_eq!(report.stages[2].detail, "Retrieval returned 1 sources");
+        assert_eq!(report.stages[3].detail, "Answer names AFI36-2903.pdf");
+        assert_eq!(seen.last(), Some(&("answer".to_string(), 3)));
+        assert!(backend.requests().iter().all(|r| !r.path.starts_with("/api/documents")));
+
+        let queries = backend.requests_to("/api/query");
+        assert_eq!(queries.len(), 1);
+        assert_eq!(queries[0].method, "POST");
+        let body = queries[0].json();
+        assert_eq!(body["question"], SMOKE_QUESTION);
+        assert_eq!(body["use_context"], false);
+    }
+
+    #[tokio::test]
+    async fn stops_at_first_failed_stage() {
+        let backend = MockServer::start(backend).await;
+        let ollama = MockServer::start(ollama_with(&["llama3.1:8b"])).await;
+
+        let (report, _) = run(&backend, &ollama).await;
+
+        assert!(!report.passed);
+        assert_eq!(stage_names(&report), ["backend_health", "ollama_model"]);
+        assert!(!report.stages[1].passed);
+        assert!(backend.requests_to("/api/query").is_empty());
+
+        let error = SmokeError::Failed(report).to_string();
+        assert!(error.starts_with("Stage ollama_model failed"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn a_query_without_sources_fails_the_retrieval_stage() {
+        let backend = MockServer::start(backend_answering(json!({
+            "answer": "The documents cover logistics.",
+            "sources": [],
+        })))
+        .await;
+        let ollama = MockServer::start(ollama_with(&["qwen2.5:14b-instruct-q5_K_M"])).await;
+
+        let (report, _) = run(&backend, &ollama).await;
+
+        assert!(!report.passed);
+        assert_eq!(stage_names(&report), ["backend_health", "ollama_model", "query"]);
+        let stage = &report.stages[2];
+        assert_eq!(stage.name, report.retrieval_stage);
+        assert!(!stage.passed);
+        assert!(stage.detail.contains("no sources"), "{}", stage.detail);
+    }
+
+    #[tokio::test]
+    async fn an_answer_that_cites_no_source_fails() {
+        let backend = MockServer::start(backend_answering(json!({
+            "answer": "I could not find anything relevant.",
+            "sources": [{"file_name": "AFI36-2903.pdf", "excerpt": "Male Airmen are authorized to wear beards"}],
+        })))
+        .await;
+        let ollama = MockServer::start(ollama_with(&["qwen2.5:14b-instruct-q5_K_M"])).await;
+
+        let (report, _) = run(&backend, &ollama).await;
+
+        assert!(!report.passed);
+        assert_eq!(stage_names(&report), ["backend_health", "ollama_model", "query", "answer"]);
+        assert!(report.stages[2].passed);
+        assert!(!report.stages[3].passed);
+        assert_eq!(report.stages[3].detail, "Answer does not cite any of the 1 returned sources");
+    }
+
+    #[test]
+    fn answers_cite_sources_by_marker_name_or_quote() {
+        let sources = [
+            source("docs/AFI36-2903.pdf", "Male Airmen are authorized to wear beards for religious reasons"),
+            source("maintenance.txt", "Torque the wheel nuts to the specified value"),
+        ];
+
+        assert_eq!(citation("See [2] for torque values.", &sources).unwrap(), "cites maintenance.txt as [2]");
+        assert_eq!(citation("Per afi36-2903, beards are allowed.", &sources).unwrap(), "names docs/AFI36-2903.pdf");
+        assert_eq!(
+            citation("You must torque the wheel nuts carefully.", &sources).unwrap(),
+            "quotes maintenance.txt"
+        );
+        assert_eq!(citation("See [3].", &sources), None);
+        assert_eq!(citation("Airmen may wear beards.", &sources), None);
+    }
+
+    #[tokio::test]
+    async fn runs_as_an_operation_that_counts_stages() {
+        let backend = MockServer::start(backend).await;
+        let ollama = MockServer::start(ollama_with(&["qwen2.5:14b-instruct-q5_K_M"])).await;
+        let endpoint = BackendEndpoint::new(backend.url.parse().unwrap());
+        let host = RecordingHost::new(false);
+
+        let report = smoke_operation(&host, &endpoint, &ollama.url).await.unwrap();
+        assert!(report.passed);
+
+        let envelopes = host.events(OperationProgress::NAME);
+        assert!(envelopes.iter().all(|e| e["kind"] == "smoke_test"));
+        let stages: Vec<(&str, u64)> = envelopes[1..envelopes.len() - 1]
+            .iter()
+            .map(|e| (e["phase"].as_str().unwrap(), e["current"].as_u64().unwrap()))
+            .collect();
+        assert_eq!(stages, [("backend_health", 0), ("ollama_model", 1), ("query", 2), ("answer", 3)]);
+        assert!(envelopes[1..].iter().all(|e| e["total"] == SMOKE_STAGE_COUNT && e["unit"] == "stages"));
+        assert_eq!(envelopes.last().unwrap()["status"]["state"], "completed");
+    }
+
+    #[tokio::test]
+    async fn a_failed_stage_fails_the_operation_but_returns_the_report() {
+        let backend = MockServer::start(backend).await;
+        let ollama = MockServer::start(ollama_with(&["llama3.1:8b"])).await;
+        let endpoint = BackendEndpoint::new(backend.url.parse().unwrap());
+        let host = RecordingHost::new(false);
+
+        let report = smoke_operation(&host, &endpoint, &ollama.url).await.unwrap();
+        assert!(!report.passed);
+
+        let last = host.events(OperationProgress::NAME).pop().unwrap();
+        assert_eq!(last["phase"], "ollama_model");
+        assert_eq!(last["status"]["state"], "failed");
+        assert!(last["status"]["error"].as_str().unwrap().starts_with("Stage ollama_model failed"));
+    }
+}
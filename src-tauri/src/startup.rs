@@ -0,0 +1,346 @@
+// Startup Tasks
+// Dependency-ordered app initialization with per-task status
+
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use ts_rs::TS;
+
+type TaskFn<C> = Box<dyn FnOnce(&C) -> Result<(), String> + Send>;
+
+/// One initialization step and the steps it must run after; `C` is what tasks
+/// set up (the app handle outside of tests)
+pub struct StartupTask<C = AppHandle> {
+    name: &'static str,
+    depends_on: &'static [&'static str],
+    /// Skipped in safe mode
+    heavy: bool,
+    run: TaskFn<C>,
+}
+
+impl StartupTask {
+    pub fn new(
+        name: &'static str,
+        depends_on: &'static [&'static str],
+        run: impl FnOnce(&AppHandle) -> Result<(), String> + Send + 'static,
+    ) -> Self {
+        Self {
+            name,
+            depends_on,
+            heavy: false,
+            run: Box::new(run),
+        }
+    }
+}
+
+impl<C> StartupTask<C> {
+    /// Mark the task as skippable: it does not run in safe mode
+    pub fn heavy(mut self) -> Self {
+        self.heavy = true;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TaskStatus {
+    Completed,
+    Failed { error: String },
+    /// Not run because a dependency failed or was skipped
+    Skipped { reason: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct TaskReport {
+    pub name: String,
+    pub status: TaskStatus,
+    #[ts(type = "number")]
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct StartupReport {
+    /// Tasks in the order they finished
+    pub tasks: Vec<TaskReport>,
+    /// Heavy tasks skipped in safe mode, and the tasks that depend on them
+    pub safe_mode_skipped: Vec<String>,
+    #[ts(type = "number")]
+    pub total_ms: u64,
+}
+
+impl StartupReport {
+    fn record(&mut self, name: &str, status: TaskStatus, duration: Duration) {
+        match &status {
+            TaskStatus::Completed => {}
+            TaskStatus::Failed { error } => log::warn!("Startup task {} failed: {}", name, error),
+            TaskStatus::Skipped { reason } => log::warn!("Startup task {} skipped: {}", name, reason),
+        }
+        self.tasks.push(TaskReport {
+            name: name.to_string(),
+            status,
+            duration_ms: duration.as_millis() as u64,
+        });
+    }
+}
+
+/// Run a wave of independent tasks on their own threads; panics count as failures
+fn run_wave<C: Sync>(app: &C, wave: Vec<StartupTask<C>>) -> Vec<(&'static str, Result<(), String>, Duration)> {
+    let started = Instant::now();
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = wave
+            .into_iter()
+            .map(|task| {
+                let handle = scope.spawn(move || {
+                    let started = Instant::now();
+                    let result = (task.run)(app);
+                    (result, started.elapsed())
+                });
+                (task.name, handle)
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|(name, handle)| match handle.join() {
+                Ok((result, duration)) => (name, result, duration),
+                Err(_) => (name, Err("task panicked".to_string()), started.elapsed()),
+            })
+            .collect()
+    })
+}
+
+/// Run tasks as their dependencies complete, running independent tasks concurrently.
+///
+/// A failed task never aborts startup: everything that depends on it is marked
+/// skipped and the rest of the graph still runs. In safe mode heavy tasks are
+/// skipped the same way.
+pub fn run<C: Sync>(app: &C, tasks: Vec<StartupTask<C>>, safe_mode: bool) -> StartupReport {
+    let started = Instant::now();
+    let mut report = StartupReport {
+        tasks: Vec::new(),
+        safe_mode_skipped: Vec::new(),
+        total_ms: 0,
+    };
+    let mut completed: HashSet<&'static str> = HashSet::new();
+    let mut unavailable: HashSet<&'static str> = HashSet::new();
+
+    let (skipped, mut pending): (Vec<_>, Vec<_>) = tasks.into_iter().partition(|task| safe_mode && task.heavy);
+    for task in skipped {
+        let reason = "safe mode".to_string();
+        report.record(task.name, TaskStatus::Skipped { reason }, Duration::ZERO);
+        report.safe_mode_skipped.push(task.name.to_string());
+        unavailable.insert(task.name);
+    }
+
+    while !pending.is_empty() {
+        let mut ready = Vec::new();
+        let mut waiting = Vec::new();
+        let mut blocked = false;
+
+        for task in pending {
+            if let Some(dep) = task.depends_on.iter().find(|dep| unavailable.contains(*dep)) {
+                let reason = format!("dependency {} did not complete", dep);
+                if report.safe_mode_skipped.iter().any(|name| name == dep) {
+                    report.safe_mode_skipped.push(task.name.to_string());
+                }
+                report.record(task.name, TaskStatus::Skipped { reason }, Duration::ZERO);
+                unavailable.insert(task.name);
+                blocked = true;
+            } else if task.depends_on.iter().all(|dep| completed.contains(dep)) {
+                ready.push(task);
+            } else {
+                waiting.push(task);
+            }
+        }
+        pending = waiting;
+
+        if ready.is_empty() {
+            if blocked {
+                continue;
+            }
+            break;
+        }
+
+        for (name, result, duration) in run_wave(app, ready) {
+            match result {
+                Ok(()) => {
+                    completed.insert(name);
+                    report.record(name, TaskStatus::Completed, duration);
+                }
+                Err(error) => {
+                    unavailable.insert(name);
+                    report.record(name, TaskStatus::Failed { error }, duration);
+                }
+            }
+        }
+    }
+
+    // Anything left waits on an unknown task or a cycle
+    for task in pending {
+        let reason = format!("unresolved dependencies {:?}", task.depends_on);
+        report.record(task.name, TaskStatus::Skipped { reason }, Duration::ZERO);
+    }
+
+    report.total_ms = started.elapsed().as_millis() as u64;
+    log::info!("Startup finished in {} ms", report.total_ms);
+    report
+}
+
+// Tauri Commands
+
+#[tauri::command]
+pub fn get_startup_report(app: AppHandle) -> Result<StartupReport, String> {
+    app.try_state::<StartupReport>()
+        .map(|report| report.inner().clone())
+        .ok_or_else(|| "Startup has not finished".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Names of the tasks that ran, in the order they started
+    type Ran = Mutex<Vec<&'static str>>;
+
+    fn step(
+        name: &'static str,
+        depends_on: &'static [&'static str],
+        run: impl FnOnce(&Ran) -> Result<(), String> + Send + 'static,
+    ) -> StartupTask<Ran> {
+        StartupTask {
+            name,
+            depends_on,
+            heavy: false,
+            run: Box::new(run),
+        }
+    }
+
+    fn task(name: &'static str, depends_on: &'static [&'static str]) -> StartupTask<Ran> {
+        step(name, depends_on, move |ran| {
+            ran.lock().unwrap().push(name);
+            Ok(())
+        })
+    }
+
+    fn failing(name: &'static str, depends_on: &'static [&'static str]) -> StartupTask<Ran> {
+        step(name, depends_on, move |_| Err(format!("{} is broken", name)))
+    }
+
+    fn status<'a>(report: &'a StartupReport, name: &str) -> &'a TaskStatus {
+        &report.tasks.iter().find(|t| t.name == name).unwrap().status
+    }
+
+    fn skip_reason(report: &StartupReport, name: &str) -> String {
+        match status(report, name) {
+            TaskStatus::Skipped { reason } => reason.clone(),
+            other => panic!("{} was not skipped: {:?}", name, other),
+        }
+    }
+
+    #[test]
+    fn dependencies_run_first() {
+        let ran = Ran::default();
+        let report = run(
+            &ran,
+            vec![task("endpoint", &["settings", "proxy"]), task("proxy", &["settings"]), task("settings", &[])],
+            false,
+        );
+
+        assert_eq!(*ran.lock().unwrap(), ["settings", "proxy", "endpoint"]);
+        assert!(report.tasks.iter().all(|t| matches!(t.status, TaskStatus::Completed)));
+    }
+
+    #[test]
+    fn a_failing_node_skips_only_what_depends_on_it() {
+        let ran = Ran::default();
+        let report = run(
+            &ran,
+            vec![
+                task("settings", &[]),
+                failing("endpoint", &["settings"]),
+                task("sidecar", &["endpoint"]),
+                task("monitor", &["sidecar"]),
+                task("audit_log", &["settings"]),
+                task("event_registry", &[]),
+            ],
+            false,
+        );
+
+        assert_eq!(report.tasks.len(), 6);
+        match status(&report, "endpoint") {
+            TaskStatus::Failed { error } => assert_eq!(error, "endpoint is broken"),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(skip_reason(&report, "sidecar"), "dependency endpoint did not complete");
+        assert_eq!(skip_reason(&report, "monitor"), "dependency sidecar did not complete");
+        assert!(report.safe_mode_skipped.is_empty());
+
+        let mut ran = ran.into_inner().unwrap();
+        ran.sort();
+        assert_eq!(ran, ["audit_log", "event_registry", "settings"]);
+    }
+
+    #[test]
+    fn a_panicking_task_is_a_failure() {
+        let report = run(
+            &Ran::default(),
+            vec![
+                step("explodes", &[], |_| panic!("boom")),
+                task("after", &["explodes"]),
+            ],
+            false,
+        );
+
+        assert!(matches!(status(&report, "explodes"), TaskStatus::Failed { error } if error == "task panicked"));
+        assert!(matches!(status(&report, "after"), TaskStatus::Skipped { .. }));
+    }
+
+    #[test]
+    fn independent_tasks_run_concurrently() {
+        let slow = |name| {
+            step(name, &[], |_| {
+                std::thread::sleep(Duration::from_millis(150));
+                Ok(())
+            })
+        };
+
+        let started = Instant::now();
+        let report = run(&Ran::default(), vec![slow("a"), slow("b"), slow("c")], false);
+        assert!(started.elapsed() < Duration::from_millis(400), "{:?}", started.elapsed());
+        assert!(report.tasks.iter().all(|t| t.duration_ms >= 150));
+    }
+
+    #[test]
+    fn unknown_dependencies_and_cycles_are_reported_not_run() {
+        let ran = Ran::default();
+        let report = run(
+            &ran,
+            vec![task("a", &["b"]), task("b", &["a"]), task("c", &["missing"]), task("d", &[])],
+            false,
+        );
+
+        assert_eq!(*ran.lock().unwrap(), ["d"]);
+        for name in ["a", "b", "c"] {
+            assert!(skip_reason(&report, name).starts_with("unresolved dependencies"), "{}", name);
+        }
+    }
+
+    #[test]
+    fn safe_mode_skips_heavy_tasks_and_their_dependents() {
+        let tasks = || vec![task("settings", &[]), task("sidecar", &["settings"]).heavy(), task("watch", &["sidecar"])];
+
+        let ran = Ran::default();
+        let report = run(&ran, tasks(), true);
+        assert_eq!(*ran.lock().unwrap(), ["settings"]);
+        assert_eq!(skip_reason(&report, "sidecar"), "safe mode");
+        assert_eq!(skip_reason(&report, "watch"), "dependency sidecar did not complete");
+        assert_eq!(report.safe_mode_skipped, ["sidecar", "watch"]);
+
+        let ran = Ran::default();
+        let report = run(&ran, tasks(), false);
+        assert_eq!(*ran.lock().unwrap(), ["settings", "sidecar", "watch"]);
+        assert!(report.safe_mode_skipped.is_empty());
+    }
+}
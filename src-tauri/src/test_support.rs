@@ -0,0 +1,311 @@
+// Test Support
+// Minimal HTTP/1.1 server for exercising the backend and Ollama clients in tests
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+use crate::events::{AppEvent, EventSink};
+use crate::operations::{OperationHost, Operations};
+use crate::profiles::ProfileHost;
+use crate::settings::SettingsStore;
+
+#[derive(Debug, Clone)]
+pub struct MockRequest {
+    pub method: String,
+    pub path: String,
+    pub body: Vec<u8>,
+}
+
+impl MockRequest {
+    pub fn json(&self) -> serde_json::Value {
+        serde_json::from_slice(&self.body).expect("JSON request body")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MockResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    chunks: Vec<Vec<u8>>,
+    /// Send with chunked transfer encoding instead of a Content-Length
+    chunked: bool,
+    delay: Duration,
+    chunk_delay: Duration,
+    /// Close the connection instead of answering
+    hang_up: bool,
+}
+
+impl MockResponse {
+    pub fn text(status: u16, body: &str) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            chunks: vec![body.as_bytes().to_vec()],
+            chunked: false,
+            delay: Duration::ZERO,
+            chunk_delay: Duration::ZERO,
+            hang_up: false,
+        }
+    }
+
+    pub fn json(status: u16, value: serde_json::Value) -> Self {
+        Self::text(status, &value.to_string()).with_header("content-type", "application/json")
+    }
+
+    /// A 200 response sent as these raw chunks
+    pub fn chunks(chunks: Vec<Vec<u8>>) -> Self {
+        Self {
+            chunks,
+            chunked: true,
+            ..Self::text(200, "")
+        }
+    }
+
+    /// Close the connection without a response, like a backend that went away
+    pub fn hang_up() -> Self {
+        Self {
+            hang_up: true,
+            ..Self::text(200, "")
+        }
+    }
+
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Wait between body chunks, so each arrives in its own read
+    pub fn chunk_delay(mut self, delay: Duration) -> Self {
+        self.chunk_delay = delay;
+        self
+    }
+}
+
+type Handler = Arc<dyn Fn(&MockRequest) -> MockResponse + Send + Sync>;
+
+/// Serves every request with `handler` and records what it received
+pub struct MockServer {
+    pub url: String,
+    requests: Arc<Mutex<Vec<MockRequest>>>,
+    tasks: Arc<Mutex<Vec<JoinHandle<()>>>>,
+}
+
+impl MockServer {
+    pub async fn start(handler: impl Fn(&MockRequest) -> MockResponse + Send + Sync + 'static) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind mock server");
+        let port = listener.local_addr().unwrap().port();
+        let handler: Handler = Arc::new(handler);
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let tasks = Arc::new(Mutex::new(Vec::new()));
+
+        let accept = {
+            let requests = requests.clone();
+            let tasks = tasks.clone();
+            tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    let connection = tokio::spawn(serve(stream, handler.clone(), requests.clone()));
+                    tasks.lock().unwrap().push(connection);
+                }
+            })
+        };
+        tasks.lock().unwrap().push(accept);
+
+        Self {
+            url: format!("http://127.0.0.1:{}", port),
+            requests,
+            tasks,
+        }
+    }
+
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    pub fn requests_to(&self, path: &str) -> Vec<MockRequest> {
+        self.requests().into_iter().filter(|r| r.path == path).collect()
+    }
+
+    /// Close the listener and every open connection
+    pub fn stop(&self) {
+        for task in self.tasks.lock().unwrap().drain(..) {
+            task.abort();
+        }
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+async fn serve(mut stream: TcpStream, handler: Handler, requests: Arc<Mutex<Vec<MockRequest>>>) {
+    let mut buffer = Vec::new();
+    while let Some(request) = read_request(&mut stream, &mut buffer).await {
+        requests.lock().unwrap().push(request.clone());
+        let response = handler(&request);
+        if write_response(&mut stream, &response).await.is_err() {
+            return;
+        }
+    }
+}
+
+async fn read_request(stream: &mut TcpStream, buffer: &mut Vec<u8>) -> Option<MockRequest> {
+    let head_end = loop {
+        if let Some(end) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end;
+        }
+        let mut chunk = [0u8; 8192];
+        let read = stream.read(&mut chunk).await.ok()?;
+        if read == 0 {
+            return None;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..head_end]).into_owned();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next()?.split(' ');
+    let method = request_line.next()?.to_string();
+    let path = request_line.next()?.to_string();
+    let length: usize = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse().ok())
+        .unwrap_or(0);
+
+    let body_start = head_end + 4;
+    while buffer.len() < body_start + length {
+        let mut chunk = [0u8; 8192];
+        let read = stream.read(&mut chunk).await.ok()?;
+        if read == 0 {
+            return None;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+    let body = buffer[body_start..body_start + length].to_vec();
+    buffer.drain(..body_start + length);
+
+    Some(MockRequest {
+        method,
+        path,
+        body,
+    })
+}
+
+async fn write_response(stream: &mut TcpStream, response: &MockResponse) -> std::io::Result<()> {
+    tokio::time::sleep(response.delay).await;
+    if response.hang_up {
+        return Err(std::io::ErrorKind::ConnectionAborted.into());
+    }
+
+    let mut head = format!("HTTP/1.1 {} Mock\r\n", response.status);
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    if response.chunked {
+        head.push_str("transfer-encoding: chunked\r\n\r\n");
+    } else {
+        let length: usize = response.chunks.iter().map(Vec::len).sum();
+        head.push_str(&format!("content-length: {}\r\n\r\n", length));
+    }
+    stream.write_all(head.as_bytes()).await?;
+
+    for (i, chunk) in response.chunks.iter().enumerate() {
+        if i > 0 {
+            tokio::time::sleep(response.chunk_delay).await;
+        }
+        if response.chunked {
+            if chunk.is_empty() {
+                continue;
+            }
+            stream.write_all(format!("{:x}\r\n", chunk.len()).as_bytes()).await?;
+            stream.write_all(chunk).await?;
+            stream.write_all(b"\r\n").await?;
+        } else {
+            stream.write_all(chunk).await?;
+        }
+        stream.flush().await?;
+    }
+    if response.chunked {
+        stream.write_all(b"0\r\n\r\n").await?;
+    }
+    stream.flush().await
+}
+
+/// App stand-in with its own operation registry and settings store that records every event sent through it
+pub struct RecordingHost {
+    pub operations: Operations,
+    events: Mutex<Vec<(&'static str, serde_json::Value)>>,
+    settings: Mutex<serde_json::Map<String, serde_json::Value>>,
+    backend_resets: Mutex<usize>,
+}
+
+impl RecordingHost {
+    pub fn new(legacy_events: bool) -> Self {
+        Self {
+            operations: Operations::new(legacy_events),
+            events: Mutex::new(Vec::new()),
+            settings: Mutex::new(serde_json::Map::new()),
+            backend_resets: Mutex::new(0),
+        }
+    }
+
+    /// Payloads sent as `name`, oldest first
+    pub fn events(&self, name: &str) -> Vec<serde_json::Value> {
+        self.events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(event, _)| *event == name)
+            .map(|(_, payload)| payload.clone())
+            .collect()
+    }
+
+    /// Value persisted under `key`, if any
+    pub fn setting(&self, key: &str) -> Option<serde_json::Value> {
+        self.settings.lock().unwrap().get(key).cloned()
+    }
+
+    /// How many times cached backend health and capabilities were dropped
+    pub fn backend_resets(&self) -> usize {
+        *self.backend_resets.lock().unwrap()
+    }
+}
+
+impl EventSink for RecordingHost {
+    fn emit_event<E: AppEvent>(&self, payload: E) {
+        let payload = serde_json::to_value(&payload).expect("serializable event");
+        self.events.lock().unwrap().push((E::NAME, payload));
+    }
+}
+
+impl OperationHost for RecordingHost {
+    fn operations(&self) -> Option<&Operations> {
+        Some(&self.operations)
+    }
+}
+
+impl SettingsStore for RecordingHost {
+    fn load_value(&self, key: &str) -> Option<serde_json::Value> {
+        self.setting(key)
+    }
+
+    fn save_value(&self, key: &str, value: Option<serde_json::Value>) -> Result<(), String> {
+        let mut settings = self.settings.lock().unwrap();
+        match value {
+            Some(value) => settings.insert(key.to_string(), value),
+            None => settings.remove(key),
+        };
+        Ok(())
+    }
+}
+
+impl ProfileHost for RecordingHost {
+    fn forget_backend_state(&self) {
+        *self.backend_resets.lock().unwrap() += 1;
+    }
+}
@@ -0,0 +1,248 @@
+// Conversation Titles
+// Short conversation names from a cheap local model, with a heuristic fallback
+
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::ollama;
+
+/// Small model used for titles unless the caller picks another
+pub const DEFAULT_TITLE_MODEL: &str = "qwen2.5:3b-instruct-q4_K_M";
+
+const MAX_TITLE_CHARS: usize = 60;
+const TITLE_MAX_TOKENS: u32 = 16;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum TitleSource {
+    Model,
+    Heuristic,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ConversationTitle {
+    pub title: String,
+    pub source: TitleSource,
+}
+
+/// Generate a title for a conversation from its first user message
+pub async fn generate_title(first_message: &str, model_name: &str) -> ConversationTitle {
+    generate_title_at(ollama::OLLAMA_API_URL, first_message, model_name).await
+}
+
+async fn generate_title_at(base_url: &str, first_message: &str, model_name: &str) -> ConversationTitle {
+    let prompt = format!(
+        "Write a title of at most six words for a conversation that starts with the \
+         message below. Reply with the title only.\n\nMessage: {}",
+        first_message.trim()
+    );
+
+    let timeout = Duration::from_secs(20);
+    match ollama::generate_text_at(base_url, model_name, &prompt, TITLE_MAX_TOKENS, timeout).await {
+        Ok(raw) => match sanitize_title(&raw) {
+            Some(title) => {
+                return ConversationTitle {
+                    title,
+                    source: TitleSource::Model,
+                }
+            }
+            None => log::warn!("Title model returned nothing usable: {:?}", raw),
+        },
+        Err(e) => log::warn!("Falling back to heuristic title: {}", e),
+    }
+
+    ConversationTitle {
+        title: heuristic_title(first_message),
+        source: TitleSource::Heuristic,
+    }
+}
+
+/// Clean up raw model output into a single-line title, or None if nothing is left
+pub fn sanitize_title(raw: &str) -> Option<String> {
+    let without_think = strip_think_blocks(raw);
+
+    let line = without_think
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())?;
+
+    let line = line.trim_start_matches(['#', '*', '-', '>', ' ']);
+    let line = match line.split_once(':') {
+        Some((label, rest)) if label.trim().eq_ignore_ascii_case("title") => rest,
+        _ => line,
+    };
+
+    let cleaned: String = line
+        .chars()
+        .filter(|c| !matches!(c, '*' | '_' | '`' | '"' | '\u{201c}' | '\u{201d}'))
+        .collect();
+    let cleaned = cleaned
+        .trim()
+        .trim_matches(['\'', '\u{2018}', '\u{2019}'])
+        .trim_end_matches(['.', ':', ';', ','])
+        .trim();
+
+    let title = truncate_words(&collapse_whitespace(cleaned), MAX_TITLE_CHARS);
+    if title.is_empty() {
+        None
+    } else {
+        Some(title)
+    }
+}
+
+/// Title from the first sentence of the message
+pub fn heuristic_title(first_message: &str) -> String {
+    let collapsed = collapse_whitespace(first_message);
+    let sentence = collapsed
+        .split_inclusive(['.', '?', '!'])
+        .next()
+        .unwrap_or("")
+        .trim_end_matches('.')
+        .trim();
+
+    let title = truncate_words(sentence, MAX_TITLE_CHARS);
+    if title.is_empty() {
+        "New conversation".to_string()
+    } else {
+        title
+    }
+}
+
+pub fn strip_think_blocks(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find("<think>") {
+        result.push_str(&rest[..start]);
+        match rest[start..].find("</think>") {
+            Some(end) => rest = &rest[start + end + "</think>".len()..],
+            None => return result,
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+pub fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Cap at `max_chars`, cutting at a word boundary when possible
+fn truncate_words(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+
+    let cut: String = text.chars().take(max_chars).collect();
+    match cut.rfind(' ') {
+        Some(space) if space > 0 => format!("{}…", cut[..space].trim_end()),
+        _ => format!("{}…", cut),
+    }
+}
+
+// Tauri Commands
+
+#[tauri::command]
+pub async fn generate_conversation_title(
+    first_message: String,
+    model: Option<String>,
+) -> Result<ConversationTitle, String> {
+    if first_message.trim().is_empty() {
+        return Err("Cannot generate a title for an empty message".to_string());
+    }
+
+    let model = model.unwrap_or_else(|| DEFAULT_TITLE_MODEL.to_string());
+    Ok(generate_title(&first_message, &model).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{MockResponse, MockServer};
+
+    const MESSAGE: &str = "What is the checklist for night patrol? We leave at 2200.";
+
+    async fn ollama(response: &'static str) -> MockServer {
+        MockServer::start(move |_| MockResponse::json(200, serde_json::json!({"response": response, "done": true}))).await
+    }
+
+    #[tokio::test]
+    async fn messy_model_output_becomes_a_clean_title() {
+        let server = ollama("<think>Patrol, night, checklist</think>\n\n**Title:** \"Night Patrol Checklist.\"\nHope this helps!").await;
+
+        let title = generate_title_at(&server.url, MESSAGE, DEFAULT_TITLE_MODEL).await;
+        assert_eq!(title.title, "Night Patrol Checklist");
+        assert!(matches!(title.source, TitleSource::Model));
+
+        let request = server.requests_to("/api/generate")[0].json();
+        assert_eq!(request["model"], DEFAULT_TITLE_MODEL);
+        assert_eq!(request["options"]["num_predict"], TITLE_MAX_TOKENS);
+        assert!(request["prompt"].as_str().unwrap().ends_with(MESSAGE));
+    }
+
+    #[tokio::test]
+    async fn unusable_or_failed_generations_fall_back_to_the_heuristic() {
+        let heuristic = heuristic_title(MESSAGE);
+        assert_eq!(heuristic, "What is the checklist for night patrol?");
+
+        let empty = ollama("<think>no idea").await;
+        let failing = MockServer::start(|_| MockResponse::text(500, "model runner crashed")).await;
+        let unreachable = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}", listener.local_addr().unwrap())
+        };
+
+        for url in [&empty.url, &failing.url, &unreachable] {
+            let title = generate_title_at(url, MESSAGE, DEFAULT_TITLE_MODEL).await;
+            assert_eq!(title.title, heuristic, "{}", url);
+            assert!(matches!(title.source, TitleSource::Heuristic), "{}", url);
+        }
+        assert_eq!(failing.requests_to("/api/generate").len(), 1);
+    }
+
+    #[test]
+    fn model_output_is_reduced_to_one_clean_line() {
+        assert_eq!(sanitize_title("\n  \"Night Patrol Checklist.\"\n").as_deref(), Some("Night Patrol Checklist"));
+        assert_eq!(sanitize_title("## **Title:** `Radio   Procedures`").as_deref(), Some("Radio Procedures"));
+        assert_eq!(sanitize_title("\u{201c}Convoy Briefing\u{201d}\nSecond line").as_deref(), Some("Convoy Briefing"));
+        assert_eq!(sanitize_title("'Map Reading'").as_deref(), Some("Map Reading"));
+    }
+
+    #[test]
+    fn think_blocks_are_dropped() {
+        assert_eq!(
+            sanitize_title("<think>The user wants\na title</think>\nWeapons Maintenance").as_deref(),
+            Some("Weapons Maintenance")
+        );
+        // An unterminated block means the model never got to the title
+        assert_eq!(sanitize_title("<think>still thinking"), None);
+    }
+
+    #[test]
+    fn empty_output_has_no_title() {
+        assert_eq!(sanitize_title(""), None);
+        assert_eq!(sanitize_title("  \n\t"), None);
+        assert_eq!(sanitize_title("Title: \"\""), None);
+        assert_eq!(sanitize_title("***"), None);
+    }
+
+    #[test]
+    fn long_titles_are_cut_at_a_word_boundary() {
+        let raw = "word ".repeat(30);
+        let title = sanitize_title(&raw).unwrap();
+        assert!(title.ends_with("word…"), "{}", title);
+        assert!(title.chars().count() <= MAX_TITLE_CHARS + 1);
+
+        let unbroken = "x".repeat(80);
+        assert_eq!(sanitize_title(&unbroken).unwrap(), format!("{}…", "x".repeat(MAX_TITLE_CHARS)));
+    }
+
+    #[test]
+    fn heuristic_uses_the_first_sentence() {
+        assert_eq!(heuristic_title("How do I zero a rifle? It keeps drifting."), "How do I zero a rifle?");
+        assert_eq!(heuristic_title("Summarize   the\nreport. Thanks"), "Summarize the report");
+        assert_eq!(heuristic_title("   "), "New conversation");
+    }
+}